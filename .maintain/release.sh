@@ -8,6 +8,8 @@ cargo publish --locked -p subspector
 # substorager depends on subhasher, sleep for 30s
 sleep 30
 cargo publish --locked -p substorager
+cargo publish --locked -p subhasher-cli
+cargo publish --locked -p subhasher-ffi
 # submetadatan depends on substorager, sleep for 30s
 cargo publish --locked -p submetadatan
 cargo publish --locked -p subversioner
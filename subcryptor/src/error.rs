@@ -15,6 +15,8 @@ pub enum Error {
 	CryptoSecretBox(#[from] CryptoSecretBox),
 	#[error("[subcryptor] from base58 error, {0:?}")]
 	FromBase58(base58::FromBase58Error),
+	#[error("[subcryptor] invalid ss58 checksum, {0:?}")]
+	InvalidChecksum(String),
 	#[error("[subcryptor] invalid prefix, {0:?}")]
 	InvalidPrefix(u8),
 	#[error("[subcryptor] invalid ss58 address, {0:?}")]
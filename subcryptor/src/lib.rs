@@ -76,16 +76,7 @@ pub fn ss58_address_of(public_key: &[u8], network: &str) -> Result<(u16, String)
 	};
 
 	bytes.extend(public_key);
-
-	let blake2b = {
-		let mut context = Blake2b::new(64);
-		context.update(b"SS58PRE");
-		context.update(&bytes);
-
-		context.finalize()
-	};
-
-	bytes.extend(&blake2b.as_bytes()[0..2]);
+	bytes.extend(checksum(&bytes));
 
 	Ok((prefix, bytes.to_base58()))
 }
@@ -109,9 +100,28 @@ where
 		prefix => Err(Error::InvalidPrefix(prefix))?,
 	};
 
-	if bytes.len() < (K::LEN + prefix_len - 1) {
+	// The prefix, the public key and the 2 bytes checksum.
+	if bytes.len() != prefix_len + K::LEN + 2 {
 		Err(Error::InvalidSs58Address(ss58_address.into()))?;
 	}
 
-	Ok(bytes[prefix_len..K::LEN + prefix_len].to_vec())
+	let (payload, checksum_bytes) = bytes.split_at(prefix_len + K::LEN);
+
+	if checksum(payload) != checksum_bytes {
+		Err(Error::InvalidChecksum(ss58_address.into()))?;
+	}
+
+	Ok(payload[prefix_len..].to_vec())
+}
+
+// Get the SS58 checksum of the prefix and the public key.
+fn checksum(payload: &[u8]) -> [u8; 2] {
+	let mut context = Blake2b::new(64);
+
+	context.update(b"SS58PRE");
+	context.update(payload);
+
+	let hash = context.finalize();
+
+	[hash.as_bytes()[0], hash.as_bytes()[1]]
 }
@@ -51,4 +51,17 @@ fn public_key_of_should_fail() {
 		public_key_of::<Sr25519>("15").unwrap_err().to_string(),
 		"[subcryptor] invalid ss58 address, \"15\""
 	);
+	// Exactly 32 bytes, without the checksum.
+	assert_eq!(
+		public_key_of::<Sr25519>("4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi")
+			.unwrap_err()
+			.to_string(),
+		"[subcryptor] invalid ss58 address, \"4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi\""
+	);
+	assert_eq!(
+		public_key_of::<Sr25519>("156HGo9setPcU2qhFMVWLkcmtCEGySLwNqa3DaEiYSWtte4Z")
+			.unwrap_err()
+			.to_string(),
+		"[subcryptor] invalid ss58 checksum, \"156HGo9setPcU2qhFMVWLkcmtCEGySLwNqa3DaEiYSWtte4Z\""
+	);
 }
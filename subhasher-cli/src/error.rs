@@ -0,0 +1,20 @@
+//! Subhasher CLI error collections.
+
+// std
+use std::io::Error as IoError;
+// crates.io
+use thiserror::Error as ThisError;
+// substrate-minimal
+use crate::Algorithm;
+
+/// Main error.
+#[allow(missing_docs)]
+#[derive(Debug, ThisError)]
+pub enum Error {
+	#[error("{0:?}")]
+	ArrayBytes(array_bytes::Error),
+	#[error(transparent)]
+	Io(#[from] IoError),
+	#[error("`{0}` has no concat variant")]
+	NoConcatVariant(Algorithm),
+}
@@ -0,0 +1,193 @@
+//! Command-line interface of the minimal implementation of Substrate hash.
+
+#![deny(missing_docs)]
+#![deny(unused_crate_dependencies)]
+
+#[cfg(test)] mod test;
+
+mod error;
+use error::Error;
+
+// std
+use std::{
+	fmt::{Display, Formatter, Result as FmtResult},
+	fs,
+	io::{self, Read, Write},
+	path::PathBuf,
+	process::ExitCode,
+};
+// crates.io
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Hash the data or calculate the storage key.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+	#[command(subcommand)]
+	command: Command,
+	/// Print the raw bytes instead of the hex.
+	#[arg(long, global = true)]
+	raw: bool,
+}
+impl Cli {
+	fn run<R>(self, stdin: R) -> Result<Vec<u8>>
+	where
+		R: Read,
+	{
+		let output = match self.command {
+			Command::Hash(c) => c.run(stdin)?,
+			Command::StorageKey { pallet, item } => substorager::storage_value_key(pallet, item).0,
+		};
+
+		Ok(if self.raw {
+			output
+		} else {
+			format!("{}\n", array_bytes::bytes2hex("0x", output)).into_bytes()
+		})
+	}
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+	/// Hash the data with the algorithm.
+	///
+	/// Read the data from the stdin if no input is given.
+	Hash(HashCmd),
+	/// Calculate the storage key of a pallet `StorageValue` item.
+	StorageKey {
+		/// Pallet name.
+		pallet: String,
+		/// Storage item name.
+		item: String,
+	},
+}
+
+#[derive(Debug, Args)]
+struct HashCmd {
+	/// Hash algorithm.
+	#[arg(value_enum)]
+	algorithm: Algorithm,
+	/// Use the concat variant of the algorithm, `blake2-128` or `twox64` only.
+	#[arg(long)]
+	concat: bool,
+	/// UTF-8 string input.
+	#[arg(group = "input")]
+	data: Option<String>,
+	/// Hex input, with or without the `0x` prefix.
+	#[arg(long, value_name = "HEX", group = "input")]
+	hex: Option<String>,
+	/// File input.
+	#[arg(long, value_name = "PATH", group = "input")]
+	file: Option<PathBuf>,
+}
+impl HashCmd {
+	fn run<R>(self, mut stdin: R) -> Result<Vec<u8>>
+	where
+		R: Read,
+	{
+		let algorithm = if self.concat { self.algorithm.concat()? } else { self.algorithm };
+		let data = if let Some(d) = self.data {
+			d.into_bytes()
+		} else if let Some(h) = self.hex {
+			array_bytes::hex2bytes(h).map_err(Error::ArrayBytes)?
+		} else if let Some(p) = self.file {
+			fs::read(p)?
+		} else {
+			let mut d = Vec::new();
+
+			stdin.read_to_end(&mut d)?;
+
+			d
+		};
+
+		Ok(algorithm.hash(&data))
+	}
+}
+
+/// Hash algorithm.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Algorithm {
+	#[value(name = "blake2-128")]
+	Blake2_128,
+	#[value(name = "blake2-256")]
+	Blake2_256,
+	#[value(name = "blake2-512")]
+	Blake2_512,
+	#[value(name = "blake2-128-concat")]
+	Blake2_128Concat,
+	#[value(name = "twox64")]
+	Twox64,
+	#[value(name = "twox128")]
+	Twox128,
+	#[value(name = "twox256")]
+	Twox256,
+	#[value(name = "twox64-concat")]
+	Twox64Concat,
+	#[value(name = "identity")]
+	Identity,
+	#[value(name = "keccak256")]
+	Keccak256,
+	#[value(name = "keccak512")]
+	Keccak512,
+	#[value(name = "sha2-256")]
+	Sha2_256,
+	#[value(name = "sha2-512")]
+	Sha2_512,
+	#[value(name = "sha3-256")]
+	Sha3_256,
+	#[value(name = "ripemd160")]
+	Ripemd160,
+}
+impl Algorithm {
+	fn concat(self) -> Result<Self> {
+		match self {
+			Self::Blake2_128 | Self::Blake2_128Concat => Ok(Self::Blake2_128Concat),
+			Self::Twox64 | Self::Twox64Concat => Ok(Self::Twox64Concat),
+			_ => Err(Error::NoConcatVariant(self)),
+		}
+	}
+
+	fn hash(self, data: &[u8]) -> Vec<u8> {
+		match self {
+			Self::Blake2_128 => subhasher::blake2_128(data).to_vec(),
+			Self::Blake2_256 => subhasher::blake2_256(data).to_vec(),
+			Self::Blake2_512 => subhasher::blake2_512(data).to_vec(),
+			Self::Blake2_128Concat => subhasher::blake2_128_concat(data),
+			Self::Twox64 => subhasher::twox64(data).to_vec(),
+			Self::Twox128 => subhasher::twox128(data).to_vec(),
+			Self::Twox256 => subhasher::twox256(data).to_vec(),
+			Self::Twox64Concat => subhasher::twox64_concat(data),
+			Self::Identity => subhasher::identity(data).to_vec(),
+			Self::Keccak256 => subhasher::keccak256(data).to_vec(),
+			Self::Keccak512 => subhasher::keccak512(data).to_vec(),
+			Self::Sha2_256 => subhasher::sha2_256(data).to_vec(),
+			Self::Sha2_512 => subhasher::sha2_512(data).to_vec(),
+			Self::Sha3_256 => subhasher::sha3_256(data).to_vec(),
+			Self::Ripemd160 => subhasher::ripemd160(data).to_vec(),
+		}
+	}
+}
+
+impl Display for Algorithm {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		// There is no skipped variant, so the value always exists.
+		self.to_possible_value().map_or(Ok(()), |v| f.write_str(v.get_name()))
+	}
+}
+
+fn main() -> ExitCode {
+	let result = Cli::parse()
+		.run(io::stdin().lock())
+		.and_then(|o| io::stdout().lock().write_all(&o).map_err(Into::into));
+
+	if let Err(e) = result {
+		eprintln!("error: {e}");
+
+		return ExitCode::FAILURE;
+	}
+
+	ExitCode::SUCCESS
+}
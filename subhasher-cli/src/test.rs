@@ -0,0 +1,75 @@
+// crates.io
+use clap::Parser;
+// substrate-minimal
+use crate::*;
+
+fn run<const N: usize>(args: [&str; N], stdin: &[u8]) -> Result<Vec<u8>> {
+	Cli::try_parse_from(["subhasher"].into_iter().chain(args)).unwrap().run(stdin)
+}
+
+fn hex<B>(bytes: B) -> Vec<u8>
+where
+	B: AsRef<[u8]>,
+{
+	format!("{}\n", array_bytes::bytes2hex("0x", bytes)).into_bytes()
+}
+
+#[test]
+fn hash_should_work() {
+	assert_eq!(run(["hash", "twox128", "System"], &[]).unwrap(), hex(subhasher::twox128("System")));
+	assert_eq!(
+		run(["hash", "blake2-256", "--hex", "0x0102"], &[]).unwrap(),
+		hex(subhasher::blake2_256([1, 2]))
+	);
+	assert_eq!(
+		run(["hash", "sha3-256", "--hex", "0102"], &[]).unwrap(),
+		hex(subhasher::sha3_256([1, 2]))
+	);
+	assert_eq!(
+		run(["hash", "keccak256"], b"from stdin").unwrap(),
+		hex(subhasher::keccak256("from stdin"))
+	);
+	assert_eq!(
+		run(["hash", "blake2-512", "--file", "Cargo.toml"], &[]).unwrap(),
+		hex(subhasher::blake2_512(fs::read("Cargo.toml").unwrap()))
+	);
+	assert_eq!(run(["hash", "identity", "--raw", "raw"], &[]).unwrap(), b"raw");
+	assert_eq!(run(["--raw", "hash", "ripemd160", ""], &[]).unwrap(), subhasher::ripemd160([]));
+}
+
+#[test]
+fn hash_concat_should_work() {
+	assert_eq!(
+		run(["hash", "blake2-128", "--concat", "Alice"], &[]).unwrap(),
+		hex(subhasher::blake2_128_concat("Alice"))
+	);
+	assert_eq!(
+		run(["hash", "twox64", "--concat", "Alice"], &[]).unwrap(),
+		run(["hash", "twox64-concat", "Alice"], &[]).unwrap()
+	);
+	assert!(matches!(
+		run(["hash", "sha2-256", "--concat", "Alice"], &[]),
+		Err(Error::NoConcatVariant(Algorithm::Sha2_256))
+	));
+}
+
+#[test]
+fn invalid_input_should_fail() {
+	assert!(matches!(run(["hash", "twox64", "--hex", "0xzz"], &[]), Err(Error::ArrayBytes(_))));
+	assert!(matches!(run(["hash", "twox64", "--file", "/nonexistent"], &[]), Err(Error::Io(_))));
+	// Only one input is allowed.
+	assert!(Cli::try_parse_from(["subhasher", "hash", "twox64", "a", "--hex", "0x00"]).is_err());
+	assert!(Cli::try_parse_from(["subhasher", "hash", "unknown"]).is_err());
+}
+
+#[test]
+fn storage_key_should_work() {
+	assert_eq!(
+		run(["storage-key", "System", "Number"], &[]).unwrap(),
+		b"0x26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac\n"
+	);
+	assert_eq!(
+		run(["storage-key", "--raw", "System", "Number"], &[]).unwrap(),
+		substorager::storage_value_key("System", "Number").0
+	);
+}
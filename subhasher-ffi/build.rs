@@ -0,0 +1,18 @@
+//! Expose the directory of the final artifacts to the tests.
+
+// std
+use std::{env, path::Path};
+
+fn main() {
+	let out_dir = env::var("OUT_DIR").unwrap();
+	// `OUT_DIR` is under `<profile dir>/build`, Cargo places the final artifacts in the profile
+	// directory.
+	let profile_dir = Path::new(&out_dir)
+		.ancestors()
+		.find(|d| d.file_name().is_some_and(|n| n == "build"))
+		.and_then(Path::parent)
+		.expect("`OUT_DIR` must be under the build directory; qed");
+
+	println!("cargo:rustc-env=SUBHASHER_FFI_LIB_DIR={}", profile_dir.display());
+	println!("cargo:rerun-if-changed=build.rs");
+}
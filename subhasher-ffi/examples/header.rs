@@ -0,0 +1,13 @@
+//! Regenerate the C header, run `cargo run -p subhasher-ffi --example header`.
+
+// std
+use std::path::Path;
+
+fn main() {
+	let crate_dir = env!("CARGO_MANIFEST_DIR");
+	let config = cbindgen::Config::from_file(Path::new(crate_dir).join("cbindgen.toml")).unwrap();
+
+	cbindgen::generate_with_config(crate_dir, config)
+		.unwrap()
+		.write_to_file(Path::new(crate_dir).join("include/subhasher.h"));
+}
@@ -0,0 +1,241 @@
+/*
+ * C ABI of the minimal implementation of Substrate hash.
+ *
+ * Every function writes its output into the buffer provided by the caller.
+ * The length of the output is always reported through `written`, if it is not `NULL`.
+ * Pass a `NULL` output buffer with zero length to query the required length.
+ *
+ * Safety (the "crate level" requirements):
+ * - `data` must be valid for reads of `data_len` bytes. It could be `NULL` only if `data_len` is zero.
+ * - `out` must be valid for writes of `out_len` bytes and must not overlap `data`.
+ * - `written` must be `NULL` or valid for a write of `size_t`.
+ */
+
+#ifndef SUBHASHER_H
+#define SUBHASHER_H
+
+/* Generated by cbindgen, do not edit it manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Status code of the functions.
+ */
+typedef enum SubhasherStatus {
+  /**
+   * Success.
+   */
+  SUBHASHER_STATUS_OK = 0,
+  /**
+   * A required pointer is `NULL`.
+   */
+  SUBHASHER_STATUS_NULL_POINTER = 1,
+  /**
+   * The output buffer is too small to hold the output.
+   */
+  SUBHASHER_STATUS_BUFFER_TOO_SMALL = 2,
+  /**
+   * The input is malformed.
+   */
+  SUBHASHER_STATUS_INVALID_INPUT = 3,
+} SubhasherStatus;
+
+/**
+ * Hash the data into a 16-bytes array with BLAKE2 algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_blake2_128(const uint8_t *data,
+                                          size_t data_len,
+                                          uint8_t *out,
+                                          size_t out_len,
+                                          size_t *written);
+
+/**
+ * Hash the data into a 32-bytes array with BLAKE2 algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_blake2_256(const uint8_t *data,
+                                          size_t data_len,
+                                          uint8_t *out,
+                                          size_t out_len,
+                                          size_t *written);
+
+/**
+ * Hash the data into a 64-bytes array with BLAKE2 algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_blake2_512(const uint8_t *data,
+                                          size_t data_len,
+                                          uint8_t *out,
+                                          size_t out_len,
+                                          size_t *written);
+
+/**
+ * Hash the data into `blake2_128(data) + data`.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_blake2_128_concat(const uint8_t *data,
+                                                 size_t data_len,
+                                                 uint8_t *out,
+                                                 size_t out_len,
+                                                 size_t *written);
+
+/**
+ * Hash the data into a 8-bytes array with XX algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_twox64(const uint8_t *data,
+                                      size_t data_len,
+                                      uint8_t *out,
+                                      size_t out_len,
+                                      size_t *written);
+
+/**
+ * Hash the data into a 16-bytes array with XX algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_twox128(const uint8_t *data,
+                                       size_t data_len,
+                                       uint8_t *out,
+                                       size_t out_len,
+                                       size_t *written);
+
+/**
+ * Hash the data into a 32-bytes array with XX algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_twox256(const uint8_t *data,
+                                       size_t data_len,
+                                       uint8_t *out,
+                                       size_t out_len,
+                                       size_t *written);
+
+/**
+ * Hash the data into `twox_64(data) + data`.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_twox64_concat(const uint8_t *data,
+                                             size_t data_len,
+                                             uint8_t *out,
+                                             size_t out_len,
+                                             size_t *written);
+
+/**
+ * Copy the data directly.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_identity(const uint8_t *data,
+                                        size_t data_len,
+                                        uint8_t *out,
+                                        size_t out_len,
+                                        size_t *written);
+
+/**
+ * Hash the data into a 32-bytes array with Keccak algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_keccak256(const uint8_t *data,
+                                         size_t data_len,
+                                         uint8_t *out,
+                                         size_t out_len,
+                                         size_t *written);
+
+/**
+ * Hash the data into a 64-bytes array with Keccak algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_keccak512(const uint8_t *data,
+                                         size_t data_len,
+                                         uint8_t *out,
+                                         size_t out_len,
+                                         size_t *written);
+
+/**
+ * Hash the data into a 32-bytes array with SHA2 algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_sha2_256(const uint8_t *data,
+                                        size_t data_len,
+                                        uint8_t *out,
+                                        size_t out_len,
+                                        size_t *written);
+
+/**
+ * Hash the data into a 64-bytes array with SHA2 algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_sha2_512(const uint8_t *data,
+                                        size_t data_len,
+                                        uint8_t *out,
+                                        size_t out_len,
+                                        size_t *written);
+
+/**
+ * Hash the data into a 32-bytes array with SHA3 algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_sha3_256(const uint8_t *data,
+                                        size_t data_len,
+                                        uint8_t *out,
+                                        size_t out_len,
+                                        size_t *written);
+
+/**
+ * Hash the data into a 20-bytes array with RIPEMD-160 algorithm.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_ripemd160(const uint8_t *data,
+                                         size_t data_len,
+                                         uint8_t *out,
+                                         size_t out_len,
+                                         size_t *written);
+
+/**
+ * Run the BLAKE2 `F` compression function on the EIP-152 encoded input.
+ *
+ * Return [`SubhasherStatus::InvalidInput`] if the input is malformed.
+ *
+ * # Safety
+ * Check the [crate level](crate#safety) requirements.
+ */
+enum SubhasherStatus subhasher_blake2f(const uint8_t *data,
+                                       size_t data_len,
+                                       uint8_t *out,
+                                       size_t out_len,
+                                       size_t *written);
+
+#endif  /* SUBHASHER_H */
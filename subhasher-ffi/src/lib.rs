@@ -0,0 +1,344 @@
+//! C ABI of the minimal implementation of Substrate hash.
+//!
+//! Every function has the same shape:
+//! ```c
+//! SubhasherStatus subhasher_xxx(const uint8_t *data, size_t data_len,
+//!                               uint8_t *out, size_t out_len, size_t *written);
+//! ```
+//! The output is written into the buffer provided by the caller.
+//! The length of the output is always reported through `written`, if it is not `NULL`.
+//! So, a caller could query the required length by passing a `NULL` output buffer with zero
+//! length, which returns [`SubhasherStatus::BufferTooSmall`].
+//!
+//! The C header is at `include/subhasher.h`.
+//!
+//! # Safety
+//! - `data` must be valid for reads of `data_len` bytes. It could be `NULL` only if `data_len` is
+//!   zero.
+//! - `out` must be valid for writes of `out_len` bytes and must not overlap `data`.
+//! - `written` must be `NULL` or valid for a write of `size_t`.
+
+#![deny(missing_docs)]
+#![deny(unused_crate_dependencies)]
+
+#[cfg(test)] use cbindgen as _;
+
+// core
+use core::{ptr, slice};
+// substrate-minimal
+use subhasher::*;
+
+/// Status code of the functions.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubhasherStatus {
+	/// Success.
+	Ok = 0,
+	/// A required pointer is `NULL`.
+	NullPointer = 1,
+	/// The output buffer is too small to hold the output.
+	BufferTooSmall = 2,
+	/// The input is malformed.
+	InvalidInput = 3,
+}
+
+unsafe fn write<H>(
+	hasher: H,
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus
+where
+	H: Hasher,
+{
+	let Some(data) = read(data, data_len) else {
+		return SubhasherStatus::NullPointer;
+	};
+
+	write_output(hasher.hash(data).as_ref(), out, out_len, written)
+}
+
+unsafe fn read<'a>(data: *const u8, data_len: usize) -> Option<&'a [u8]> {
+	if data_len == 0 {
+		Some(&[])
+	} else if data.is_null() {
+		None
+	} else {
+		Some(slice::from_raw_parts(data, data_len))
+	}
+}
+
+unsafe fn write_output(
+	output: &[u8],
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	if !written.is_null() {
+		*written = output.len();
+	}
+	if output.len() > out_len {
+		return SubhasherStatus::BufferTooSmall;
+	}
+	if output.is_empty() {
+		return SubhasherStatus::Ok;
+	}
+	if out.is_null() {
+		return SubhasherStatus::NullPointer;
+	}
+
+	ptr::copy_nonoverlapping(output.as_ptr(), out, output.len());
+
+	SubhasherStatus::Ok
+}
+
+/// Hash the data into a 16-bytes array with BLAKE2 algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_blake2_128(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Blake2_128, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into a 32-bytes array with BLAKE2 algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_blake2_256(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Blake2_256, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into a 64-bytes array with BLAKE2 algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_blake2_512(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Blake2_512, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into `blake2_128(data) + data`.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_blake2_128_concat(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Blake2_128Concat, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into a 8-bytes array with XX algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_twox64(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Twox64, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into a 16-bytes array with XX algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_twox128(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Twox128, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into a 32-bytes array with XX algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_twox256(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Twox256, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into `twox_64(data) + data`.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_twox64_concat(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Twox64Concat, data, data_len, out, out_len, written)
+}
+
+/// Copy the data directly.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_identity(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Identity, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into a 32-bytes array with Keccak algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_keccak256(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Keccak256, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into a 64-bytes array with Keccak algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_keccak512(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Keccak512, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into a 32-bytes array with SHA2 algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_sha2_256(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Sha2_256, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into a 64-bytes array with SHA2 algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_sha2_512(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Sha2_512, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into a 32-bytes array with SHA3 algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_sha3_256(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Sha3_256, data, data_len, out, out_len, written)
+}
+
+/// Hash the data into a 20-bytes array with RIPEMD-160 algorithm.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_ripemd160(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	write(Ripemd160, data, data_len, out, out_len, written)
+}
+
+/// Run the BLAKE2 `F` compression function on the EIP-152 encoded input.
+///
+/// Return [`SubhasherStatus::InvalidInput`] if the input is malformed.
+///
+/// # Safety
+/// Check the [crate level](crate#safety) requirements.
+#[no_mangle]
+pub unsafe extern "C" fn subhasher_blake2f(
+	data: *const u8,
+	data_len: usize,
+	out: *mut u8,
+	out_len: usize,
+	written: *mut usize,
+) -> SubhasherStatus {
+	let Some(data) = read(data, data_len) else {
+		return SubhasherStatus::NullPointer;
+	};
+	let Some(output) = subhasher::blake2f(data) else {
+		return SubhasherStatus::InvalidInput;
+	};
+
+	write_output(&output, out, out_len, written)
+}
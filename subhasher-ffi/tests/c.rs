@@ -0,0 +1,37 @@
+//! Build and run the C test program against the shared library.
+
+// std
+use std::{
+	env,
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+#[test]
+fn c_program_should_work() {
+	let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+	let lib_dir = Path::new(env!("SUBHASHER_FFI_LIB_DIR"));
+	let program = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("subhasher-ffi-c");
+	let cc = env::var("CC").unwrap_or_else(|_| "cc".into());
+	let status = Command::new(cc)
+		.arg(crate_dir.join("tests/c/main.c"))
+		.arg("-Wall")
+		.arg("-Werror")
+		.arg("-I")
+		.arg(crate_dir.join("include"))
+		.arg("-L")
+		.arg(lib_dir)
+		.arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+		.arg("-lsubhasher_ffi")
+		.arg("-o")
+		.arg(&program)
+		.status()
+		.unwrap();
+
+	assert!(status.success(), "failed to compile the C program");
+
+	// Cargo points `LD_LIBRARY_PATH` to `target/<profile>`, which has precedence over the rpath.
+	let status = Command::new(&program).env_remove("LD_LIBRARY_PATH").status().unwrap();
+
+	assert!(status.success(), "the C program failed");
+}
@@ -0,0 +1,154 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "subhasher.h"
+
+static const uint8_t DATA[] = {
+	180, 247, 240, 59,  235, 197, 110, 190, 150, 188, 82,  234, 94, 211, 21, 157,
+	69,  160, 206, 58,  141, 127, 8,   41,  131, 195, 62,  241, 51, 39,  71, 71,
+};
+
+typedef SubhasherStatus (*hash_fn)(const uint8_t *, size_t, uint8_t *, size_t, size_t *);
+
+static int failures = 0;
+
+static void check(const char *name, hash_fn f, const uint8_t *expected, size_t expected_len) {
+	uint8_t out[128];
+	size_t written = 0;
+	SubhasherStatus status = f(DATA, sizeof(DATA), out, sizeof(out), &written);
+
+	if (status != SUBHASHER_STATUS_OK || written != expected_len ||
+	    memcmp(out, expected, expected_len) != 0) {
+		fprintf(stderr, "%s: unexpected output\n", name);
+		failures++;
+	}
+
+	// Query the required length.
+	written = 0;
+	status = f(DATA, sizeof(DATA), NULL, 0, &written);
+
+	if (status != SUBHASHER_STATUS_BUFFER_TOO_SMALL || written != expected_len) {
+		fprintf(stderr, "%s: unexpected length query result\n", name);
+		failures++;
+	}
+
+	// One byte short.
+	status = f(DATA, sizeof(DATA), out, expected_len - 1, NULL);
+
+	if (status != SUBHASHER_STATUS_BUFFER_TOO_SMALL) {
+		fprintf(stderr, "%s: short buffer accepted\n", name);
+		failures++;
+	}
+
+	status = f(NULL, 1, out, sizeof(out), NULL);
+
+	if (status != SUBHASHER_STATUS_NULL_POINTER) {
+		fprintf(stderr, "%s: null data accepted\n", name);
+		failures++;
+	}
+}
+
+#define CHECK(name, ...)                                                                         \
+	do {                                                                                     \
+		static const uint8_t expected[] = {__VA_ARGS__};                                 \
+		check(#name, subhasher_##name, expected, sizeof(expected));                      \
+	} while (0)
+
+int main(void) {
+	CHECK(blake2_128, 229, 139, 8, 235, 70, 70, 242, 62, 137, 189, 226, 103, 108, 142, 31, 238);
+	CHECK(blake2_256, 131, 170, 146, 72, 1, 154, 151, 42, 103, 74, 218, 88, 207, 203, 107, 13,
+	      200, 92, 201, 76, 144, 105, 27, 30, 147, 211, 231, 29, 237, 80, 125, 50);
+	CHECK(blake2_512, 112, 104, 54, 133, 50, 167, 180, 164, 222, 223, 153, 148, 191, 229, 132,
+	      228, 71, 147, 4, 232, 219, 0, 60, 238, 54, 88, 219, 122, 217, 56, 173, 212, 171, 44,
+	      232, 214, 46, 85, 158, 77, 225, 65, 34, 113, 36, 211, 181, 177, 216, 216, 77, 62, 94,
+	      86, 8, 78, 167, 128, 79, 135, 27, 63, 222, 157);
+	CHECK(blake2_128_concat, 229, 139, 8, 235, 70, 70, 242, 62, 137, 189, 226, 103, 108, 142, 31,
+	      238, 180, 247, 240, 59, 235, 197, 110, 190, 150, 188, 82, 234, 94, 211, 21, 157, 69, 160,
+	      206, 58, 141, 127, 8, 41, 131, 195, 62, 241, 51, 39, 71, 71);
+	CHECK(twox64, 58, 176, 83, 220, 107, 106, 113, 225);
+	CHECK(twox128, 58, 176, 83, 220, 107, 106, 113, 225, 63, 19, 228, 73, 73, 184, 227, 136);
+	CHECK(twox256, 58, 176, 83, 220, 107, 106, 113, 225, 63, 19, 228, 73, 73, 184, 227, 136, 99,
+	      136, 56, 248, 128, 160, 227, 220, 24, 225, 151, 183, 248, 239, 214, 220);
+	CHECK(twox64_concat, 58, 176, 83, 220, 107, 106, 113, 225, 180, 247, 240, 59, 235, 197, 110,
+	      190, 150, 188, 82, 234, 94, 211, 21, 157, 69, 160, 206, 58, 141, 127, 8, 41, 131, 195,
+	      62, 241, 51, 39, 71, 71);
+	CHECK(identity, 180, 247, 240, 59, 235, 197, 110, 190, 150, 188, 82, 234, 94, 211, 21, 157,
+	      69, 160, 206, 58, 141, 127, 8, 41, 131, 195, 62, 241, 51, 39, 71, 71);
+	CHECK(keccak256, 119, 116, 227, 161, 78, 72, 104, 189, 95, 63, 107, 212, 9, 104, 94, 253, 14,
+	      81, 121, 231, 131, 237, 83, 210, 165, 218, 66, 201, 17, 239, 176, 90);
+	CHECK(keccak512, 231, 174, 179, 22, 249, 128, 128, 152, 231, 215, 25, 152, 212, 100, 44, 120,
+	      74, 236, 189, 187, 192, 48, 97, 253, 202, 236, 105, 146, 131, 27, 246, 174, 90, 187, 86,
+	      115, 175, 79, 61, 236, 79, 74, 238, 213, 67, 57, 15, 254, 218, 158, 15, 137, 0, 247,
+	      113, 245, 96, 67, 18, 129, 119, 177, 188, 234);
+	CHECK(sha2_256, 93, 10, 233, 171, 98, 92, 231, 197, 14, 92, 237, 7, 159, 65, 79, 40, 22, 27,
+	      27, 100, 93, 244, 150, 121, 235, 215, 148, 252, 67, 194, 57, 188);
+
+	CHECK(sha2_512, 108, 130, 57, 107, 242, 26, 217, 38, 107, 132, 9, 108, 153, 201, 24, 41, 187,
+	      250, 61, 75, 140, 250, 191, 126, 254, 79, 151, 185, 131, 117, 132, 68, 66, 28, 121, 61,
+	      110, 98, 93, 187, 79, 56, 200, 48, 15, 217, 211, 236, 121, 95, 172, 0, 198, 28, 18, 132,
+	      220, 122, 235, 185, 145, 17, 152, 28);
+	CHECK(sha3_256, 44, 241, 125, 56, 130, 223, 125, 23, 1, 172, 220, 55, 88, 110, 86, 225, 198,
+	      144, 54, 71, 126, 102, 89, 29, 143, 201, 207, 17, 27, 214, 228, 210);
+	CHECK(ripemd160, 5, 164, 126, 83, 160, 219, 49, 123, 111, 243, 12, 22, 157, 214, 138, 32, 180,
+	      14, 86, 245);
+
+	// EIP-152 test vector 4, which is the single block BLAKE2b-512 of `abc`.
+	{
+		uint8_t input[213] = {0, 0, 0, 12};
+		uint8_t expected[64];
+		uint8_t out[64];
+		size_t written = 0;
+
+		memcpy(input + 4, (const uint8_t[]){0x48, 0xc9, 0xbd, 0xf2, 0x67, 0xe6, 0x09, 0x6a}, 8);
+		memcpy(input + 12, (const uint8_t[]){0x3b, 0xa7, 0xca, 0x84, 0x85, 0xae, 0x67, 0xbb}, 8);
+		memcpy(input + 20, (const uint8_t[]){0x2b, 0xf8, 0x94, 0xfe, 0x72, 0xf3, 0x6e, 0x3c}, 8);
+		memcpy(input + 28, (const uint8_t[]){0xf1, 0x36, 0x1d, 0x5f, 0x3a, 0xf5, 0x4f, 0xa5}, 8);
+		memcpy(input + 36, (const uint8_t[]){0xd1, 0x82, 0xe6, 0xad, 0x7f, 0x52, 0x0e, 0x51}, 8);
+		memcpy(input + 44, (const uint8_t[]){0x1f, 0x6c, 0x3e, 0x2b, 0x8c, 0x68, 0x05, 0x9b}, 8);
+		memcpy(input + 52, (const uint8_t[]){0x6b, 0xbd, 0x41, 0xfb, 0xab, 0xd9, 0x83, 0x1f}, 8);
+		memcpy(input + 60, (const uint8_t[]){0x79, 0x21, 0x7e, 0x13, 0x19, 0xcd, 0xe0, 0x5b}, 8);
+		memcpy(input + 68, "abc", 3);
+		input[196] = 3;
+		input[212] = 1;
+		subhasher_blake2_512((const uint8_t *)"abc", 3, expected, sizeof(expected), NULL);
+
+		if (subhasher_blake2f(input, sizeof(input), out, sizeof(out), &written) !=
+			    SUBHASHER_STATUS_OK ||
+		    written != 64 || memcmp(out, expected, 64) != 0) {
+			fprintf(stderr, "blake2f: unexpected output\n");
+			failures++;
+		}
+
+		input[212] = 2;
+
+		if (subhasher_blake2f(input, sizeof(input), out, sizeof(out), NULL) !=
+		    SUBHASHER_STATUS_INVALID_INPUT) {
+			fprintf(stderr, "blake2f: invalid final block flag accepted\n");
+			failures++;
+		}
+		if (subhasher_blake2f(input, 212, out, sizeof(out), NULL) !=
+		    SUBHASHER_STATUS_INVALID_INPUT) {
+			fprintf(stderr, "blake2f: short input accepted\n");
+			failures++;
+		}
+	}
+
+	// Empty data could be `NULL`.
+	{
+		uint8_t out[8];
+		size_t written = 0;
+
+		if (subhasher_twox64(NULL, 0, out, sizeof(out), &written) != SUBHASHER_STATUS_OK ||
+		    written != 8) {
+			fprintf(stderr, "twox64: empty data rejected\n");
+			failures++;
+		}
+		if (subhasher_identity(NULL, 0, NULL, 0, &written) != SUBHASHER_STATUS_OK ||
+		    written != 0) {
+			fprintf(stderr, "identity: empty data rejected\n");
+			failures++;
+		}
+	}
+
+	return failures == 0 ? 0 : 1;
+}
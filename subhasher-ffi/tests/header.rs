@@ -0,0 +1,41 @@
+//! Keep the committed C header in sync with the functions.
+
+// std
+use std::{fs, path::Path};
+
+#[test]
+fn header_should_be_up_to_date() {
+	let crate_dir = env!("CARGO_MANIFEST_DIR");
+	let path = Path::new(crate_dir).join("include/subhasher.h");
+	let config = cbindgen::Config::from_file(Path::new(crate_dir).join("cbindgen.toml")).unwrap();
+	let mut generated = Vec::new();
+
+	cbindgen::generate_with_config(crate_dir, config).unwrap().write(&mut generated);
+
+	let committed = fs::read_to_string(&path).unwrap_or_default();
+	let generated = String::from_utf8(generated).unwrap();
+
+	if committed != generated {
+		let (committed, generated) =
+			(committed.lines().collect::<Vec<_>>(), generated.lines().collect::<Vec<_>>());
+		let diff = (0..committed.len().max(generated.len()))
+			.filter_map(|i| {
+				let (c, g) = (committed.get(i), generated.get(i));
+
+				(c != g).then(|| {
+					[c.map(|c| format!("-{}: {c}", i + 1)), g.map(|g| format!("+{}: {g}", i + 1))]
+						.into_iter()
+						.flatten()
+						.collect::<Vec<_>>()
+						.join("\n")
+				})
+			})
+			.collect::<Vec<_>>()
+			.join("\n");
+
+		panic!(
+			"`{}` is outdated, run `cargo run -p subhasher-ffi --example header`\n{diff}",
+			path.display()
+		);
+	}
+}
@@ -0,0 +1,62 @@
+// crates.io
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+const SIZES: &[usize] = &[32, 256, 4_096];
+
+fn blake2(c: &mut Criterion) {
+	let mut g = c.benchmark_group("blake2_256");
+
+	for &size in SIZES {
+		let data = vec![0x5a; size];
+
+		g.throughput(Throughput::Bytes(size as u64));
+
+		g.bench_with_input(BenchmarkId::new("blake2-rfc", size), &data, |b, d| {
+			b.iter(|| subhasher::backend::blake2::blake2_rfc::<32>(d))
+		});
+		#[cfg(feature = "blake2b-simd")]
+		g.bench_with_input(BenchmarkId::new("blake2b-simd", size), &data, |b, d| {
+			b.iter(|| subhasher::backend::blake2::blake2b_simd::<32>(d))
+		});
+	}
+
+	g.finish();
+}
+
+fn twox(c: &mut Criterion) {
+	let mut g = c.benchmark_group("xxh64");
+
+	for &size in SIZES {
+		let data = vec![0x5a; size];
+
+		g.throughput(Throughput::Bytes(size as u64));
+
+		g.bench_with_input(BenchmarkId::new("twox-hash", size), &data, |b, d| {
+			b.iter(|| subhasher::backend::twox::twox_hash(d, 0))
+		});
+		#[cfg(feature = "xxhash-rust")]
+		g.bench_with_input(BenchmarkId::new("xxhash-rust", size), &data, |b, d| {
+			b.iter(|| subhasher::backend::twox::xxhash_rust(d, 0))
+		});
+	}
+
+	g.finish();
+}
+
+fn storage_key(c: &mut Criterion) {
+	let account = [0x5a; 32];
+
+	c.bench_function("blake2_128_concat", |b| b.iter(|| subhasher::blake2_128_concat(account)));
+	c.bench_function("twox128", |b| b.iter(|| subhasher::twox128(b"Account")));
+}
+
+fn hash_many(c: &mut Criterion) {
+	let accounts = (0..100_000_u64).map(|i| [i as u8; 32]).collect::<Vec<_>>();
+
+	c.bench_function("hash_many", |b| {
+		b.iter(|| subhasher::hash_many(subhasher::Blake2_128Concat, &accounts))
+	});
+}
+
+criterion_group!(benches, blake2, twox, storage_key, hash_many);
+criterion_main!(benches);
@@ -0,0 +1,55 @@
+//! Hash backends.
+//!
+//! `blake2-rfc` and `twox-hash` are always available, the faster ones are gated behind the
+//! features of the same name. The crate level functions use the fastest enabled one.
+
+#[cfg(not(feature = "blake2b-simd"))] pub(crate) use blake2::blake2_rfc as blake2b;
+#[cfg(feature = "blake2b-simd")] pub(crate) use blake2::blake2b_simd as blake2b;
+
+#[cfg(not(feature = "xxhash-rust"))] pub(crate) use twox::twox_hash as xxh64;
+#[cfg(feature = "xxhash-rust")] pub(crate) use twox::xxhash_rust as xxh64;
+
+pub mod blake2 {
+	//! BLAKE2b backends.
+
+	/// Hash the data into a `N`-bytes array with [blake2-rfc](https://crates.io/crates/blake2-rfc).
+	pub fn blake2_rfc<const N: usize>(data: &[u8]) -> [u8; N] {
+		let mut dest = [0; N];
+
+		dest.copy_from_slice(blake2_rfc::blake2b::blake2b(N, &[], data).as_bytes());
+
+		dest
+	}
+
+	/// Hash the data into a `N`-bytes array with [blake2b_simd](https://crates.io/crates/blake2b_simd).
+	#[cfg(feature = "blake2b-simd")]
+	pub fn blake2b_simd<const N: usize>(data: &[u8]) -> [u8; N] {
+		let mut dest = [0; N];
+
+		dest.copy_from_slice(blake2b_simd::Params::new().hash_length(N).hash(data).as_bytes());
+
+		dest
+	}
+}
+
+pub mod twox {
+	//! XXH64 backends.
+
+	/// Hash the data with the seed into a [`u64`] with [twox-hash](https://crates.io/crates/twox-hash).
+	pub fn twox_hash(data: &[u8], seed: u64) -> u64 {
+		// core
+		use core::hash::Hasher;
+
+		let mut h = twox_hash::XxHash::with_seed(seed);
+
+		h.write(data);
+
+		h.finish()
+	}
+
+	/// Hash the data with the seed into a [`u64`] with [xxhash-rust](https://crates.io/crates/xxhash-rust).
+	#[cfg(feature = "xxhash-rust")]
+	pub fn xxhash_rust(data: &[u8], seed: u64) -> u64 {
+		xxhash_rust::xxh64::xxh64(data, seed)
+	}
+}
@@ -0,0 +1,135 @@
+//! Batch hashing.
+//!
+//! With the `rayon` feature, [`hash_many`] and [`HashManyExt::par_hash_many`] spread the work
+//! across threads. The results are always in the same order as the inputs.
+
+// alloc
+#[cfg(not(feature = "std"))] use alloc::vec::Vec;
+// crates.io
+#[cfg(feature = "rayon")] use rayon::prelude::*;
+// substrate-minimal
+use crate::Hasher;
+
+/// Number of the items which [`HashMany`] pulls from the inner iterator at once.
+pub const CHUNK_SIZE: usize = 1_024;
+
+/// Hash all the inputs with the given algorithm.
+///
+/// # Example
+/// ```
+/// let keys = subhasher::hash_many(subhasher::Blake2_128Concat, &[[0_u8; 32], [1; 32]]);
+///
+/// assert_eq!(keys[1], subhasher::blake2_128_concat([1; 32]));
+/// ```
+pub fn hash_many<H, D>(hasher: H, inputs: &[D]) -> Vec<H::Output>
+where
+	H: Hasher + Sync,
+	H::Output: Send,
+	D: AsRef<[u8]> + Sync,
+{
+	#[cfg(feature = "rayon")]
+	{
+		inputs.par_iter().map(|d| hasher.hash(d.as_ref())).collect()
+	}
+	#[cfg(not(feature = "rayon"))]
+	{
+		inputs.iter().map(|d| hasher.hash(d.as_ref())).collect()
+	}
+}
+
+/// Iterator adapter for hashing the items on the fly.
+pub trait HashManyExt
+where
+	Self: Sized + Iterator,
+{
+	/// Hash each item with the given algorithm.
+	fn hash_many<H>(self, hasher: H) -> HashMany<Self, H>
+	where
+		H: Hasher,
+	{
+		HashMany { inner: self, hasher }
+	}
+
+	/// Hash each item with the given algorithm, across threads.
+	///
+	/// The items are pulled and hashed by chunks of [`CHUNK_SIZE`].
+	#[cfg(feature = "rayon")]
+	fn par_hash_many<H>(self, hasher: H) -> ParHashMany<Self, H>
+	where
+		H: Hasher,
+	{
+		ParHashMany { inner: self, hasher, chunk: Vec::new(), hashed: Vec::new().into_iter() }
+	}
+}
+impl<I> HashManyExt for I where I: Iterator {}
+
+/// Iterator returned by [`HashManyExt::hash_many`].
+pub struct HashMany<I, H> {
+	inner: I,
+	hasher: H,
+}
+impl<I, H> Iterator for HashMany<I, H>
+where
+	I: Iterator,
+	I::Item: AsRef<[u8]>,
+	H: Hasher,
+{
+	type Item = H::Output;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		self.inner.next().map(|d| self.hasher.hash(d.as_ref()))
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.inner.size_hint()
+	}
+}
+
+/// Iterator returned by [`HashManyExt::par_hash_many`].
+#[cfg(feature = "rayon")]
+pub struct ParHashMany<I, H>
+where
+	I: Iterator,
+	H: Hasher,
+{
+	inner: I,
+	hasher: H,
+	chunk: Vec<I::Item>,
+	hashed: std::vec::IntoIter<H::Output>,
+}
+#[cfg(feature = "rayon")]
+impl<I, H> Iterator for ParHashMany<I, H>
+where
+	I: Iterator,
+	I::Item: AsRef<[u8]> + Send,
+	H: Hasher + Sync,
+	H::Output: Send,
+{
+	type Item = H::Output;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if let Some(o) = self.hashed.next() {
+			return Some(o);
+		}
+
+		self.chunk.extend(self.inner.by_ref().take(CHUNK_SIZE));
+
+		let hasher = &self.hasher;
+
+		self.hashed = self
+			.chunk
+			.par_drain(..)
+			.map(|d| hasher.hash(d.as_ref()))
+			.collect::<Vec<_>>()
+			.into_iter();
+
+		self.hashed.next()
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let hashed = self.hashed.len();
+		let (lower, upper) = self.inner.size_hint();
+
+		(lower.saturating_add(hashed), upper.and_then(|u| u.checked_add(hashed)))
+	}
+}
@@ -0,0 +1,228 @@
+//! Binary Merkle tree.
+//!
+//! Each leaf is hashed first, then the pairs are hashed in order.
+//! An odd node is promoted to the upper layer without hashing.
+//!
+//! Substrate reference(s):
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/utils/binary-merkle-tree/src/lib.rs>
+
+// alloc
+#[cfg(not(feature = "std"))] use alloc::vec::Vec;
+// substrate-minimal
+use crate::Hasher;
+
+/// Merkle proof of a leaf.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleProof<H, L> {
+	/// Root of the tree.
+	pub root: H,
+	/// Sibling hashes, from the bottom to the top.
+	pub proof: Vec<H>,
+	/// Number of the leaves.
+	pub number_of_leaves: u32,
+	/// Index of the proven leaf.
+	pub leaf_index: u32,
+	/// Proven leaf.
+	pub leaf: L,
+}
+
+/// Leaf to verify.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Leaf<'a, H> {
+	/// Leaf content, which will be hashed before verifying.
+	Value(&'a [u8]),
+	/// Hash of the leaf.
+	Hash(H),
+}
+impl<'a, H, T> From<&'a T> for Leaf<'a, H>
+where
+	T: AsRef<[u8]>,
+{
+	fn from(v: &'a T) -> Self {
+		Self::Value(v.as_ref())
+	}
+}
+
+/// Calculate the root of the leaves.
+///
+/// Return the default hash, which is usually all zeros, if there is no leaf.
+pub fn merkle_root<H, I>(hasher: H, leaves: I) -> H::Output
+where
+	H: Hasher,
+	H::Output: Clone + Default,
+	I: IntoIterator,
+	I::Item: AsRef<[u8]>,
+{
+	let leaves = leaves.into_iter().map(|l| hasher.hash(l.as_ref())).collect();
+
+	merkelize(&hasher, leaves, |_| ())
+}
+
+/// Similar to [`merkle_root`], but the leaves are hashes already.
+pub fn merkle_root_raw<H, I>(hasher: H, leaves: I) -> H::Output
+where
+	H: Hasher,
+	H::Output: Clone + Default,
+	I: IntoIterator<Item = H::Output>,
+{
+	merkelize(&hasher, leaves.into_iter().collect(), |_| ())
+}
+
+/// Generate the proof of the leaf at the `leaf_index`.
+///
+/// Return [`None`] if the index is out of range.
+pub fn merkle_proof<H, I, T>(
+	hasher: H,
+	leaves: I,
+	leaf_index: u32,
+) -> Option<MerkleProof<H::Output, T>>
+where
+	H: Hasher,
+	H::Output: Clone + Default,
+	I: IntoIterator<Item = T>,
+	T: AsRef<[u8]>,
+{
+	let mut leaf = None;
+	let leaves = leaves
+		.into_iter()
+		.enumerate()
+		.map(|(i, l)| {
+			let h = hasher.hash(l.as_ref());
+
+			if i as u32 == leaf_index {
+				leaf = Some(l);
+			}
+
+			h
+		})
+		.collect::<Vec<_>>();
+
+	prove(&hasher, leaves, leaf_index, leaf?)
+}
+
+/// Similar to [`merkle_proof`], but the leaves are hashes already.
+pub fn merkle_proof_raw<H, I>(
+	hasher: H,
+	leaves: I,
+	leaf_index: u32,
+) -> Option<MerkleProof<H::Output, H::Output>>
+where
+	H: Hasher,
+	H::Output: Clone + Default,
+	I: IntoIterator<Item = H::Output>,
+{
+	let leaves = leaves.into_iter().collect::<Vec<_>>();
+	let leaf = leaves.get(leaf_index as usize)?.clone();
+
+	prove(&hasher, leaves, leaf_index, leaf)
+}
+
+/// Verify the proof of a leaf against the root.
+///
+/// The proof must contain exactly the sibling hashes on the path from the leaf to the root.
+pub fn verify_proof<'a, H, P, L>(
+	hasher: H,
+	root: &H::Output,
+	proof: P,
+	number_of_leaves: u32,
+	leaf_index: u32,
+	leaf: L,
+) -> bool
+where
+	H: Hasher,
+	P: IntoIterator<Item = H::Output>,
+	L: Into<Leaf<'a, H::Output>>,
+{
+	if leaf_index >= number_of_leaves {
+		return false;
+	}
+
+	let mut hash = match leaf.into() {
+		Leaf::Value(v) => hasher.hash(v),
+		Leaf::Hash(h) => h,
+	};
+	let mut proof = proof.into_iter();
+	let mut position = leaf_index;
+	let mut width = number_of_leaves;
+	let mut combined = Vec::new();
+
+	while width > 1 {
+		// The last odd node is promoted, so there is no sibling.
+		if position ^ 1 < width {
+			let Some(sibling) = proof.next() else {
+				return false;
+			};
+
+			combined.clear();
+
+			if position % 2 == 0 {
+				combined.extend_from_slice(hash.as_ref());
+				combined.extend_from_slice(sibling.as_ref());
+			} else {
+				combined.extend_from_slice(sibling.as_ref());
+				combined.extend_from_slice(hash.as_ref());
+			}
+
+			hash = hasher.hash(&combined);
+		}
+
+		position /= 2;
+		width = width.div_ceil(2);
+	}
+
+	proof.next().is_none() && hash.as_ref() == root.as_ref()
+}
+
+fn prove<H, L>(
+	hasher: &H,
+	leaves: Vec<H::Output>,
+	leaf_index: u32,
+	leaf: L,
+) -> Option<MerkleProof<H::Output, L>>
+where
+	H: Hasher,
+	H::Output: Clone + Default,
+{
+	let number_of_leaves = u32::try_from(leaves.len()).ok()?;
+	let mut proof = Vec::new();
+	let mut position = leaf_index as usize;
+	let root = merkelize(hasher, leaves, |layer| {
+		if let Some(sibling) = layer.get(position ^ 1) {
+			proof.push(sibling.clone());
+		}
+
+		position /= 2;
+	});
+
+	Some(MerkleProof { root, proof, number_of_leaves, leaf_index, leaf })
+}
+
+fn merkelize<H, F>(hasher: &H, mut layer: Vec<H::Output>, mut visit: F) -> H::Output
+where
+	H: Hasher,
+	H::Output: Clone + Default,
+	F: FnMut(&[H::Output]),
+{
+	let mut combined = Vec::new();
+
+	while layer.len() > 1 {
+		visit(&layer);
+
+		layer = layer
+			.chunks(2)
+			.map(|pair| {
+				if let [a, b] = pair {
+					combined.clear();
+					combined.extend_from_slice(a.as_ref());
+					combined.extend_from_slice(b.as_ref());
+
+					hasher.hash(&combined)
+				} else {
+					pair[0].clone()
+				}
+			})
+			.collect();
+	}
+
+	layer.pop().unwrap_or_default()
+}
@@ -0,0 +1,124 @@
+//! Hash algorithms as types.
+
+// alloc
+#[cfg(not(feature = "std"))] use alloc::vec::Vec;
+
+/// Hash algorithm.
+///
+/// Each supported algorithm is available as an unit struct,
+/// which could be passed around wherever an algorithm is required.
+pub trait Hasher {
+	/// Hash output.
+	type Output: AsRef<[u8]>;
+
+	/// Hash the data.
+	fn hash(&self, data: &[u8]) -> Self::Output;
+
+	/// Hash the data and append the result to the given buffer.
+	fn hash_into(&self, data: &[u8], dest: &mut Vec<u8>) {
+		dest.extend_from_slice(self.hash(data).as_ref());
+	}
+}
+
+impl<H> Hasher for &H
+where
+	H: Hasher,
+{
+	type Output = H::Output;
+
+	fn hash(&self, data: &[u8]) -> Self::Output {
+		(*self).hash(data)
+	}
+
+	fn hash_into(&self, data: &[u8], dest: &mut Vec<u8>) {
+		(*self).hash_into(data, dest)
+	}
+}
+
+macro_rules! impl_hashers {
+	($($(#[$attr:meta])* $name:ident => $f:ident -> $output:ty,)+) => {
+		$(
+			$(#[$attr])*
+			#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+			pub struct $name;
+			impl Hasher for $name {
+				type Output = $output;
+
+				fn hash(&self, data: &[u8]) -> Self::Output {
+					crate::$f(data)
+				}
+			}
+		)+
+	};
+}
+impl_hashers! {
+	/// [`blake2_128`](crate::blake2_128) algorithm.
+	Blake2_128 => blake2_128 -> [u8; 16],
+	/// [`blake2_256`](crate::blake2_256) algorithm.
+	Blake2_256 => blake2_256 -> [u8; 32],
+	/// [`blake2_512`](crate::blake2_512) algorithm.
+	Blake2_512 => blake2_512 -> [u8; 64],
+	/// [`twox64`](crate::twox64) algorithm.
+	Twox64 => twox64 -> [u8; 8],
+	/// [`twox128`](crate::twox128) algorithm.
+	Twox128 => twox128 -> [u8; 16],
+	/// [`twox256`](crate::twox256) algorithm.
+	Twox256 => twox256 -> [u8; 32],
+	/// [`keccak256`](crate::keccak256) algorithm.
+	Keccak256 => keccak256 -> [u8; 32],
+	/// [`keccak512`](crate::keccak512) algorithm.
+	Keccak512 => keccak512 -> [u8; 64],
+	/// [`sha2_256`](crate::sha2_256) algorithm.
+	Sha2_256 => sha2_256 -> [u8; 32],
+	/// [`sha2_512`](crate::sha2_512) algorithm.
+	Sha2_512 => sha2_512 -> [u8; 64],
+	/// [`sha3_256`](crate::sha3_256) algorithm.
+	Sha3_256 => sha3_256 -> [u8; 32],
+	/// [`ripemd160`](crate::ripemd160) algorithm.
+	Ripemd160 => ripemd160 -> [u8; 20],
+}
+
+/// [`blake2_128_concat`](crate::blake2_128_concat) algorithm.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Blake2_128Concat;
+impl Hasher for Blake2_128Concat {
+	type Output = Vec<u8>;
+
+	fn hash(&self, data: &[u8]) -> Self::Output {
+		crate::blake2_128_concat(data)
+	}
+
+	fn hash_into(&self, data: &[u8], dest: &mut Vec<u8>) {
+		crate::blake2_128_concat_into(data, dest)
+	}
+}
+
+/// [`twox64_concat`](crate::twox64_concat) algorithm.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Twox64Concat;
+impl Hasher for Twox64Concat {
+	type Output = Vec<u8>;
+
+	fn hash(&self, data: &[u8]) -> Self::Output {
+		crate::twox64_concat(data)
+	}
+
+	fn hash_into(&self, data: &[u8], dest: &mut Vec<u8>) {
+		crate::twox64_concat_into(data, dest)
+	}
+}
+
+/// [`identity`](crate::identity) algorithm.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Identity;
+impl Hasher for Identity {
+	type Output = Vec<u8>;
+
+	fn hash(&self, data: &[u8]) -> Self::Output {
+		data.to_vec()
+	}
+
+	fn hash_into(&self, data: &[u8], dest: &mut Vec<u8>) {
+		dest.extend_from_slice(data)
+	}
+}
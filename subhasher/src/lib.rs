@@ -7,29 +7,36 @@
 #[cfg(not(feature = "std"))] extern crate alloc;
 
 #[cfg(test)] mod test;
+#[cfg(test)] use criterion as _;
+
+pub mod backend;
+
+mod batch;
+pub use batch::*;
+
+pub mod binary_merkle_tree;
+
+mod blake2f;
+pub use blake2f::*;
+
+mod hasher;
+pub use hasher::*;
+
+pub mod mmr;
 
-// core
-use core::hash::Hasher as _;
 // alloc
 #[cfg(not(feature = "std"))] use alloc::vec::Vec;
 // crates.io
-use blake2_rfc::blake2b::blake2b;
 use byteorder::{ByteOrder, LittleEndian};
-use sha2::{Digest, Sha256};
-use tiny_keccak::{Hasher as _, Keccak};
-use twox_hash::XxHash;
+use sha2::{Digest, Sha256, Sha512};
+use tiny_keccak::{Hasher as _, Keccak, Sha3};
 
 /// Hash the data into a 16-bytes array with BLAKE2 algorithm.
 pub fn blake2_128<D>(data: D) -> [u8; 16]
 where
 	D: AsRef<[u8]>,
 {
-	let data = data.as_ref();
-	let mut dest = [0; 16];
-
-	dest.copy_from_slice(blake2b(16, &[], data).as_bytes());
-
-	dest
+	backend::blake2b(data.as_ref())
 }
 
 /// Hash the data into a 32-bytes array with BLAKE2 algorithm.
@@ -37,12 +44,7 @@ pub fn blake2_256<D>(data: D) -> [u8; 32]
 where
 	D: AsRef<[u8]>,
 {
-	let data = data.as_ref();
-	let mut dest = [0; 32];
-
-	dest.copy_from_slice(blake2b(32, &[], data).as_bytes());
-
-	dest
+	backend::blake2b(data.as_ref())
 }
 
 /// Hash the data into a 64-bytes array with BLAKE2 algorithm.
@@ -50,12 +52,7 @@ pub fn blake2_512<D>(data: D) -> [u8; 64]
 where
 	D: AsRef<[u8]>,
 {
-	let data = data.as_ref();
-	let mut dest = [0; 64];
-
-	dest.copy_from_slice(blake2b(64, &[], data).as_bytes());
-
-	dest
+	backend::blake2b(data.as_ref())
 }
 
 /// Hash the data into `blake2_128(data) + data`.
@@ -64,19 +61,28 @@ where
 	D: AsRef<[u8]>,
 {
 	let data = data.as_ref();
-	let mut v = blake2_128(data).to_vec();
+	let mut v = Vec::with_capacity(16 + data.len());
 
-	v.extend_from_slice(data);
+	blake2_128_concat_into(data, &mut v);
 
 	v
 }
 
+/// Similar to [`blake2_128_concat`], but append the result to the given buffer.
+pub fn blake2_128_concat_into<D>(data: D, dest: &mut Vec<u8>)
+where
+	D: AsRef<[u8]>,
+{
+	let data = data.as_ref();
+
+	dest.extend_from_slice(&blake2_128(data));
+	dest.extend_from_slice(data);
+}
+
 fn twox(dest: &mut [u8], data: &[u8], seed: u64) {
-	let mut h = XxHash::with_seed(seed);
 	let i = seed as usize * 8;
 
-	h.write(data);
-	LittleEndian::write_u64(&mut dest[i..i + 8], h.finish());
+	LittleEndian::write_u64(&mut dest[i..i + 8], backend::xxh64(data, seed));
 }
 
 /// Hash the data into a 8-bytes array with XX algorithm.
@@ -128,13 +134,24 @@ where
 	D: AsRef<[u8]>,
 {
 	let data = data.as_ref();
-	let mut v = twox64(data).to_vec();
+	let mut v = Vec::with_capacity(8 + data.len());
 
-	v.extend_from_slice(data);
+	twox64_concat_into(data, &mut v);
 
 	v
 }
 
+/// Similar to [`twox64_concat`], but append the result to the given buffer.
+pub fn twox64_concat_into<D>(data: D, dest: &mut Vec<u8>)
+where
+	D: AsRef<[u8]>,
+{
+	let data = data.as_ref();
+
+	dest.extend_from_slice(&twox64(data));
+	dest.extend_from_slice(data);
+}
+
 /// Return data directly.
 pub fn identity(data: &[u8]) -> &[u8] {
 	data
@@ -187,3 +204,53 @@ where
 
 	output
 }
+
+/// Hash the data into a 64-bytes array with SHA2 algorithm.
+pub fn sha2_512<D>(data: D) -> [u8; 64]
+where
+	D: AsRef<[u8]>,
+{
+	let data = data.as_ref();
+
+	let mut hasher = Sha512::new();
+	hasher.update(data);
+
+	let mut output = [0u8; 64];
+	output.copy_from_slice(&hasher.finalize());
+
+	output
+}
+
+/// Hash the data into a 32-bytes array with SHA3 algorithm.
+///
+/// This is the FIPS 202 one, which is different from [`keccak256`].
+pub fn sha3_256<D>(data: D) -> [u8; 32]
+where
+	D: AsRef<[u8]>,
+{
+	let data = data.as_ref();
+
+	let mut sha3 = Sha3::v256();
+	sha3.update(data);
+
+	let mut output = [0u8; 32];
+	sha3.finalize(&mut output);
+
+	output
+}
+
+/// Hash the data into a 20-bytes array with RIPEMD-160 algorithm.
+pub fn ripemd160<D>(data: D) -> [u8; 20]
+where
+	D: AsRef<[u8]>,
+{
+	let data = data.as_ref();
+
+	let mut hasher = ripemd::Ripemd160::new();
+	hasher.update(data);
+
+	let mut output = [0u8; 20];
+	output.copy_from_slice(&hasher.finalize());
+
+	output
+}
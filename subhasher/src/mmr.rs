@@ -0,0 +1,405 @@
+//! Merkle Mountain Range.
+//!
+//! Compatible with the MMR of `pallet-mmr`, which is built on top of
+//! [ckb-merkle-mountain-range](https://github.com/paritytech/merkle-mountain-range).
+//! Two nodes are merged into `hash(left + right)`, and the peaks are bagged from right to left
+//! into `hash(right + left)`.
+//!
+//! Substrate reference(s):
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/frame/merkle-mountain-range/src/mmr/mmr.rs>
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/primitives/merkle-mountain-range/src/lib.rs>
+
+// alloc
+#[cfg(not(feature = "std"))] use alloc::{collections::VecDeque, vec::Vec};
+// std
+#[cfg(feature = "std")] use std::collections::VecDeque;
+// crates.io
+#[cfg(feature = "codec")] use parity_scale_codec::{Decode, Encode};
+// substrate-minimal
+use crate::Hasher;
+
+/// MMR proof of a group of leaves, in the format which `pallet-mmr` emits.
+///
+/// With the `codec` feature, the `proof` field of the `mmr_generateProof` RPC response could be
+/// decoded into this directly.
+/// The `leaves` field of the response is a SCALE encoded `Vec<Vec<u8>>`, each item is the
+/// encoded leaf.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+pub struct LeafProof<H> {
+	/// Indices of the proven leaves.
+	pub leaf_indices: Vec<u64>,
+	/// Number of the leaves in the MMR, when the proof was generated.
+	pub leaf_count: u64,
+	/// Hashes of the siblings on the paths to the peaks, and the bagged right hand side peaks.
+	pub items: Vec<H>,
+}
+
+/// Convert a leaf index to its node position.
+///
+/// Return [`None`] if the position overflows.
+pub fn leaf_index_to_pos(index: u64) -> Option<u64> {
+	Some(leaf_index_to_mmr_size(index)? - (index + 1).trailing_zeros() as u64 - 1)
+}
+
+/// Get the MMR size, number of the nodes, right after the leaf at the `index` was appended.
+///
+/// Return [`None`] if the size overflows.
+pub fn leaf_index_to_mmr_size(index: u64) -> Option<u64> {
+	let leaf_count = index.checked_add(1)?;
+
+	Some(leaf_count.checked_mul(2)? - leaf_count.count_ones() as u64)
+}
+
+/// Get the MMR size, number of the nodes, of a MMR with `leaf_count` leaves.
+///
+/// Return [`None`] if the size overflows.
+pub fn leaf_count_to_mmr_size(leaf_count: u64) -> Option<u64> {
+	leaf_count.checked_sub(1).map_or(Some(0), leaf_index_to_mmr_size)
+}
+
+/// Check if the `mmr_size` is a size which a MMR could be.
+pub fn is_valid_mmr_size(mmr_size: u64) -> bool {
+	if mmr_size == 0 {
+		return true;
+	}
+
+	let leaf_count = peak_map(mmr_size);
+
+	leaf_count != 0 && leaf_index_to_mmr_size(leaf_count - 1) == Some(mmr_size)
+}
+
+/// Get the peak positions of a MMR with the `mmr_size`, from left to right.
+pub fn peak_positions(mmr_size: u64) -> Vec<u64> {
+	if mmr_size == 0 {
+		return Vec::new();
+	}
+
+	let mut pos = mmr_size;
+	let mut peak_size = u64::MAX >> pos.leading_zeros();
+	let mut peaks = Vec::new();
+	let mut peaks_sum = 0;
+
+	while peak_size > 0 {
+		if pos >= peak_size {
+			pos -= peak_size;
+			peaks.push(peaks_sum + peak_size - 1);
+			peaks_sum += peak_size;
+		}
+
+		peak_size >>= 1;
+	}
+
+	peaks
+}
+
+/// Calculate the peaks of the MMR built from the leaves, from left to right.
+///
+/// Each leaf is hashed first.
+pub fn peaks<H, I>(hasher: H, leaves: I) -> Vec<H::Output>
+where
+	H: Hasher,
+	I: IntoIterator,
+	I::Item: AsRef<[u8]>,
+{
+	// Peaks with their heights.
+	let mut peaks = Vec::<(u32, H::Output)>::new();
+
+	for l in leaves {
+		let mut node = (0, hasher.hash(l.as_ref()));
+
+		while let Some((height, left)) = peaks.pop() {
+			if height != node.0 {
+				peaks.push((height, left));
+
+				break;
+			}
+
+			node = (height + 1, merge(&hasher, &left, &node.1));
+		}
+
+		peaks.push(node);
+	}
+
+	peaks.into_iter().map(|(_, p)| p).collect()
+}
+
+/// Bag the peaks into the root, from right to left.
+///
+/// Return [`None`] if there is no peak.
+pub fn bag_peaks<H>(hasher: H, mut peaks: Vec<H::Output>) -> Option<H::Output>
+where
+	H: Hasher,
+{
+	while peaks.len() > 1 {
+		let right = peaks.pop()?;
+		let left = peaks.pop()?;
+
+		peaks.push(merge(&hasher, &right, &left));
+	}
+
+	peaks.pop()
+}
+
+/// Calculate the root of the MMR built from the leaves.
+///
+/// Each leaf is hashed first.
+/// Return [`None`] if there is no leaf.
+pub fn root<H, I>(hasher: H, leaves: I) -> Option<H::Output>
+where
+	H: Hasher,
+	I: IntoIterator,
+	I::Item: AsRef<[u8]>,
+{
+	let peaks = peaks(&hasher, leaves);
+
+	bag_peaks(hasher, peaks)
+}
+
+/// Verify the proof of the leaves against the root.
+///
+/// The leaves must be in the same order as the [`LeafProof::leaf_indices`].
+/// Each leaf is the SCALE encoded leaf data, which will be hashed before verifying.
+pub fn verify_leaves_proof<H, I>(
+	hasher: H,
+	root: &H::Output,
+	leaves: I,
+	proof: &LeafProof<H::Output>,
+) -> bool
+where
+	H: Hasher,
+	H::Output: Clone + PartialEq,
+	I: IntoIterator,
+	I::Item: AsRef<[u8]>,
+{
+	let leaves = leaves.into_iter().map(|l| hasher.hash(l.as_ref())).collect();
+
+	verify_leaves_proof_raw(hasher, root, leaves, proof)
+}
+
+/// Similar to [`verify_leaves_proof`], but the leaves are hashes already.
+pub fn verify_leaves_proof_raw<H>(
+	hasher: H,
+	root: &H::Output,
+	leaves: Vec<H::Output>,
+	proof: &LeafProof<H::Output>,
+) -> bool
+where
+	H: Hasher,
+	H::Output: Clone + PartialEq,
+{
+	if leaves.is_empty()
+		|| leaves.len() != proof.leaf_indices.len()
+		|| proof.leaf_indices.iter().any(|i| *i >= proof.leaf_count)
+	{
+		return false;
+	}
+
+	let Some(positions) =
+		proof.leaf_indices.iter().map(|i| leaf_index_to_pos(*i)).collect::<Option<Vec<_>>>()
+	else {
+		return false;
+	};
+	let Some(mmr_size) = leaf_count_to_mmr_size(proof.leaf_count) else {
+		return false;
+	};
+
+	calculate_root(hasher, positions.into_iter().zip(leaves).collect(), mmr_size, &proof.items)
+		.is_some_and(|r| &r == root)
+}
+
+/// Calculate the root from the leaves, with their node positions, and the proof items.
+///
+/// Return [`None`] if the proof is malformed.
+pub fn calculate_root<H>(
+	hasher: H,
+	leaves: Vec<(u64, H::Output)>,
+	mmr_size: u64,
+	proof: &[H::Output],
+) -> Option<H::Output>
+where
+	H: Hasher,
+	H::Output: Clone + PartialEq,
+{
+	let peaks = calculate_peaks(&hasher, leaves, mmr_size, proof)?;
+
+	bag_peaks(hasher, peaks)
+}
+
+fn calculate_peaks<H>(
+	hasher: &H,
+	mut leaves: Vec<(u64, H::Output)>,
+	mmr_size: u64,
+	proof: &[H::Output],
+) -> Option<Vec<H::Output>>
+where
+	H: Hasher,
+	H::Output: Clone + PartialEq,
+{
+	if !is_valid_mmr_size(mmr_size) || leaves.iter().any(|(p, _)| pos_height_in_tree(*p) > 0) {
+		return None;
+	}
+	if mmr_size == 1 && leaves.len() == 1 && leaves[0].0 == 0 {
+		return Some(leaves.into_iter().map(|(_, l)| l).collect());
+	}
+
+	leaves.sort_by_key(|(p, _)| *p);
+
+	if leaves.windows(2).any(|w| w[0].0 == w[1].0 && w[0].1 != w[1].1) {
+		return None;
+	}
+
+	leaves.dedup_by(|a, b| a.0 == b.0);
+
+	let mut proof = proof.iter();
+	let mut leaves = leaves.into_iter().peekable();
+	let mut peaks = Vec::new();
+
+	for peak_pos in peak_positions(mmr_size) {
+		let mut peak_leaves = Vec::new();
+
+		while let Some(l) = leaves.next_if(|(p, _)| *p <= peak_pos) {
+			peak_leaves.push(l);
+		}
+
+		let peak = if peak_leaves.len() == 1 && peak_leaves[0].0 == peak_pos {
+			peak_leaves.pop()?.1
+		} else if peak_leaves.is_empty() {
+			// The rest peaks have been bagged into one proof item, or there are no more peaks.
+			let Some(p) = proof.next() else {
+				break;
+			};
+
+			p.clone()
+		} else {
+			calculate_peak(hasher, peak_leaves, peak_pos, &mut proof)?
+		};
+
+		peaks.push(peak);
+	}
+
+	if leaves.next().is_some() {
+		return None;
+	}
+	if let Some(p) = proof.next() {
+		peaks.push(p.clone());
+	}
+	if proof.next().is_some() {
+		return None;
+	}
+
+	Some(peaks)
+}
+
+fn calculate_peak<'a, H, I>(
+	hasher: &H,
+	leaves: Vec<(u64, H::Output)>,
+	peak_pos: u64,
+	proof: &mut I,
+) -> Option<H::Output>
+where
+	H: Hasher,
+	H::Output: 'a + Clone,
+	I: Iterator<Item = &'a H::Output>,
+{
+	let mut queue = leaves.into_iter().map(|(p, l)| (p, l, 0)).collect::<VecDeque<_>>();
+
+	while let Some((pos, node, height)) = queue.pop_front() {
+		if pos == peak_pos {
+			return queue.is_empty().then_some(node);
+		}
+
+		let (parent_pos, parent) = if pos_height_in_tree(pos + 1) > height {
+			// Right child.
+			let sibling = sibling(&mut queue, pos.checked_sub(sibling_offset(height))?, proof)?;
+
+			(pos + 1, merge(hasher, &sibling, &node))
+		} else {
+			// Left child.
+			let sibling = sibling(&mut queue, pos + sibling_offset(height), proof)?;
+
+			(pos + parent_offset(height), merge(hasher, &node, &sibling))
+		};
+
+		if parent_pos > peak_pos {
+			return None;
+		}
+
+		queue.push_back((parent_pos, parent, height + 1));
+	}
+
+	None
+}
+
+fn sibling<'a, T, I>(queue: &mut VecDeque<(u64, T, u8)>, pos: u64, proof: &mut I) -> Option<T>
+where
+	T: 'a + Clone,
+	I: Iterator<Item = &'a T>,
+{
+	if queue.front().is_some_and(|(p, ..)| *p == pos) {
+		queue.pop_front().map(|(_, s, _)| s)
+	} else {
+		proof.next().cloned()
+	}
+}
+
+fn merge<H>(hasher: &H, left: &H::Output, right: &H::Output) -> H::Output
+where
+	H: Hasher,
+{
+	let mut combined = Vec::with_capacity(left.as_ref().len() + right.as_ref().len());
+
+	combined.extend_from_slice(left.as_ref());
+	combined.extend_from_slice(right.as_ref());
+
+	hasher.hash(&combined)
+}
+
+fn peak_map(mmr_size: u64) -> u64 {
+	if mmr_size == 0 {
+		return 0;
+	}
+
+	let mut pos = mmr_size;
+	let mut peak_size = u64::MAX >> pos.leading_zeros();
+	let mut peak_map = 0;
+
+	while peak_size > 0 {
+		peak_map <<= 1;
+
+		if pos >= peak_size {
+			pos -= peak_size;
+			peak_map |= 1;
+		}
+
+		peak_size >>= 1;
+	}
+
+	peak_map
+}
+
+fn pos_height_in_tree(mut pos: u64) -> u8 {
+	if pos == 0 {
+		return 0;
+	}
+
+	let mut peak_size = u64::MAX >> pos.leading_zeros();
+
+	while peak_size > 0 {
+		if pos >= peak_size {
+			pos -= peak_size;
+		}
+
+		peak_size >>= 1;
+	}
+
+	pos as u8
+}
+
+fn parent_offset(height: u8) -> u64 {
+	2 << height
+}
+
+fn sibling_offset(height: u8) -> u64 {
+	(2 << height) - 1
+}
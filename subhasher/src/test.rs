@@ -120,3 +120,476 @@ fn sha2_256_should_work() {
 		]
 	)
 }
+
+#[test]
+fn concat_into_should_work() {
+	let mut buffer = b"prefix".to_vec();
+
+	blake2_128_concat_into(DATA, &mut buffer);
+	twox64_concat_into(DATA, &mut buffer);
+
+	assert_eq!(buffer[..6], *b"prefix");
+	assert_eq!(buffer[6..54], blake2_128_concat(DATA));
+	assert_eq!(buffer[54..], twox64_concat(DATA));
+}
+
+#[test]
+fn hasher_should_work() {
+	assert_eq!(Blake2_128.hash(DATA), blake2_128(DATA));
+	assert_eq!(Twox64Concat.hash(DATA), twox64_concat(DATA));
+	assert_eq!(Identity.hash(DATA), DATA);
+
+	let mut buffer = Vec::new();
+
+	Blake2_128Concat.hash_into(DATA, &mut buffer);
+	Sha2_256.hash_into(DATA, &mut buffer);
+
+	assert_eq!(buffer[..48], blake2_128_concat(DATA));
+	assert_eq!(buffer[48..], sha2_256(DATA));
+}
+
+#[test]
+fn hash_many_should_work() {
+	let inputs = (0..3_000_u32).map(u32::to_le_bytes).collect::<Vec<_>>();
+	let expected = inputs.iter().map(blake2_128_concat).collect::<Vec<_>>();
+
+	assert_eq!(hash_many(Blake2_128Concat, &inputs), expected);
+	assert_eq!(inputs.iter().hash_many(Blake2_128Concat).collect::<Vec<_>>(), expected);
+	assert!(hash_many(Twox128, &Vec::<Vec<u8>>::new()).is_empty());
+	#[cfg(feature = "rayon")]
+	assert_eq!(inputs.iter().par_hash_many(Blake2_128Concat).collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn merkle_root_should_work() {
+	use binary_merkle_tree::*;
+
+	assert_eq!(merkle_root(Keccak256, Vec::<Vec<u8>>::new()), [0; 32]);
+	assert_eq!(
+		merkle_root(
+			Keccak256,
+			[array_bytes::hex2bytes_unchecked("E04CC55ebEE1cBCE552f250e85c57B70B2E2625b")]
+		),
+		array_bytes::hex2array_unchecked::<_, 32>(
+			"aeb47a269393297f4b0a3c9c9cfd00c7a4195255274cf39d83dabc2fcc9ff3d7"
+		)
+	);
+	assert_eq!(
+		merkle_root(
+			Keccak256,
+			[
+				array_bytes::hex2bytes_unchecked("E04CC55ebEE1cBCE552f250e85c57B70B2E2625b"),
+				array_bytes::hex2bytes_unchecked("25451A4de12dcCc2D166922fA938E900fCc4ED24"),
+			]
+		),
+		array_bytes::hex2array_unchecked::<_, 32>(
+			"697ea2a8fe5b03468548a7a413424a6292ab44a82a6f5cc594c3fa7dda7ce402"
+		)
+	);
+
+	[
+		("aff1208e69c9e8be9b584b07ebac4e48a1ee9d15ce3afe20b77a4d29e4175aa3", &["a", "b", "c"][..]),
+		("b8912f7269068901f231a965adfefbc10f0eedcfa61852b103efd54dac7db3d7", &["a", "b", "a"]),
+		("dc8e73fe6903148ff5079baecc043983625c23b39f31537e322cd0deee09fa9c", &["a", "b", "a", "b"]),
+		(
+			"fb3b3be94be9e983ba5e094c9c51a7d96a4fa2e5d8e891df00ca89ba05bb1239",
+			&["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"],
+		),
+	]
+	.into_iter()
+	.for_each(|(root, leaves)| {
+		assert_eq!(merkle_root(Keccak256, leaves), array_bytes::hex2array_unchecked::<_, 32>(root))
+	});
+}
+
+#[test]
+fn merkle_proof_should_work() {
+	use binary_merkle_tree::*;
+
+	let leaves = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"];
+	let proof = merkle_proof(Keccak256, leaves, 6).unwrap();
+
+	assert_eq!(
+		proof.root,
+		array_bytes::hex2array_unchecked::<_, 32>(
+			"fb3b3be94be9e983ba5e094c9c51a7d96a4fa2e5d8e891df00ca89ba05bb1239"
+		)
+	);
+	assert_eq!(
+		proof.proof,
+		[
+			"a766932420cc6e9072394bef2c036ad8972c44696fee29397bd5e2c06001f615",
+			"f0b49bb4b0d9396e0315755ceafaa280707b32e75e6c9053f5cdf2679dcd5c6a",
+			"68203f90e9d07dc5859259d7536e87a6ba9d345f2552b5b9de2999ddce9ce1bf",
+			"00f1ab17c0a22cac8888dcacf2506f283715df19c6155fecd32865fa76fe0b4c",
+		]
+		.map(array_bytes::hex2array_unchecked::<_, 32>)
+	);
+	assert_eq!(proof.number_of_leaves, 10);
+	assert_eq!(proof.leaf, "g");
+	assert!(merkle_proof(Keccak256, leaves, 10).is_none());
+	assert_eq!(merkle_proof_raw(Keccak256, leaves.map(keccak256), 6).unwrap().proof, proof.proof);
+}
+
+#[test]
+fn verify_proof_should_work() {
+	use binary_merkle_tree::*;
+
+	(1..=33).for_each(|n| {
+		let leaves = (0..n).map(|i: u32| i.to_le_bytes()).collect::<Vec<_>>();
+
+		(0..n).for_each(|i| {
+			let p = merkle_proof(Keccak256, &leaves, i).unwrap();
+
+			assert!(verify_proof(Keccak256, &p.root, p.proof.clone(), n, i, p.leaf));
+			assert!(verify_proof(
+				Keccak256,
+				&p.root,
+				p.proof.clone(),
+				n,
+				i,
+				Leaf::Hash(keccak256(p.leaf))
+			));
+			// Wrong index.
+			assert!(!verify_proof(Keccak256, &p.root, p.proof.clone(), n, n, p.leaf));
+			// Wrong leaf.
+			assert!(!verify_proof(Keccak256, &p.root, p.proof.clone(), n, i, &[0_u8]));
+
+			// Missing or redundant sibling.
+			if let Some(sibling) = p.proof.last() {
+				let mut proof = p.proof.clone();
+
+				proof.push(*sibling);
+
+				assert!(!verify_proof(Keccak256, &p.root, proof, n, i, p.leaf));
+				assert!(!verify_proof(Keccak256, &p.root, p.proof[1..].to_vec(), n, i, p.leaf));
+			}
+		});
+	});
+}
+
+// Compact encoded leaf of `pallet-mmr`'s mock runtime.
+//
+// `(DataOrHash::Hash((parent_number, parent_hash)), DataOrHash::Hash(LeafData { a, b: [] }))`
+fn mmr_leaf(index: u64) -> Vec<u8> {
+	let mut parent = index.to_le_bytes().to_vec();
+	let mut data = (index + 1).to_le_bytes().to_vec();
+
+	parent.extend_from_slice(&[index as u8 + 1; 32]);
+	data.push(0);
+
+	[&[1][..], &keccak256(parent), &[1], &keccak256(data)].concat()
+}
+
+fn h256(hex: &str) -> [u8; 32] {
+	array_bytes::hex2array_unchecked(hex)
+}
+
+#[test]
+fn mmr_root_should_work() {
+	let leaves = (0..7).map(mmr_leaf).collect::<Vec<_>>();
+
+	assert_eq!(
+		keccak256(&leaves[0]),
+		h256("4320435e8c3318562dba60116bdbcc0b82ffcecb9bb39aae3300cfda3ad0b8b0")
+	);
+	assert_eq!(mmr::root(Keccak256, Vec::<Vec<u8>>::new()), None);
+	assert_eq!(
+		mmr::root(Keccak256, &leaves[..1]),
+		Some(h256("4320435e8c3318562dba60116bdbcc0b82ffcecb9bb39aae3300cfda3ad0b8b0"))
+	);
+	assert_eq!(
+		mmr::root(Keccak256, &leaves[..2]),
+		Some(h256("672c04a9cd05a644789d769daa552d35d8de7c33129f8a7cbf49e595234c4854"))
+	);
+	assert_eq!(
+		mmr::peaks(Keccak256, &leaves),
+		[
+			h256("ae88a0825da50e953e7a359c55fe13c8015e48d03d301b8bdfc9193874da9252"),
+			h256("7e4316ae2ebf7c3b6821cb3a46ca8b7a4f9351a9b40fcf014bb0a4fd8e8f29da"),
+			h256("611c2174c6164952a66d985cfe1ec1a623794393e3acff96b136d198f37a648c"),
+		]
+	);
+	assert_eq!(
+		mmr::root(Keccak256, &leaves),
+		Some(h256("e45e25259f7930626431347fa4dd9aae7ac83b4966126d425ca70ab343709d2c"))
+	);
+}
+
+#[test]
+fn mmr_helpers_should_work() {
+	assert_eq!(
+		[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 21].map(mmr::leaf_count_to_mmr_size),
+		[0, 1, 3, 4, 7, 8, 10, 11, 15, 16, 18, 19, 22, 23, 25, 26, 39].map(Some)
+	);
+	assert_eq!([0, 1, 2, 3, 4, 5, 6].map(mmr::leaf_index_to_pos), [0, 1, 3, 4, 7, 8, 10].map(Some));
+	assert_eq!(mmr::leaf_index_to_pos(u64::MAX), None);
+	assert_eq!(mmr::leaf_index_to_mmr_size(u64::MAX / 2), None);
+	assert_eq!(mmr::leaf_count_to_mmr_size(u64::MAX), None);
+	assert_eq!(mmr::peak_positions(11), [6, 9, 10]);
+	assert_eq!(mmr::peak_positions(19), [14, 17, 18]);
+	assert!(mmr::is_valid_mmr_size(19));
+	assert!(!mmr::is_valid_mmr_size(6));
+}
+
+#[test]
+fn verify_leaves_proof_should_work() {
+	let leaves = (0..7).map(mmr_leaf).collect::<Vec<_>>();
+	let root = mmr::root(Keccak256, &leaves).unwrap();
+	let proof = |leaf_indices: &[u64], leaf_count, items: &[&str]| mmr::LeafProof {
+		leaf_indices: leaf_indices.to_vec(),
+		leaf_count,
+		items: items.iter().map(|i| h256(i)).collect(),
+	};
+	let proof_0 = proof(
+		&[0],
+		7,
+		&[
+			"ad4cbc033833612ccd4626d5f023b9dfc50a35e838514dd1f3c86f8506728705",
+			"cb24f4614ad5b2a5430344c99545b421d9af83c46fd632d70a332200884b4d46",
+			"dca421199bdcc55bb773c6b6967e8d16675de69062b52285ca63685241fdf626",
+		],
+	);
+	let proof_4 = proof(
+		&[4],
+		7,
+		&[
+			"ae88a0825da50e953e7a359c55fe13c8015e48d03d301b8bdfc9193874da9252",
+			"8ed25570209d8f753d02df07c1884ddb36a3d9d4770e4608b188322151c657fe",
+			"611c2174c6164952a66d985cfe1ec1a623794393e3acff96b136d198f37a648c",
+		],
+	);
+	let proof_6 = proof(
+		&[6],
+		7,
+		&[
+			"ae88a0825da50e953e7a359c55fe13c8015e48d03d301b8bdfc9193874da9252",
+			"7e4316ae2ebf7c3b6821cb3a46ca8b7a4f9351a9b40fcf014bb0a4fd8e8f29da",
+		],
+	);
+	let proof_0_4_5 = proof(
+		&[0, 4, 5],
+		7,
+		&[
+			"ad4cbc033833612ccd4626d5f023b9dfc50a35e838514dd1f3c86f8506728705",
+			"cb24f4614ad5b2a5430344c99545b421d9af83c46fd632d70a332200884b4d46",
+			"611c2174c6164952a66d985cfe1ec1a623794393e3acff96b136d198f37a648c",
+		],
+	);
+
+	assert!(mmr::verify_leaves_proof(Keccak256, &root, &leaves[..1], &proof_0));
+	assert!(mmr::verify_leaves_proof(Keccak256, &root, &leaves[4..5], &proof_4));
+	assert!(mmr::verify_leaves_proof(Keccak256, &root, &leaves[6..], &proof_6));
+	assert!(mmr::verify_leaves_proof(
+		Keccak256,
+		&root,
+		[&leaves[0], &leaves[4], &leaves[5]],
+		&proof_0_4_5
+	));
+	assert!(mmr::verify_leaves_proof_raw(
+		Keccak256,
+		&root,
+		vec![keccak256(&leaves[0]), keccak256(&leaves[4]), keccak256(&leaves[5])],
+		&proof_0_4_5
+	));
+
+	// Historical proofs.
+	assert!(mmr::verify_leaves_proof(
+		Keccak256,
+		&mmr::root(Keccak256, &leaves[..6]).unwrap(),
+		[&leaves[0], &leaves[4], &leaves[5]],
+		&proof(
+			&[0, 4, 5],
+			6,
+			&[
+				"ad4cbc033833612ccd4626d5f023b9dfc50a35e838514dd1f3c86f8506728705",
+				"cb24f4614ad5b2a5430344c99545b421d9af83c46fd632d70a332200884b4d46",
+			]
+		)
+	));
+	assert!(mmr::verify_leaves_proof(
+		Keccak256,
+		&mmr::root(Keccak256, &leaves[..1]).unwrap(),
+		&leaves[..1],
+		&proof(&[0], 1, &[])
+	));
+
+	// Wrong leaf.
+	assert!(!mmr::verify_leaves_proof(Keccak256, &root, &leaves[1..2], &proof_0));
+	// Wrong leaf count.
+	assert!(!mmr::verify_leaves_proof(Keccak256, &root, &leaves[..2], &proof_0));
+	// Wrong MMR size.
+	assert!(!mmr::verify_leaves_proof(
+		Keccak256,
+		&root,
+		&leaves[..1],
+		&mmr::LeafProof { leaf_count: 8, ..proof_0.clone() }
+	));
+	// Leaf index out of range.
+	assert!(!mmr::verify_leaves_proof(
+		Keccak256,
+		&root,
+		&leaves[..1],
+		&mmr::LeafProof { leaf_indices: vec![7], ..proof_0.clone() }
+	));
+	// Overflowing leaf index and leaf count.
+	assert!(!mmr::verify_leaves_proof(
+		Keccak256,
+		&root,
+		&leaves[..1],
+		&mmr::LeafProof { leaf_indices: vec![u64::MAX], leaf_count: u64::MAX, ..proof_0.clone() }
+	));
+	assert!(!mmr::verify_leaves_proof(
+		Keccak256,
+		&root,
+		&leaves[..1],
+		&mmr::LeafProof { leaf_count: u64::MAX, ..proof_0.clone() }
+	));
+	// Redundant item.
+	assert!(!mmr::verify_leaves_proof(
+		Keccak256,
+		&root,
+		&leaves[..1],
+		&mmr::LeafProof {
+			items: [proof_0.items.clone(), vec![[0; 32]]].concat(),
+			..proof_0.clone()
+		}
+	));
+}
+
+// A tiny xorshift generator, enough for producing the fuzzing inputs.
+#[cfg(any(feature = "blake2b-simd", feature = "xxhash-rust"))]
+fn random_inputs() -> impl Iterator<Item = Vec<u8>> {
+	let mut state = 0x2545_f491_4f6c_dd1d_u64;
+	let mut next = move || {
+		state ^= state << 13;
+		state ^= state >> 7;
+		state ^= state << 17;
+
+		state
+	};
+
+	(0..256).map(move |_| {
+		let len = next() as usize % 1024;
+
+		(0..len).map(|_| next() as u8).collect()
+	})
+}
+
+#[cfg(feature = "blake2b-simd")]
+#[test]
+fn blake2_backends_should_be_equivalent() {
+	random_inputs().for_each(|data| {
+		assert_eq!(
+			backend::blake2::blake2_rfc::<16>(&data),
+			backend::blake2::blake2b_simd::<16>(&data)
+		);
+		assert_eq!(
+			backend::blake2::blake2_rfc::<32>(&data),
+			backend::blake2::blake2b_simd::<32>(&data)
+		);
+		assert_eq!(
+			backend::blake2::blake2_rfc::<64>(&data),
+			backend::blake2::blake2b_simd::<64>(&data)
+		);
+	});
+}
+
+#[cfg(feature = "xxhash-rust")]
+#[test]
+fn twox_backends_should_be_equivalent() {
+	random_inputs().for_each(|data| {
+		(0..4).for_each(|seed| {
+			assert_eq!(
+				backend::twox::twox_hash(&data, seed),
+				backend::twox::xxhash_rust(&data, seed)
+			);
+		});
+	});
+}
+
+#[test]
+fn sha2_512_should_work() {
+	// FIPS 180-2.
+	assert_eq!(
+		sha2_512(b"abc"),
+		array_bytes::hex2array_unchecked::<_, 64>("ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f")
+	);
+	assert_eq!(
+		sha2_512(b""),
+		array_bytes::hex2array_unchecked::<_, 64>("cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e")
+	);
+}
+
+#[test]
+fn sha3_256_should_work() {
+	// FIPS 202.
+	assert_eq!(
+		sha3_256(b"abc"),
+		array_bytes::hex2array_unchecked::<_, 32>(
+			"3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"
+		)
+	);
+	assert_eq!(
+		sha3_256(b""),
+		array_bytes::hex2array_unchecked::<_, 32>(
+			"a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a"
+		)
+	);
+	assert_ne!(sha3_256(DATA), keccak256(DATA));
+}
+
+#[test]
+fn ripemd160_should_work() {
+	// <https://homes.esat.kuleuven.be/~bosselae/ripemd160.html>.
+	assert_eq!(
+		ripemd160(b""),
+		array_bytes::hex2array_unchecked::<_, 20>("9c1185a5c5e9fc54612808977ee8f548b2258d31")
+	);
+	assert_eq!(
+		ripemd160(b"abc"),
+		array_bytes::hex2array_unchecked::<_, 20>("8eb208f7e05d987a9b044a8e98c6b087f15a0bfc")
+	);
+	assert_eq!(
+		ripemd160(b"message digest"),
+		array_bytes::hex2array_unchecked::<_, 20>("5d0689ef49d2fae572b881b123a85ffa21595f36")
+	);
+}
+
+#[test]
+fn blake2f_should_work() {
+	// EIP-152 test vectors.
+	const INPUT: &str = "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b61626300000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000001";
+
+	let input = |rounds: &str, f: &str| {
+		array_bytes::hex2bytes_unchecked(format!("{rounds}{}{f}", &INPUT[..INPUT.len() - 2]))
+	};
+
+	// Vector 0, 1, 2 and 3.
+	assert_eq!(blake2f([]), None);
+	assert_eq!(blake2f(input("000000", "01")), None);
+	assert_eq!(blake2f(input("0000000c00", "01")), None);
+	assert_eq!(blake2f(input("0000000c", "02")), None);
+	// Vector 4.
+	assert_eq!(
+		blake2f(input("0000000c", "01")).unwrap(),
+		array_bytes::hex2array_unchecked::<_, 64>("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923")
+	);
+	// It is the single block BLAKE2b-512 of `abc`.
+	assert_eq!(blake2f(input("0000000c", "01")).unwrap(), blake2_512(b"abc"));
+	// Vector 5.
+	assert_eq!(
+		blake2f(input("0000000c", "00")).unwrap(),
+		array_bytes::hex2array_unchecked::<_, 64>("75ab69d3190a562c51aef8d88f1c2775876944407270c42c9844252c26d2875298743e7f6d5ea2f2d3e8d226039cd31b4e426ac4f2d3d666a610c2116fde4735")
+	);
+	// Vector 6.
+	assert_eq!(
+		blake2f(input("00000001", "01")).unwrap(),
+		array_bytes::hex2array_unchecked::<_, 64>("b63a380cb2897d521994a85234ee2c181b5f844d2c624c002677e9703449d2fba551b3a8333bcdf5f2f7e08993d53923de3d64fcc68c034e717b9293fed7a421")
+	);
+	// Vector 8.
+	assert_eq!(
+		blake2f(input("00000000", "01")).unwrap(),
+		array_bytes::hex2array_unchecked::<_, 64>("08c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d282e6ad7f520e511f6c3e2b8c68059b9442be0454267ce079217e1319cde05b")
+	);
+}
@@ -0,0 +1,399 @@
+//! Structured diff of two runtime metadata, with the breaking change classification.
+//!
+//! A change is breaking if the clients built against the old metadata could encode or decode
+//! wrongly, such as a removed item, a moved index, a changed type or hasher.
+//! Additions and value-only changes are non-breaking.
+
+// std
+use std::{
+	collections::BTreeSet,
+	fmt::{Display, Formatter, Result as FmtResult},
+};
+// crates.io
+use fxhash::FxHashMap;
+use serde::Serialize;
+// substrate-minimal
+use super::same_shape;
+use crate::metadata::*;
+
+/// Compare two [`Metadata`] and return the changes from `a` to `b`.
+///
+/// The changes are sorted by the pallet name, then by the item kind and name.
+/// The types are compared by their shapes if both metadata have a type registry, or by the type
+/// names if neither has. Otherwise, the type system was changed and the types are not compared.
+pub fn diff(a: &Metadata, b: &Metadata) -> MetadataDiff {
+	let mut differ = Differ { a, b, changes: Vec::new() };
+
+	for name in union(&a.pallets, &b.pallets) {
+		match (a.pallets.get(name), b.pallets.get(name)) {
+			(Some(a), Some(b)) => differ.pallet(name, a, b),
+			(a, _) => differ.push(
+				name,
+				None,
+				if a.is_some() { ChangeKind::Removed } else { ChangeKind::Added },
+			),
+		}
+	}
+
+	MetadataDiff {
+		changes: differ.changes,
+		type_system_changed: (a.version < 14) != (b.version < 14),
+	}
+}
+
+/// Changes between two runtime metadata.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct MetadataDiff {
+	/// Changes.
+	pub changes: Vec<Change>,
+	/// Whether only one of the metadata has a type registry, which means the type system was
+	/// changed (V13 to V14) and the types are not compared.
+	pub type_system_changed: bool,
+}
+impl MetadataDiff {
+	/// Check if there is no change.
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty() && !self.type_system_changed
+	}
+
+	/// Check if there is any breaking change.
+	pub fn is_breaking(&self) -> bool {
+		self.changes.iter().any(|c| c.breaking)
+	}
+
+	/// Get the breaking changes.
+	pub fn breaking_changes(&self) -> impl Iterator<Item = &Change> {
+		self.changes.iter().filter(|c| c.breaking)
+	}
+
+	/// Convert the diff into a JSON value.
+	pub fn to_json(&self) -> serde_json::Value {
+		serde_json::to_value(self).expect("diff must be serializable; qed")
+	}
+}
+impl Display for MetadataDiff {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		if self.is_empty() {
+			return write!(f, "no changes");
+		}
+		if self.type_system_changed {
+			write!(f, "type system changed, the types are not compared")?;
+
+			if !self.changes.is_empty() {
+				writeln!(f)?;
+			}
+		}
+
+		for (i, c) in self.changes.iter().enumerate() {
+			if i != 0 {
+				writeln!(f)?;
+			}
+
+			write!(f, "{c}")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Change of a pallet or a pallet item.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Change {
+	/// Pallet name.
+	pub pallet: String,
+	/// Pallet item, [`None`] if this is a pallet level change.
+	pub item: Option<PalletItem>,
+	/// Change kind.
+	#[serde(flatten)]
+	pub kind: ChangeKind,
+	/// Whether this is a breaking change for the clients.
+	pub breaking: bool,
+}
+impl Display for Change {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		let level = if self.breaking { "breaking" } else { "non-breaking" };
+
+		match &self.item {
+			Some(i) => write!(f, "[{level}] {}::{i}: {}", self.pallet, self.kind),
+			None => write!(f, "[{level}] {}: {}", self.pallet, self.kind),
+		}
+	}
+}
+
+/// Pallet item.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "name", rename_all = "snake_case")]
+pub enum PalletItem {
+	Call(String),
+	Event(String),
+	Error(String),
+	Constant(String),
+	Storage(String),
+}
+impl Display for PalletItem {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match self {
+			Self::Call(n) => write!(f, "{n} (call)"),
+			Self::Event(n) => write!(f, "{n} (event)"),
+			Self::Error(n) => write!(f, "{n} (error)"),
+			Self::Constant(n) => write!(f, "{n} (constant)"),
+			Self::Storage(n) => write!(f, "{n} (storage)"),
+		}
+	}
+}
+
+/// Change kind.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChangeKind {
+	/// The pallet or item was added.
+	Added,
+	/// The pallet or item was removed.
+	Removed,
+	/// The pallet index or the call, event or error index was moved.
+	IndexChanged {
+		#[allow(missing_docs)]
+		from: u8,
+		#[allow(missing_docs)]
+		to: u8,
+	},
+	/// The storage prefix of the pallet was changed.
+	PrefixChanged {
+		#[allow(missing_docs)]
+		from: String,
+		#[allow(missing_docs)]
+		to: String,
+	},
+	/// The call, event or error fields were changed.
+	FieldsChanged,
+	/// The constant type or the storage key or value type was changed.
+	TypeChanged,
+	/// The storage hashers were changed, empty if this is a plain storage.
+	HashersChanged {
+		#[allow(missing_docs)]
+		from: Vec<String>,
+		#[allow(missing_docs)]
+		to: Vec<String>,
+	},
+	/// The storage modifier was changed.
+	ModifierChanged {
+		#[allow(missing_docs)]
+		from: String,
+		#[allow(missing_docs)]
+		to: String,
+	},
+	/// The constant value was changed.
+	ValueChanged,
+	/// The storage default value was changed.
+	DefaultChanged,
+}
+impl ChangeKind {
+	/// Check if this is a breaking change for the clients.
+	pub fn is_breaking(&self) -> bool {
+		!matches!(self, Self::Added | Self::ValueChanged | Self::DefaultChanged)
+	}
+}
+impl Display for ChangeKind {
+	fn fmt(&self, f: &mut Formatter) -> FmtResult {
+		match self {
+			Self::Added => write!(f, "added"),
+			Self::Removed => write!(f, "removed"),
+			Self::IndexChanged { from, to } => write!(f, "index changed from {from} to {to}"),
+			Self::PrefixChanged { from, to } =>
+				write!(f, "storage prefix changed from {from} to {to}"),
+			Self::FieldsChanged => write!(f, "fields changed"),
+			Self::TypeChanged => write!(f, "type changed"),
+			Self::HashersChanged { from, to } =>
+				write!(f, "hashers changed from [{}] to [{}]", from.join(", "), to.join(", ")),
+			Self::ModifierChanged { from, to } => write!(f, "modifier changed from {from} to {to}"),
+			Self::ValueChanged => write!(f, "value changed"),
+			Self::DefaultChanged => write!(f, "default value changed"),
+		}
+	}
+}
+
+struct Differ<'a> {
+	a: &'a Metadata,
+	b: &'a Metadata,
+	changes: Vec<Change>,
+}
+impl Differ<'_> {
+	fn push(&mut self, pallet: &str, item: Option<PalletItem>, kind: ChangeKind) {
+		let breaking = kind.is_breaking();
+
+		self.changes.push(Change { pallet: pallet.into(), item, kind, breaking });
+	}
+
+	// Compare the types, by the type names if both metadata have no type registry (legacy
+	// metadata).
+	fn same_type(&self, a: (u32, Option<&str>), b: (u32, Option<&str>)) -> bool {
+		match (self.a.version < 14, self.b.version < 14) {
+			(true, true) => a.1 == b.1,
+			(false, false) => same_shape(&self.a.types, &self.b.types, a.0, b.0),
+			// The type system was changed, which is reported once instead.
+			_ => true,
+		}
+	}
+
+	fn pallet(&mut self, name: &str, a: &PalletMetadata, b: &PalletMetadata) {
+		if a.index != b.index {
+			self.push(name, None, ChangeKind::IndexChanged { from: a.index, to: b.index });
+		}
+		if let (Some(a), Some(b)) = (&a.storages, &b.storages) {
+			if a.prefix != b.prefix {
+				self.push(
+					name,
+					None,
+					ChangeKind::PrefixChanged { from: a.prefix.clone(), to: b.prefix.clone() },
+				);
+			}
+		}
+
+		self.variants(name, a.calls.as_ref(), b.calls.as_ref(), PalletItem::Call);
+		self.variants(name, a.events.as_ref(), b.events.as_ref(), PalletItem::Event);
+		self.variants(name, a.error.as_ref(), b.error.as_ref(), PalletItem::Error);
+		self.constants(name, &a.constants, &b.constants);
+		self.storages(name, a.storages.as_ref(), b.storages.as_ref());
+	}
+
+	fn variants(
+		&mut self,
+		pallet: &str,
+		a: Option<&PalletEnumMetadata>,
+		b: Option<&PalletEnumMetadata>,
+		item: fn(String) -> PalletItem,
+	) {
+		let empty = FxHashMap::default();
+		let a = a.map_or(&empty, |e| &e.variants);
+		let b = b.map_or(&empty, |e| &e.variants);
+
+		for name in union(a, b) {
+			let (a, b) = match (a.get(name), b.get(name)) {
+				(Some(a), Some(b)) => (a, b),
+				(a, _) => {
+					let kind = if a.is_some() { ChangeKind::Removed } else { ChangeKind::Added };
+
+					self.push(pallet, Some(item(name.into())), kind);
+
+					continue;
+				},
+			};
+
+			if a.index != b.index {
+				self.push(
+					pallet,
+					Some(item(name.into())),
+					ChangeKind::IndexChanged { from: a.index, to: b.index },
+				);
+			}
+
+			let same_fields = a.fields.len() == b.fields.len()
+				&& a.fields.iter().zip(&b.fields).all(|(a, b)| {
+					a.name == b.name
+						&& self.same_type(
+							(a.r#type, a.type_name.as_deref()),
+							(b.r#type, b.type_name.as_deref()),
+						)
+				});
+
+			if !same_fields {
+				self.push(pallet, Some(item(name.into())), ChangeKind::FieldsChanged);
+			}
+		}
+	}
+
+	fn constants(
+		&mut self,
+		pallet: &str,
+		a: &FxHashMap<String, ConstantMetadata>,
+		b: &FxHashMap<String, ConstantMetadata>,
+	) {
+		for name in union(a, b) {
+			let item = Some(PalletItem::Constant(name.into()));
+			let kind = match (a.get(name), b.get(name)) {
+				(Some(a), Some(b)) =>
+					if !self.same_type(
+						(a.r#type, a.type_name.as_deref()),
+						(b.r#type, b.type_name.as_deref()),
+					) {
+						ChangeKind::TypeChanged
+					} else if a.value != b.value {
+						ChangeKind::ValueChanged
+					} else {
+						continue;
+					},
+				(Some(_), None) => ChangeKind::Removed,
+				_ => ChangeKind::Added,
+			};
+
+			self.push(pallet, item, kind);
+		}
+	}
+
+	fn storages(
+		&mut self,
+		pallet: &str,
+		a: Option<&PalletStorageMetadata>,
+		b: Option<&PalletStorageMetadata>,
+	) {
+		let empty = FxHashMap::default();
+		let a = a.map_or(&empty, |s| &s.entries);
+		let b = b.map_or(&empty, |s| &s.entries);
+		let hashers = |e: &StorageEntryMetadata| match &e.r#type {
+			StorageEntryType::Plain => Vec::new(),
+			StorageEntryType::Map(hs) => hs.iter().map(|h| format!("{h:?}")).collect(),
+		};
+
+		for name in union(a, b) {
+			let item = || Some(PalletItem::Storage(name.into()));
+			let (a, b) = match (a.get(name), b.get(name)) {
+				(Some(a), Some(b)) => (a, b),
+				(a, _) => {
+					self.push(
+						pallet,
+						item(),
+						if a.is_some() { ChangeKind::Removed } else { ChangeKind::Added },
+					);
+
+					continue;
+				},
+			};
+			let (a_hashers, b_hashers) = (hashers(a), hashers(b));
+			let (a_keys, b_keys) = (a.key_type_names.join(", "), b.key_type_names.join(", "));
+			let same_key = a_hashers.is_empty() && b_hashers.is_empty()
+				|| self.same_type((a.key_type, Some(&a_keys)), (b.key_type, Some(&b_keys)));
+			let same_value = self.same_type(
+				(a.value_type, a.value_type_name.as_deref()),
+				(b.value_type, b.value_type_name.as_deref()),
+			);
+
+			if a_hashers != b_hashers {
+				self.push(
+					pallet,
+					item(),
+					ChangeKind::HashersChanged { from: a_hashers, to: b_hashers },
+				);
+			}
+			if !same_key || !same_value {
+				self.push(pallet, item(), ChangeKind::TypeChanged);
+			}
+			if a.modifier != b.modifier {
+				self.push(
+					pallet,
+					item(),
+					ChangeKind::ModifierChanged {
+						from: format!("{:?}", a.modifier),
+						to: format!("{:?}", b.modifier),
+					},
+				);
+			} else if same_value && a.default != b.default {
+				self.push(pallet, item(), ChangeKind::DefaultChanged);
+			}
+		}
+	}
+}
+
+fn union<'a, V>(a: &'a FxHashMap<String, V>, b: &'a FxHashMap<String, V>) -> BTreeSet<&'a String> {
+	a.keys().chain(b.keys()).collect()
+}
@@ -0,0 +1,211 @@
+//! Utilities for comparing the metadata of two Substrate runtimes.
+
+mod diff;
+pub use diff::*;
+
+// std
+use std::any::TypeId;
+// crates.io
+use frame_metadata::v14::{StorageEntryMetadata, StorageEntryType};
+use fxhash::FxHashMap;
+use scale_info::{form::PortableForm, interner::UntrackedSymbol, *};
+
+/// Compare two [`StorageEntryMetadata`] and return the [`bool`] result.
+pub fn storage_entry(
+	a_types: &PortableRegistry,
+	b_types: &PortableRegistry,
+	a: &StorageEntryMetadata<PortableForm>,
+	b: &StorageEntryMetadata<PortableForm>,
+) -> bool {
+	a.name == b.name
+		&& a.modifier == b.modifier
+		&& a.default == b.default
+		&& a.docs == b.docs
+		&& storage_entry_type(a_types, b_types, &a.ty, &b.ty)
+}
+
+/// Compare two [`StorageEntryType`] and return the [`bool`] result.
+pub fn storage_entry_type(
+	a_types: &PortableRegistry,
+	b_types: &PortableRegistry,
+	a: &StorageEntryType<PortableForm>,
+	b: &StorageEntryType<PortableForm>,
+) -> bool {
+	let mut c = Comparator::new(a_types, b_types, true);
+
+	match (a, b) {
+		(StorageEntryType::Plain(a), StorageEntryType::Plain(b)) => c.symbol(a.id, b.id),
+		(
+			StorageEntryType::Map { hashers: a_hashers, key: a_key, value: a_value },
+			StorageEntryType::Map { hashers: b_hashers, key: b_key, value: b_value },
+		) =>
+			a_hashers == b_hashers
+				&& c.symbol(a_key.id, b_key.id)
+				&& c.symbol(a_value.id, b_value.id),
+		_ => false,
+	}
+}
+
+/// Compare two [`UntrackedSymbol`] and return the [`bool`] result.
+pub fn untracked_symbol(
+	a_types: &PortableRegistry,
+	b_types: &PortableRegistry,
+	a: &UntrackedSymbol<TypeId>,
+	b: &UntrackedSymbol<TypeId>,
+) -> bool {
+	Comparator::new(a_types, b_types, true).symbol(a.id, b.id)
+}
+
+/// Compare two [`Type`] and return the [`bool`] result.
+pub fn r#type(
+	a_types: &PortableRegistry,
+	b_types: &PortableRegistry,
+	a: Option<&Type<PortableForm>>,
+	b: Option<&Type<PortableForm>>,
+) -> bool {
+	Comparator::new(a_types, b_types, true).r#type(a, b)
+}
+
+/// Compare two [`TypeParameter`] and return the [`bool`] result.
+pub fn type_params(
+	a_types: &PortableRegistry,
+	b_types: &PortableRegistry,
+	a: &[TypeParameter<PortableForm>],
+	b: &[TypeParameter<PortableForm>],
+) -> bool {
+	Comparator::new(a_types, b_types, true).type_params(a, b)
+}
+
+/// Compare two [`TypeDef`] and return the [`bool`] result.
+pub fn type_def(
+	a_types: &PortableRegistry,
+	b_types: &PortableRegistry,
+	a: &TypeDef<PortableForm>,
+	b: &TypeDef<PortableForm>,
+) -> bool {
+	Comparator::new(a_types, b_types, true).type_def(a, b)
+}
+
+/// Compare two [`Field`] and return the [`bool`] result.
+pub fn fields(
+	a_types: &PortableRegistry,
+	b_types: &PortableRegistry,
+	a: &[Field<PortableForm>],
+	b: &[Field<PortableForm>],
+) -> bool {
+	Comparator::new(a_types, b_types, true).fields(a, b)
+}
+
+/// Compare two [`Variant`] and return the [`bool`] result.
+pub fn variants(
+	a_types: &PortableRegistry,
+	b_types: &PortableRegistry,
+	a: &[Variant<PortableForm>],
+	b: &[Variant<PortableForm>],
+) -> bool {
+	Comparator::new(a_types, b_types, true).variants(a, b)
+}
+
+// Check if two types have the same shape, the paths, docs and names are ignored.
+fn same_shape(a_types: &PortableRegistry, b_types: &PortableRegistry, a: u32, b: u32) -> bool {
+	Comparator::new(a_types, b_types, false).symbol(a, b)
+}
+
+// The type comparator of two registries.
+//
+// A pair of types is assumed to be equal while it is being compared, so the recursive types
+// terminate. Since the result is a conjunction, a wrong assumption always fails the root, so the
+// comparator must not be reused across the comparisons.
+struct Comparator<'a> {
+	a_types: &'a PortableRegistry,
+	b_types: &'a PortableRegistry,
+	// Compare the paths, docs and type names besides the shapes.
+	strict: bool,
+	results: FxHashMap<(u32, u32), bool>,
+}
+impl<'a> Comparator<'a> {
+	fn new(a_types: &'a PortableRegistry, b_types: &'a PortableRegistry, strict: bool) -> Self {
+		Self { a_types, b_types, strict, results: Default::default() }
+	}
+
+	fn symbol(&mut self, a: u32, b: u32) -> bool {
+		if let Some(r) = self.results.get(&(a, b)) {
+			return *r;
+		}
+
+		self.results.insert((a, b), true);
+
+		let r = self.r#type(self.a_types.resolve(a), self.b_types.resolve(b));
+
+		self.results.insert((a, b), r);
+
+		r
+	}
+
+	fn r#type(&mut self, a: Option<&Type<PortableForm>>, b: Option<&Type<PortableForm>>) -> bool {
+		match (a, b) {
+			(Some(a), Some(b)) =>
+				(!self.strict
+					|| a.path == b.path
+						&& a.docs == b.docs
+						&& self.type_params(&a.type_params, &b.type_params))
+					&& self.type_def(&a.type_def, &b.type_def),
+			(a, b) => a.is_none() && b.is_none(),
+		}
+	}
+
+	fn type_params(
+		&mut self,
+		a: &[TypeParameter<PortableForm>],
+		b: &[TypeParameter<PortableForm>],
+	) -> bool {
+		a.len() == b.len()
+			&& a.iter().zip(b).all(|(a, b)| {
+				a.name == b.name
+					&& match (&a.ty, &b.ty) {
+						(Some(a), Some(b)) => self.symbol(a.id, b.id),
+						(a, b) => a.is_none() && b.is_none(),
+					}
+			})
+	}
+
+	fn type_def(&mut self, a: &TypeDef<PortableForm>, b: &TypeDef<PortableForm>) -> bool {
+		match (a, b) {
+			(TypeDef::Composite(a), TypeDef::Composite(b)) => self.fields(&a.fields, &b.fields),
+			(TypeDef::Variant(a), TypeDef::Variant(b)) => self.variants(&a.variants, &b.variants),
+			(TypeDef::Sequence(a), TypeDef::Sequence(b)) =>
+				self.symbol(a.type_param.id, b.type_param.id),
+			(TypeDef::Array(a), TypeDef::Array(b)) =>
+				a.len == b.len && self.symbol(a.type_param.id, b.type_param.id),
+			(TypeDef::Tuple(a), TypeDef::Tuple(b)) =>
+				a.fields.len() == b.fields.len()
+					&& a.fields.iter().zip(&b.fields).all(|(a, b)| self.symbol(a.id, b.id)),
+			(TypeDef::Primitive(a), TypeDef::Primitive(b)) => a == b,
+			(TypeDef::Compact(a), TypeDef::Compact(b)) =>
+				self.symbol(a.type_param.id, b.type_param.id),
+			(TypeDef::BitSequence(a), TypeDef::BitSequence(b)) =>
+				self.symbol(a.bit_order_type.id, b.bit_order_type.id)
+					&& self.symbol(a.bit_store_type.id, b.bit_store_type.id),
+			_ => false,
+		}
+	}
+
+	fn fields(&mut self, a: &[Field<PortableForm>], b: &[Field<PortableForm>]) -> bool {
+		a.len() == b.len()
+			&& a.iter().zip(b).all(|(a, b)| {
+				a.name == b.name
+					&& (!self.strict || a.type_name == b.type_name && a.docs == b.docs)
+					&& self.symbol(a.ty.id, b.ty.id)
+			})
+	}
+
+	fn variants(&mut self, a: &[Variant<PortableForm>], b: &[Variant<PortableForm>]) -> bool {
+		a.len() == b.len()
+			&& a.iter().zip(b).all(|(a, b)| {
+				a.name == b.name
+					&& a.index == b.index
+					&& (!self.strict || a.docs == b.docs)
+					&& self.fields(&a.fields, &b.fields)
+			})
+	}
+}
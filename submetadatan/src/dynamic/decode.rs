@@ -0,0 +1,304 @@
+//! Dynamic SCALE decoding.
+
+// crates.io
+use parity_scale_codec::{Compact, Decode};
+use scale_info::{
+	form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefBitSequence, TypeDefPrimitive,
+};
+use serde_json::{Map, Value};
+// substrate-minimal
+use super::*;
+use crate::{Error, Result};
+
+/// Dynamic SCALE decoder.
+///
+/// Check the [module](super) documentation for the value representation.
+#[derive(Clone, Copy, Debug)]
+pub struct Decoder<'a> {
+	types: &'a PortableRegistry,
+	network: &'a str,
+}
+impl<'a> Decoder<'a> {
+	/// Create a new decoder with the type registry.
+	///
+	/// The SS58 addresses are in the `substrate` network format by default.
+	pub fn new(types: &'a PortableRegistry) -> Self {
+		Self { types, network: "substrate" }
+	}
+
+	/// Set the network of the SS58 addresses, such as `polkadot`.
+	pub fn with_network(mut self, network: &'a str) -> Self {
+		self.network = network;
+
+		self
+	}
+
+	/// Decode a value of the type from the input, the input will be advanced.
+	pub fn decode(&self, type_id: u32, input: &mut &[u8]) -> Result<Value> {
+		self.decode_type(type_id, input, 0)
+			.map_err(|e| e.into_error(self.types, type_id, Error::DynamicDecode))
+	}
+
+	/// Decode a value of the type, the bytes must be consumed entirely.
+	pub fn decode_all(&self, type_id: u32, bytes: &[u8]) -> Result<Value> {
+		let mut input = bytes;
+		let value = self.decode(type_id, &mut input)?;
+
+		if !input.is_empty() {
+			Err(Failure::new(format!("{} trailing bytes", input.len())).into_error(
+				self.types,
+				type_id,
+				Error::DynamicDecode,
+			))?;
+		}
+
+		Ok(value)
+	}
+
+	fn read<T>(&self, id: u32, input: &mut &[u8]) -> Fallible<T>
+	where
+		T: Decode,
+	{
+		T::decode(input)
+			.map_err(|e| Failure::new(format!("expected {}, {e}", type_name(self.types, id))))
+	}
+
+	fn take<'b>(&self, id: u32, len: usize, input: &mut &'b [u8]) -> Fallible<&'b [u8]> {
+		if input.len() < len {
+			Err(Failure::new(format!(
+				"expected {}, {len} bytes required but {} left",
+				type_name(self.types, id),
+				input.len()
+			)))?;
+		}
+
+		let (bytes, rest) = input.split_at(len);
+
+		*input = rest;
+
+		Ok(bytes)
+	}
+
+	fn decode_type(&self, id: u32, input: &mut &[u8], depth: usize) -> Fallible<Value> {
+		if depth >= MAX_DEPTH {
+			Err(Failure::new("reached the maximum depth"))?;
+		}
+
+		let ty =
+			self.types.resolve(id).ok_or_else(|| Failure::new(format!("type #{id} not found")))?;
+
+		if is_account_id(ty) {
+			let public_key = self.read::<[u8; 32]>(id, input)?;
+
+			return subcryptor::ss58_address_of(&public_key, self.network)
+				.map(|(_, a)| Value::String(a))
+				.map_err(|e| Failure::new(e.to_string()));
+		}
+
+		match &ty.type_def {
+			TypeDef::Composite(c) => self.decode_fields(&c.fields, input, depth),
+			TypeDef::Variant(v) => {
+				let index = self.read::<u8>(id, input)?;
+				let variant = v.variants.iter().find(|v| v.index == index).ok_or_else(|| {
+					Failure::new(format!(
+						"unknown variant index {index} of {}",
+						type_name(self.types, id)
+					))
+				})?;
+				let fields = self
+					.decode_fields(&variant.fields, input, depth)
+					.map_err(|e| e.at(&variant.name))?;
+
+				if is_option(ty)
+					&& (variant.fields.is_empty() || !is_wrapped_option(self.types, ty))
+				{
+					Ok(fields)
+				} else if variant.fields.is_empty() {
+					Ok(Value::String(variant.name.clone()))
+				} else {
+					Ok(Value::Object(Map::from_iter([(variant.name.clone(), fields)])))
+				}
+			},
+			TypeDef::Sequence(s) => {
+				let len = self.read::<Compact<u32>>(id, input)?.0 as usize;
+				// Bound the length by the remaining input to prevent the huge allocation, even the
+				// zero sized elements are counted as one byte, such as `Vec<()>`.
+				let max = input.len() / min_size(self.types, s.type_param.id, 0).max(1);
+
+				if len > max {
+					Err(Failure::new(format!(
+						"expected {}, length {len} exceeds the remaining input",
+						type_name(self.types, id)
+					)))?;
+				}
+
+				self.decode_elements(id, s.type_param.id, len, input, depth)
+			},
+			TypeDef::Array(a) =>
+				self.decode_elements(id, a.type_param.id, a.len as _, input, depth),
+			TypeDef::Tuple(t) if t.fields.is_empty() => Ok(Value::Null),
+			TypeDef::Tuple(t) => t
+				.fields
+				.iter()
+				.enumerate()
+				.map(|(i, f)| {
+					self.decode_type(f.id, input, depth + 1).map_err(|e| e.at(i.to_string()))
+				})
+				.collect(),
+			TypeDef::Primitive(p) => self.decode_primitive(id, p, input),
+			TypeDef::Compact(_) => Ok(u128_to_value(self.read::<Compact<u128>>(id, input)?.0)),
+			TypeDef::BitSequence(b) => self.decode_bits(id, b, input),
+		}
+	}
+
+	fn decode_fields(
+		&self,
+		fields: &[Field<PortableForm>],
+		input: &mut &[u8],
+		depth: usize,
+	) -> Fallible<Value> {
+		match fields {
+			[] => Ok(Value::Null),
+			[f] if f.name.is_none() => self.decode_type(f.ty.id, input, depth + 1),
+			fs if fs.iter().all(|f| f.name.is_some()) => fs
+				.iter()
+				.map(|f| {
+					let name = f.name.clone().expect("name must be some; qed");
+					let value =
+						self.decode_type(f.ty.id, input, depth + 1).map_err(|e| e.at(&name))?;
+
+					Ok((name, value))
+				})
+				.collect::<Fallible<Map<_, _>>>()
+				.map(Value::Object),
+			fs => fs
+				.iter()
+				.enumerate()
+				.map(|(i, f)| {
+					self.decode_type(f.ty.id, input, depth + 1).map_err(|e| e.at(i.to_string()))
+				})
+				.collect(),
+		}
+	}
+
+	fn decode_elements(
+		&self,
+		id: u32,
+		element: u32,
+		len: usize,
+		input: &mut &[u8],
+		depth: usize,
+	) -> Fallible<Value> {
+		if is_u8(self.types, element) {
+			return Ok(Value::String(array_bytes::bytes2hex("0x", self.take(id, len, input)?)));
+		}
+
+		(0..len)
+			.map(|i| {
+				self.decode_type(element, input, depth + 1).map_err(|e| e.at(format!("[{i}]")))
+			})
+			.collect()
+	}
+
+	fn decode_primitive(
+		&self,
+		id: u32,
+		primitive: &TypeDefPrimitive,
+		input: &mut &[u8],
+	) -> Fallible<Value> {
+		let value = match primitive {
+			TypeDefPrimitive::Bool => self.read::<bool>(id, input)?.into(),
+			TypeDefPrimitive::Char => char::from_u32(self.read(id, input)?)
+				.ok_or_else(|| Failure::new("invalid char"))?
+				.to_string()
+				.into(),
+			TypeDefPrimitive::Str => self.read::<String>(id, input)?.into(),
+			TypeDefPrimitive::U8 => self.read::<u8>(id, input)?.into(),
+			TypeDefPrimitive::U16 => self.read::<u16>(id, input)?.into(),
+			TypeDefPrimitive::U32 => self.read::<u32>(id, input)?.into(),
+			TypeDefPrimitive::U64 => self.read::<u64>(id, input)?.into(),
+			TypeDefPrimitive::U128 => u128_to_value(self.read(id, input)?),
+			TypeDefPrimitive::U256 => u256_to_string(self.read(id, input)?).into(),
+			TypeDefPrimitive::I8 => self.read::<i8>(id, input)?.into(),
+			TypeDefPrimitive::I16 => self.read::<i16>(id, input)?.into(),
+			TypeDefPrimitive::I32 => self.read::<i32>(id, input)?.into(),
+			TypeDefPrimitive::I64 => self.read::<i64>(id, input)?.into(),
+			TypeDefPrimitive::I128 => {
+				let v = self.read::<i128>(id, input)?;
+
+				i64::try_from(v).map(Value::from).unwrap_or_else(|_| v.to_string().into())
+			},
+			TypeDefPrimitive::I256 => i256_to_string(self.read(id, input)?).into(),
+		};
+
+		Ok(value)
+	}
+
+	fn decode_bits(
+		&self,
+		id: u32,
+		bits: &TypeDefBitSequence<PortableForm>,
+		input: &mut &[u8],
+	) -> Fallible<Value> {
+		let (width, msb0) = bit_format(self.types, bits)?;
+		let len = self.read::<Compact<u32>>(id, input)?.0 as usize;
+		let words = len.div_ceil(width);
+		let words = self
+			.take(id, words * width / 8, input)?
+			.chunks_exact(width / 8)
+			.map(|c| {
+				let mut word = [0; 8];
+
+				word[..c.len()].copy_from_slice(c);
+
+				u64::from_le_bytes(word)
+			})
+			.collect::<Vec<_>>();
+
+		Ok((0..len)
+			.map(|i| {
+				let (word, bit) = (words[i / width], i % width);
+				let bit = if msb0 { width - 1 - bit } else { bit };
+
+				Value::Bool(word >> bit & 1 == 1)
+			})
+			.collect())
+	}
+}
+
+// Get the lower bound of the encoded size of the type, the variants are counted as their index
+// only.
+fn min_size(types: &PortableRegistry, id: u32, depth: u8) -> usize {
+	// The malformed recursive types.
+	if depth > 32 {
+		return 0;
+	}
+
+	let size = |id| min_size(types, id, depth + 1);
+
+	match types.resolve(id).map(|t| &t.type_def) {
+		Some(TypeDef::Composite(c)) =>
+			c.fields.iter().fold(0, |s, f| s.saturating_add(size(f.ty.id))),
+		Some(TypeDef::Array(a)) => size(a.type_param.id).saturating_mul(a.len as _),
+		Some(TypeDef::Tuple(t)) => t.fields.iter().fold(0, |s, f| s.saturating_add(size(f.id))),
+		Some(TypeDef::Primitive(p)) => match p {
+			TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => 2,
+			TypeDefPrimitive::Char | TypeDefPrimitive::U32 | TypeDefPrimitive::I32 => 4,
+			TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => 8,
+			TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => 16,
+			TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => 32,
+			_ => 1,
+		},
+		Some(
+			TypeDef::Variant(_)
+			| TypeDef::Sequence(_)
+			| TypeDef::Compact(_)
+			| TypeDef::BitSequence(_),
+		) => 1,
+		None => 0,
+	}
+}
+
+fn u128_to_value(v: u128) -> Value {
+	u64::try_from(v).map(Value::from).unwrap_or_else(|_| v.to_string().into())
+}
@@ -0,0 +1,364 @@
+//! Dynamic SCALE encoding.
+
+// crates.io
+use parity_scale_codec::{Compact, Encode};
+use scale_info::{
+	form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefBitSequence, TypeDefPrimitive,
+};
+use serde_json::Value;
+// substrate-minimal
+use super::*;
+use crate::{Error, Result};
+
+/// Dynamic SCALE encoder.
+///
+/// Check the [module](super) documentation for the value representation.
+#[derive(Clone, Copy, Debug)]
+pub struct Encoder<'a> {
+	types: &'a PortableRegistry,
+}
+impl<'a> Encoder<'a> {
+	/// Create a new encoder with the type registry.
+	pub fn new(types: &'a PortableRegistry) -> Self {
+		Self { types }
+	}
+
+	/// Encode the value as the type.
+	pub fn encode(&self, type_id: u32, value: &Value) -> Result<Vec<u8>> {
+		let mut dest = Vec::new();
+
+		self.encode_to(type_id, value, &mut dest)?;
+
+		Ok(dest)
+	}
+
+	/// Encode the value as the type, the bytes will be appended to the destination.
+	pub fn encode_to(&self, type_id: u32, value: &Value, dest: &mut Vec<u8>) -> Result<()> {
+		self.encode_type(type_id, value, dest, 0)
+			.map_err(|e| e.into_error(self.types, type_id, Error::DynamicEncode))
+	}
+
+	fn mismatch(&self, id: u32, value: &Value) -> Failure {
+		Failure::new(format!("expected {}, found {value}", type_name(self.types, id)))
+	}
+
+	fn hex(&self, id: u32, value: &Value) -> Fallible<Vec<u8>> {
+		let Value::String(s) = value else { Err(self.mismatch(id, value))? };
+
+		array_bytes::hex2bytes(s).map_err(|_| Failure::new(format!("invalid hex string {s}")))
+	}
+
+	fn unsigned(&self, id: u32, value: &Value) -> Fallible<u128> {
+		match value {
+			Value::Number(n) => n.as_u64().map(Into::into),
+			Value::String(s) => s.parse().ok(),
+			_ => None,
+		}
+		.ok_or_else(|| self.mismatch(id, value))
+	}
+
+	fn signed(&self, id: u32, value: &Value) -> Fallible<i128> {
+		match value {
+			Value::Number(n) => n.as_i64().map(Into::into),
+			Value::String(s) => s.parse().ok(),
+			_ => None,
+		}
+		.ok_or_else(|| self.mismatch(id, value))
+	}
+
+	fn encode_type(
+		&self,
+		id: u32,
+		value: &Value,
+		dest: &mut Vec<u8>,
+		depth: usize,
+	) -> Fallible<()> {
+		if depth >= MAX_DEPTH {
+			Err(Failure::new("reached the maximum depth"))?;
+		}
+
+		let ty =
+			self.types.resolve(id).ok_or_else(|| Failure::new(format!("type #{id} not found")))?;
+
+		if is_account_id(ty) {
+			let public_key = match value {
+				Value::String(s) if s.starts_with("0x") => self.hex(id, value)?,
+				Value::String(s) =>
+					subcryptor::public_key_of::<subcryptor::Sr25519>(s).map_err(|e| match e {
+						subcryptor::Error::InvalidChecksum(_) =>
+							Failure::new(format!("invalid SS58 address {s}, checksum mismatch")),
+						_ => Failure::new(format!("invalid SS58 address {s}")),
+					})?,
+				_ => Err(self.mismatch(id, value))?,
+			};
+
+			if public_key.len() != 32 {
+				Err(Failure::new(format!(
+					"expected 32 bytes public key, found {} bytes",
+					public_key.len()
+				)))?;
+			}
+
+			dest.extend(public_key);
+
+			return Ok(());
+		}
+
+		match &ty.type_def {
+			TypeDef::Composite(c) => self.encode_fields(id, &c.fields, value, dest, depth),
+			TypeDef::Variant(v) => {
+				let (name, fields) = match value {
+					Value::Null if is_option(ty) => ("None", &Value::Null),
+					// The wrapped `Some` is matched as an object below.
+					_ if is_option(ty) && !is_wrapped_option(self.types, ty) => ("Some", value),
+					Value::String(name) => (name.as_str(), &Value::Null),
+					Value::Object(o) if o.len() == 1 =>
+						o.iter().next().map(|(n, f)| (n.as_str(), f)).expect("len is 1; qed"),
+					_ => Err(self.mismatch(id, value))?,
+				};
+				let variant = v.variants.iter().find(|v| v.name == name).ok_or_else(|| {
+					Failure::new(format!("unknown variant {name} of {}", type_name(self.types, id)))
+				})?;
+
+				dest.push(variant.index);
+
+				self.encode_fields(id, &variant.fields, fields, dest, depth).map_err(|e| e.at(name))
+			},
+			TypeDef::Sequence(s) =>
+				self.encode_elements(id, s.type_param.id, None, value, dest, depth),
+			TypeDef::Array(a) =>
+				self.encode_elements(id, a.type_param.id, Some(a.len as _), value, dest, depth),
+			TypeDef::Tuple(t) if t.fields.is_empty() => match value {
+				Value::Null => Ok(()),
+				_ => Err(self.mismatch(id, value)),
+			},
+			TypeDef::Tuple(t) => {
+				let Value::Array(a) = value else { Err(self.mismatch(id, value))? };
+
+				if a.len() != t.fields.len() {
+					Err(Failure::new(format!(
+						"expected {} elements, found {}",
+						t.fields.len(),
+						a.len()
+					)))?;
+				}
+
+				t.fields.iter().zip(a).enumerate().try_for_each(|(i, (f, v))| {
+					self.encode_type(f.id, v, dest, depth + 1).map_err(|e| e.at(i.to_string()))
+				})
+			},
+			TypeDef::Primitive(p) => self.encode_primitive(id, p, value, dest),
+			TypeDef::Compact(c) => {
+				let v = self.unsigned(id, value)?;
+				let max = match compact_primitive(self.types, c.type_param.id, depth + 1) {
+					Some(TypeDefPrimitive::U8) => u8::MAX as u128,
+					Some(TypeDefPrimitive::U16) => u16::MAX as u128,
+					Some(TypeDefPrimitive::U32) => u32::MAX as u128,
+					Some(TypeDefPrimitive::U64) => u64::MAX as u128,
+					_ => u128::MAX,
+				};
+
+				if v > max {
+					Err(Failure::new(format!(
+						"{v} out of range of {}",
+						type_name(self.types, id)
+					)))?;
+				}
+
+				Compact(v).encode_to(dest);
+
+				Ok(())
+			},
+			TypeDef::BitSequence(b) => self.encode_bits(id, b, value, dest),
+		}
+	}
+
+	fn encode_fields(
+		&self,
+		id: u32,
+		fields: &[Field<PortableForm>],
+		value: &Value,
+		dest: &mut Vec<u8>,
+		depth: usize,
+	) -> Fallible<()> {
+		match fields {
+			[] if value.is_null() => Ok(()),
+			[] => Err(Failure::new(format!("expected no fields, found {value}"))),
+			[f] if f.name.is_none() => self.encode_type(f.ty.id, value, dest, depth + 1),
+			fs if fs.iter().all(|f| f.name.is_some()) => {
+				let Value::Object(o) = value else { Err(self.mismatch(id, value))? };
+
+				if let Some(k) = o.keys().find(|k| fs.iter().all(|f| f.name.as_ref() != Some(k))) {
+					Err(Failure::new(format!("unknown field {k}")))?;
+				}
+
+				fs.iter().try_for_each(|f| {
+					let name = f.name.as_deref().expect("name must be some; qed");
+					let v =
+						o.get(name).ok_or_else(|| Failure::new(format!("missing field {name}")))?;
+
+					self.encode_type(f.ty.id, v, dest, depth + 1).map_err(|e| e.at(name))
+				})
+			},
+			fs => {
+				let Value::Array(a) = value else { Err(self.mismatch(id, value))? };
+
+				if a.len() != fs.len() {
+					Err(Failure::new(format!("expected {} fields, found {}", fs.len(), a.len())))?;
+				}
+
+				fs.iter().zip(a).enumerate().try_for_each(|(i, (f, v))| {
+					self.encode_type(f.ty.id, v, dest, depth + 1).map_err(|e| e.at(i.to_string()))
+				})
+			},
+		}
+	}
+
+	fn encode_elements(
+		&self,
+		id: u32,
+		element: u32,
+		len: Option<usize>,
+		value: &Value,
+		dest: &mut Vec<u8>,
+		depth: usize,
+	) -> Fallible<()> {
+		let check_len = |found: usize, dest: &mut Vec<u8>| match len {
+			Some(l) if l != found =>
+				Err(Failure::new(format!("expected {l} elements, found {found}"))),
+			Some(_) => Ok(()),
+			None => {
+				Compact(found as u32).encode_to(dest);
+
+				Ok(())
+			},
+		};
+
+		if is_u8(self.types, element) && value.is_string() {
+			let bytes = self.hex(id, value)?;
+
+			check_len(bytes.len(), dest)?;
+			dest.extend(bytes);
+
+			return Ok(());
+		}
+
+		let Value::Array(a) = value else { Err(self.mismatch(id, value))? };
+
+		check_len(a.len(), dest)?;
+
+		a.iter().enumerate().try_for_each(|(i, v)| {
+			self.encode_type(element, v, dest, depth + 1).map_err(|e| e.at(format!("[{i}]")))
+		})
+	}
+
+	fn encode_primitive(
+		&self,
+		id: u32,
+		primitive: &TypeDefPrimitive,
+		value: &Value,
+		dest: &mut Vec<u8>,
+	) -> Fallible<()> {
+		macro_rules! encode_int {
+			($t:ty, $f:ident) => {{
+				let v = self.$f(id, value)?;
+
+				<$t>::try_from(v)
+					.map_err(|_| {
+						Failure::new(format!("{v} out of range of {}", type_name(self.types, id)))
+					})?
+					.encode_to(dest)
+			}};
+		}
+
+		match primitive {
+			TypeDefPrimitive::Bool =>
+				value.as_bool().ok_or_else(|| self.mismatch(id, value))?.encode_to(dest),
+			TypeDefPrimitive::Char => {
+				let mut chars = value.as_str().ok_or_else(|| self.mismatch(id, value))?.chars();
+				let (Some(c), None) = (chars.next(), chars.next()) else {
+					Err(self.mismatch(id, value))?
+				};
+
+				(c as u32).encode_to(dest)
+			},
+			TypeDefPrimitive::Str =>
+				value.as_str().ok_or_else(|| self.mismatch(id, value))?.encode_to(dest),
+			TypeDefPrimitive::U8 => encode_int!(u8, unsigned),
+			TypeDefPrimitive::U16 => encode_int!(u16, unsigned),
+			TypeDefPrimitive::U32 => encode_int!(u32, unsigned),
+			TypeDefPrimitive::U64 => encode_int!(u64, unsigned),
+			TypeDefPrimitive::U128 => encode_int!(u128, unsigned),
+			TypeDefPrimitive::U256 => {
+				let v = match value {
+					Value::Number(n) => n.as_u64().map(|n| n.to_string()),
+					Value::String(s) => Some(s.to_owned()),
+					_ => None,
+				};
+
+				dest.extend(
+					v.and_then(|v| string_to_u256(&v)).ok_or_else(|| self.mismatch(id, value))?,
+				);
+			},
+			TypeDefPrimitive::I8 => encode_int!(i8, signed),
+			TypeDefPrimitive::I16 => encode_int!(i16, signed),
+			TypeDefPrimitive::I32 => encode_int!(i32, signed),
+			TypeDefPrimitive::I64 => encode_int!(i64, signed),
+			TypeDefPrimitive::I128 => encode_int!(i128, signed),
+			TypeDefPrimitive::I256 => {
+				let v = match value {
+					Value::Number(n) => n.as_i64().map(|n| n.to_string()),
+					Value::String(s) => Some(s.to_owned()),
+					_ => None,
+				};
+
+				dest.extend(
+					v.and_then(|v| string_to_i256(&v)).ok_or_else(|| self.mismatch(id, value))?,
+				);
+			},
+		}
+
+		Ok(())
+	}
+
+	fn encode_bits(
+		&self,
+		id: u32,
+		bits: &TypeDefBitSequence<PortableForm>,
+		value: &Value,
+		dest: &mut Vec<u8>,
+	) -> Fallible<()> {
+		let (width, msb0) = bit_format(self.types, bits)?;
+		let Value::Array(a) = value else { Err(self.mismatch(id, value))? };
+		let mut words = vec![0_u64; a.len().div_ceil(width)];
+
+		for (i, v) in a.iter().enumerate() {
+			let v = v.as_bool().ok_or_else(|| {
+				Failure::new(format!("expected bool, found {v}")).at(format!("[{i}]"))
+			})?;
+			let (word, bit) = (i / width, i % width);
+			let bit = if msb0 { width - 1 - bit } else { bit };
+
+			words[word] |= (v as u64) << bit;
+		}
+
+		Compact(a.len() as u32).encode_to(dest);
+		words.into_iter().for_each(|w| dest.extend(&w.to_le_bytes()[..width / 8]));
+
+		Ok(())
+	}
+}
+
+// Resolve the primitive behind the compact, through the single field wrappers.
+fn compact_primitive(types: &PortableRegistry, id: u32, depth: usize) -> Option<TypeDefPrimitive> {
+	if depth >= MAX_DEPTH {
+		return None;
+	}
+
+	match &types.resolve(id)?.type_def {
+		TypeDef::Primitive(p) => Some(p.clone()),
+		TypeDef::Composite(c) if c.fields.len() == 1 =>
+			compact_primitive(types, c.fields[0].ty.id, depth + 1),
+		_ => None,
+	}
+}
@@ -0,0 +1,257 @@
+//! Dynamic SCALE codec, which works with the type registry instead of the generated types.
+//!
+//! The values are represented as [`serde_json::Value`]:
+//! - A composite with named fields is an object, with a single unnamed field is the inner value and
+//!   with multiple unnamed fields is an array.
+//! - A variant without fields is its name, otherwise it is an object `{ "Name": fields }`.
+//! - An `Option` is `null` or the inner value. If the inner value can be `null`, such as in
+//!   `Option<()>` or `Option<Option<T>>`, `Some` is an object `{ "Some": value }` instead.
+//! - A byte sequence or array is a hex string, an `AccountId32` is an SS58 address.
+//! - An integer which doesn't fit into [`u64`] or [`i64`] is a decimal string.
+//! - A bit sequence is an array of booleans.
+//!
+//! The encoder is more lenient, it also accepts:
+//! - An SS58 address of any network or a hex string for an `AccountId32`.
+//! - An array of numbers for a byte sequence or array.
+//! - A number or a decimal string for any integer.
+//! - An object `{ "Name": null }` for a variant without fields.
+
+mod decode;
+pub use decode::*;
+
+mod encode;
+pub use encode::*;
+
+// crates.io
+use scale_info::{
+	form::PortableForm, PortableRegistry, Type, TypeDef, TypeDefBitSequence, TypeDefPrimitive,
+};
+// substrate-minimal
+use crate::Error;
+
+// The codec is recursive, limit the depth to prevent the stack overflow.
+const MAX_DEPTH: usize = 256;
+
+type Fallible<T> = std::result::Result<T, Failure>;
+
+// The failure with the path, which is built from the leaf to the root.
+struct Failure {
+	path: Vec<String>,
+	reason: String,
+}
+impl Failure {
+	fn new(reason: impl Into<String>) -> Self {
+		Self { path: Vec::new(), reason: reason.into() }
+	}
+
+	fn at(mut self, segment: impl Into<String>) -> Self {
+		self.path.push(segment.into());
+
+		self
+	}
+
+	fn into_error(
+		self,
+		types: &PortableRegistry,
+		id: u32,
+		error: fn(String, String) -> Error,
+	) -> Error {
+		let path = self.path.into_iter().rev().fold(type_name(types, id), |mut path, s| {
+			if !s.starts_with('[') {
+				path.push('.');
+			}
+
+			path.push_str(&s);
+
+			path
+		});
+
+		error(path, self.reason)
+	}
+}
+
+// Get the readable name of the type, for the error messages.
+fn type_name(types: &PortableRegistry, id: u32) -> String {
+	let Some(ty) = types.resolve(id) else {
+		return format!("#{id}");
+	};
+
+	if !ty.path.is_empty() {
+		return ty.path.segments.join("::");
+	}
+
+	match &ty.type_def {
+		TypeDef::Primitive(p) => format!("{p:?}").to_lowercase(),
+		TypeDef::Sequence(s) => format!("Vec<{}>", type_name(types, s.type_param.id)),
+		TypeDef::Array(a) => format!("[{}; {}]", type_name(types, a.type_param.id), a.len),
+		TypeDef::Tuple(t) => format!(
+			"({})",
+			t.fields.iter().map(|f| type_name(types, f.id)).collect::<Vec<_>>().join(", ")
+		),
+		TypeDef::Compact(c) => format!("Compact<{}>", type_name(types, c.type_param.id)),
+		TypeDef::BitSequence(_) => "BitVec".into(),
+		TypeDef::Composite(_) | TypeDef::Variant(_) => format!("#{id}"),
+	}
+}
+
+fn is_account_id(ty: &Type<PortableForm>) -> bool {
+	ty.path.ident().as_deref() == Some("AccountId32")
+}
+
+fn is_option(ty: &Type<PortableForm>) -> bool {
+	ty.path.segments == ["Option"]
+}
+
+// Whether `Some` of the option is wrapped, since its inner value can be `null`.
+fn is_wrapped_option(types: &PortableRegistry, ty: &Type<PortableForm>) -> bool {
+	let TypeDef::Variant(v) = &ty.type_def else { return false };
+
+	is_option(ty)
+		&& v.variants
+			.iter()
+			.filter(|v| v.name == "Some")
+			.flat_map(|v| &v.fields)
+			.any(|f| is_nullable(types, f.ty.id))
+}
+
+// Whether the value of the type can be `null`, through the single field wrappers.
+fn is_nullable(types: &PortableRegistry, mut id: u32) -> bool {
+	// Bounded for the malformed recursive types.
+	for _ in 0..32 {
+		let Some(ty) = types.resolve(id) else { return false };
+
+		match &ty.type_def {
+			_ if is_option(ty) => return true,
+			TypeDef::Composite(c) => match c.fields.as_slice() {
+				[] => return true,
+				[f] if f.name.is_none() => id = f.ty.id,
+				_ => return false,
+			},
+			TypeDef::Tuple(t) => return t.fields.is_empty(),
+			_ => return false,
+		}
+	}
+
+	false
+}
+
+fn is_u8(types: &PortableRegistry, id: u32) -> bool {
+	matches!(types.resolve(id).map(|t| &t.type_def), Some(TypeDef::Primitive(TypeDefPrimitive::U8)))
+}
+
+// Get the bit store width and whether the bit order is `Msb0`.
+fn bit_format(
+	types: &PortableRegistry,
+	bits: &TypeDefBitSequence<PortableForm>,
+) -> Fallible<(usize, bool)> {
+	let width = match types.resolve(bits.bit_store_type.id).map(|t| &t.type_def) {
+		Some(TypeDef::Primitive(TypeDefPrimitive::U8)) => 8,
+		Some(TypeDef::Primitive(TypeDefPrimitive::U16)) => 16,
+		Some(TypeDef::Primitive(TypeDefPrimitive::U32)) => 32,
+		Some(TypeDef::Primitive(TypeDefPrimitive::U64)) => 64,
+		_ => Err(Failure::new("unsupported bit store type"))?,
+	};
+	let msb0 = match types.resolve(bits.bit_order_type.id).and_then(|t| t.path.ident()) {
+		Some(o) if o == "Lsb0" => false,
+		Some(o) if o == "Msb0" => true,
+		_ => Err(Failure::new("unsupported bit order type"))?,
+	};
+
+	Ok((width, msb0))
+}
+
+// Convert the little endian 256-bit unsigned integer into a decimal string.
+pub(crate) fn u256_to_string(bytes: [u8; 32]) -> String {
+	let mut limbs = [0_u64; 4];
+
+	limbs.iter_mut().zip(bytes.as_chunks::<8>().0).for_each(|(l, c)| *l = u64::from_le_bytes(*c));
+
+	let mut digits = Vec::new();
+
+	loop {
+		let mut rem = 0_u128;
+
+		for l in limbs.iter_mut().rev() {
+			let cur = (rem << 64) | *l as u128;
+
+			*l = (cur / 10) as u64;
+			rem = cur % 10;
+		}
+
+		digits.push(char::from(b'0' + rem as u8));
+
+		if limbs == [0; 4] {
+			break;
+		}
+	}
+
+	digits.into_iter().rev().collect()
+}
+
+// Convert the little endian 256-bit two's complement integer into a decimal string.
+pub(crate) fn i256_to_string(mut bytes: [u8; 32]) -> String {
+	if bytes[31] & 0x80 == 0 {
+		return u256_to_string(bytes);
+	}
+
+	negate(&mut bytes);
+
+	format!("-{}", u256_to_string(bytes))
+}
+
+// Parse the decimal string into a little endian 256-bit unsigned integer.
+pub(crate) fn string_to_u256(s: &str) -> Option<[u8; 32]> {
+	if s.is_empty() {
+		return None;
+	}
+
+	let mut limbs = [0_u64; 4];
+
+	for c in s.chars() {
+		let mut carry = c.to_digit(10)? as u128;
+
+		for l in limbs.iter_mut() {
+			let cur = *l as u128 * 10 + carry;
+
+			*l = cur as u64;
+			carry = cur >> 64;
+		}
+
+		if carry != 0 {
+			return None;
+		}
+	}
+
+	let mut bytes = [0; 32];
+
+	bytes.as_chunks_mut::<8>().0.iter_mut().zip(limbs).for_each(|(c, l)| *c = l.to_le_bytes());
+
+	Some(bytes)
+}
+
+// Parse the decimal string into a little endian 256-bit two's complement integer.
+pub(crate) fn string_to_i256(s: &str) -> Option<[u8; 32]> {
+	let (negative, s) = s.strip_prefix('-').map_or((false, s), |s| (true, s));
+	let mut bytes = string_to_u256(s)?;
+
+	if negative {
+		negate(&mut bytes);
+
+		// Zero stays zero, otherwise the result must be negative.
+		(bytes == [0; 32] || bytes[31] & 0x80 != 0).then_some(bytes)
+	} else {
+		(bytes[31] & 0x80 == 0).then_some(bytes)
+	}
+}
+
+// Negate the little endian two's complement integer.
+fn negate(bytes: &mut [u8; 32]) {
+	let mut carry = true;
+
+	for b in bytes.iter_mut() {
+		let (v, c) = (!*b).overflowing_add(carry as u8);
+
+		*b = v;
+		carry = c;
+	}
+}
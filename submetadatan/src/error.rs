@@ -13,4 +13,15 @@ pub enum Error {
 	ArrayBytes(array_bytes::Error),
 	#[error(transparent)]
 	Codec(parity_scale_codec::Error),
+	#[error("storage entry not found, {0}::{1}")]
+	StorageEntryNotFound(String, String),
+	#[cfg(feature = "dynamic")]
+	#[error("failed to decode {0}, {1}")]
+	DynamicDecode(String, String),
+	#[cfg(feature = "dynamic")]
+	#[error("failed to encode {0}, {1}")]
+	DynamicEncode(String, String),
+	#[cfg(feature = "metadata-hash")]
+	#[error("failed to merkleize the metadata, {0}")]
+	MetadataHash(String),
 }
@@ -0,0 +1,356 @@
+//! Legacy (V9–V13) metadata normalization.
+//!
+//! The legacy metadata has no type registry, the types are only named by strings.
+//! So the converted [`Metadata::types`] is empty, [`StorageEntryMetadata::key_type`] and
+//! [`StorageEntryMetadata::value_type`] are always `0` and the key sizes are only resolved for the
+//! well-known fixed size types.
+//!
+//! The pallet index was introduced in V12, before that the index is the position of the module.
+//! And the calls and events are indexed by the position among the modules having them.
+
+// crates.io
+use frame_metadata::{
+	decode_different::{DecodeDifferent, DecodeDifferentArray, DecodeDifferentStr, FnEncode},
+	v10, v11, v12, v13, v9,
+};
+use fxhash::FxHashMap;
+use parity_scale_codec::Encode;
+use scale_info::Registry;
+use substorager::StorageHasher;
+// substrate-minimal
+use crate::*;
+
+trait Normalize {
+	type Output;
+
+	fn normalize(self) -> Self::Output;
+}
+
+macro_rules! impl_normalize_for_storage_hasher {
+	($($m:ident { $($v:ident,)+ })+) => {
+		$(
+			impl Normalize for $m::StorageHasher {
+				type Output = StorageHasher;
+
+				fn normalize(self) -> Self::Output {
+					match self {
+						$(Self::$v => StorageHasher::$v,)+
+					}
+				}
+			}
+		)+
+	};
+}
+impl_normalize_for_storage_hasher! {
+	v9 { Blake2_128, Blake2_256, Blake2_128Concat, Twox128, Twox256, Twox64Concat, }
+	v10 { Blake2_128, Blake2_256, Blake2_128Concat, Twox128, Twox256, Twox64Concat, }
+	v11 { Blake2_128, Blake2_256, Blake2_128Concat, Twox128, Twox256, Twox64Concat, Identity, }
+	v12 { Blake2_128, Blake2_256, Blake2_128Concat, Twox128, Twox256, Twox64Concat, Identity, }
+	v13 { Blake2_128, Blake2_256, Blake2_128Concat, Twox128, Twox256, Twox64Concat, Identity, }
+}
+
+macro_rules! impl_normalize_for_storage_entry_modifier {
+	($($m:ident,)+) => {
+		$(
+			impl Normalize for $m::StorageEntryModifier {
+				type Output = StorageEntryModifier;
+
+				fn normalize(self) -> Self::Output {
+					match self {
+						Self::Optional => StorageEntryModifier::Optional,
+						Self::Default => StorageEntryModifier::Default,
+					}
+				}
+			}
+		)+
+	};
+}
+impl_normalize_for_storage_entry_modifier! {
+	v9,
+	v10,
+	v11,
+	v12,
+	v13,
+}
+
+// Normalize into the storage entry type, the key type names and the value type name.
+macro_rules! impl_normalize_for_storage_entry_type {
+	($($m:ident $({ $($nmap:tt)+ })?,)+) => {
+		$(
+			impl Normalize for $m::StorageEntryType {
+				type Output = (StorageEntryType, Vec<String>, String);
+
+				fn normalize(self) -> Self::Output {
+					match self {
+						Self::Plain(value) => (StorageEntryType::Plain, Vec::new(), string(value)),
+						Self::Map { hasher, key, value, .. } => (
+							StorageEntryType::Map(vec![hasher.normalize()]),
+							vec![string(key)],
+							string(value),
+						),
+						Self::DoubleMap { hasher, key1, key2, value, key2_hasher } => (
+							StorageEntryType::Map(vec![hasher.normalize(), key2_hasher.normalize()]),
+							vec![string(key1), string(key2)],
+							string(value),
+						),
+						$($($nmap)+)?
+					}
+				}
+			}
+		)+
+	};
+}
+impl_normalize_for_storage_entry_type! {
+	v9,
+	v10,
+	v11,
+	v12,
+	v13 {
+		Self::NMap { keys, hashers, value } => (
+			StorageEntryType::Map(
+				decoded(hashers, |h| h.to_vec()).into_iter().map(Normalize::normalize).collect(),
+			),
+			strings(keys),
+			string(value),
+		),
+	},
+}
+
+// Normalize into the variant name and the variant metadata, the index is the position.
+macro_rules! impl_normalize_for_variant {
+	($($m:ident,)+) => {
+		$(
+			impl Normalize for (usize, $m::FunctionMetadata) {
+				type Output = (String, VariantMetadata);
+
+				fn normalize(self) -> Self::Output {
+					let (i, v) = self;
+					let fields = array(v.arguments)
+						.into_iter()
+						.map(|a| FieldMetadata {
+							name: Some(string(a.name)),
+							r#type: 0,
+							type_name: Some(string(a.ty)),
+						})
+						.collect();
+
+					(
+						string(v.name),
+						VariantMetadata { index: i as _, fields, docs: strings(v.documentation) },
+					)
+				}
+			}
+
+			impl Normalize for (usize, $m::EventMetadata) {
+				type Output = (String, VariantMetadata);
+
+				fn normalize(self) -> Self::Output {
+					let (i, v) = self;
+					let fields = strings(v.arguments)
+						.into_iter()
+						.map(|a| FieldMetadata { name: None, r#type: 0, type_name: Some(a) })
+						.collect();
+
+					(
+						string(v.name),
+						VariantMetadata { index: i as _, fields, docs: strings(v.documentation) },
+					)
+				}
+			}
+
+			impl Normalize for (usize, $m::ErrorMetadata) {
+				type Output = (String, VariantMetadata);
+
+				fn normalize(self) -> Self::Output {
+					let (i, v) = self;
+
+					(
+						string(v.name),
+						VariantMetadata {
+							index: i as _,
+							fields: Vec::new(),
+							docs: strings(v.documentation),
+						},
+					)
+				}
+			}
+
+			impl Normalize for $m::ModuleConstantMetadata {
+				type Output = (String, ConstantMetadata);
+
+				fn normalize(self) -> Self::Output {
+					(
+						string(self.name),
+						ConstantMetadata {
+							r#type: 0,
+							type_name: Some(string(self.ty)),
+							value: decoded(self.value, |v| $m::DefaultByte::default_byte(v.0)),
+							docs: strings(self.documentation),
+						},
+					)
+				}
+			}
+		)+
+	};
+}
+impl_normalize_for_variant! {
+	v9,
+	v10,
+	v11,
+	v12,
+	v13,
+}
+
+macro_rules! impl_from_legacy_runtime_metadata {
+	($($version:literal, $m:ident::$t:ident, |$p:ident| $index:expr;)+) => {
+		$(
+			impl From<$m::$t> for Metadata {
+				fn from(v: $m::$t) -> Self {
+					let mut pallets = FxHashMap::default();
+					let mut calls_count = 0;
+					let mut events_count = 0;
+
+					for (i, $p) in array(v.modules).into_iter().enumerate() {
+						let (index, calls_index, events_index) = match $index {
+							Some(index) => (index, index, index),
+							None => (i as u8, calls_count, events_count),
+						};
+						let r#enum =
+							|index, variants| PalletEnumMetadata { r#type: 0, index, variants };
+						let storages = $p.storage.map(|s| {
+							let s = decoded(s, |s| s.0());
+
+							PalletStorageMetadata {
+								prefix: string(s.prefix),
+								entries: array(s.entries)
+									.into_iter()
+									.map(|e| {
+										let (r#type, keys, value) = e.ty.normalize();
+
+										(
+											string(e.name),
+											StorageEntryMetadata {
+												r#type,
+												modifier: e.modifier.normalize(),
+												default: decoded(e.default, |d| {
+													$m::DefaultByte::default_byte(d.0)
+												}),
+												key_type: 0,
+												key_sizes: keys
+													.iter()
+													.map(|k| encoded_size(k))
+													.collect(),
+												key_type_names: keys,
+												value_type: 0,
+												value_type_name: Some(value),
+											},
+										)
+									})
+									.collect(),
+							}
+						});
+						let calls = $p.calls.map(|c| {
+							calls_count += 1;
+
+							r#enum(
+								calls_index,
+								functions(c)
+									.into_iter()
+									.enumerate()
+									.map(Normalize::normalize)
+									.collect(),
+							)
+						});
+						let events = $p.event.map(|e| {
+							events_count += 1;
+
+							r#enum(
+								events_index,
+								functions(e)
+									.into_iter()
+									.enumerate()
+									.map(Normalize::normalize)
+									.collect(),
+							)
+						});
+						let constants =
+							functions($p.constants).into_iter().map(Normalize::normalize).collect();
+						let errors = functions($p.errors);
+						// The errors are always indexed by the pallet index.
+						let error = (!errors.is_empty()).then(|| {
+							r#enum(
+								index,
+								errors.into_iter().enumerate().map(Normalize::normalize).collect(),
+							)
+						});
+
+						pallets.insert(
+							string($p.name),
+							PalletMetadata { index, storages, calls, events, constants, error },
+						);
+					}
+
+					Self { version: $version, pallets, types: Registry::new().into() }
+				}
+			}
+		)+
+	};
+}
+impl_from_legacy_runtime_metadata! {
+	9, v9::RuntimeMetadataV9, |p| None;
+	10, v10::RuntimeMetadataV10, |p| None;
+	11, v11::RuntimeMetadataV11, |p| None;
+	12, v12::RuntimeMetadataV12, |p| Some(p.index);
+	13, v13::RuntimeMetadataV13, |p| Some(p.index);
+}
+
+fn decoded<B, O>(v: DecodeDifferent<B, O>, f: impl FnOnce(B) -> O) -> O {
+	match v {
+		DecodeDifferent::Encode(v) => f(v),
+		DecodeDifferent::Decoded(v) => v,
+	}
+}
+
+fn string(v: DecodeDifferentStr) -> String {
+	decoded(v, Into::into)
+}
+
+fn strings(v: DecodeDifferentArray<&'static str, String>) -> Vec<String> {
+	decoded(v, |v| v.iter().map(|s| s.to_string()).collect())
+}
+
+fn array<T>(v: DecodeDifferentArray<T>) -> Vec<T>
+where
+	T: Clone,
+{
+	decoded(v, |v| v.to_vec())
+}
+
+fn functions<T>(v: DecodeDifferent<FnEncode<&'static [T]>, Vec<T>>) -> Vec<T>
+where
+	T: Clone + Encode,
+{
+	decoded(v, |f| f.0().to_vec())
+}
+
+// Get the SCALE encoded size of the type name, [`None`] if it is not fixed or unknown.
+fn encoded_size(name: &str) -> Option<usize> {
+	let size = match name.trim() {
+		"()" => 0,
+		"bool" | "u8" | "i8" => 1,
+		"u16" | "i16" => 2,
+		"u32" | "i32" => 4,
+		"u64" | "i64" => 8,
+		"u128" | "i128" => 16,
+		"H160" => 20,
+		"H256" => 32,
+		"H512" => 64,
+		name => {
+			let (ty, len) = name.strip_prefix('[')?.strip_suffix(']')?.split_once(';')?;
+
+			encoded_size(ty)?.checked_mul(len.trim().parse().ok()?)?
+		},
+	};
+
+	Some(size)
+}
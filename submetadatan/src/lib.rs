@@ -3,35 +3,111 @@
 #![deny(missing_docs)]
 #![deny(unused_crate_dependencies)]
 
+#[cfg(test)] mod test;
+#[cfg(test)] use merkleized_metadata as _;
+
+mod legacy;
+
 pub mod error;
 pub use error::Error;
 
 pub mod metadata;
 pub use metadata::*;
 
+pub mod storage_key;
+pub use storage_key::*;
+
+pub mod storage_value;
+pub use storage_value::*;
+
+pub mod storage_version;
+pub use storage_version::*;
+
 #[cfg(feature = "cmp")] pub mod cmp;
 
-pub use frame_metadata::{self, RuntimeMetadataV14 as LatestRuntimeMetadata};
+#[cfg(feature = "dynamic")] pub mod dynamic;
+
+#[cfg(feature = "metadata-hash")] pub mod metadata_hash;
+
+pub use frame_metadata::{self, v16::RuntimeMetadataV16 as LatestRuntimeMetadata};
 pub use parity_scale_codec;
 pub use scale_info;
+#[cfg(any(feature = "cmp", feature = "dynamic"))] pub use serde_json;
 
 // crates.io
+use frame_metadata::{v10, v11, v12, v13, v14, v15, v16, v9};
 use parity_scale_codec::Decode;
+use scale_info::PortableRegistry;
 
 /// Main result.
 pub type Result<T> = std::result::Result<T, Error>;
 
-/// Try extracting [`LatestRuntimeMetadata`] from [`frame_metadata::RuntimeMetadataPrefixed`].
+/// Runtime metadata of the supported versions.
+///
+/// V9–V13 are the legacy versions, which have no type registry.
+#[allow(missing_docs)]
+#[derive(Debug, PartialEq, Eq)]
+pub enum VersionedRuntimeMetadata {
+	V9(v9::RuntimeMetadataV9),
+	V10(v10::RuntimeMetadataV10),
+	V11(v11::RuntimeMetadataV11),
+	V12(v12::RuntimeMetadataV12),
+	V13(v13::RuntimeMetadataV13),
+	V14(v14::RuntimeMetadataV14),
+	V15(v15::RuntimeMetadataV15),
+	V16(v16::RuntimeMetadataV16),
+}
+impl VersionedRuntimeMetadata {
+	/// Get the metadata version.
+	pub fn version(&self) -> u32 {
+		match self {
+			Self::V9(_) => 9,
+			Self::V10(_) => 10,
+			Self::V11(_) => 11,
+			Self::V12(_) => 12,
+			Self::V13(_) => 13,
+			Self::V14(_) => 14,
+			Self::V15(_) => 15,
+			Self::V16(_) => 16,
+		}
+	}
+
+	/// Get the type registry, [`None`] if this is a legacy version.
+	pub fn types(&self) -> Option<&PortableRegistry> {
+		match self {
+			Self::V9(_) | Self::V10(_) | Self::V11(_) | Self::V12(_) | Self::V13(_) => None,
+			Self::V14(v) => Some(&v.types),
+			Self::V15(v) => Some(&v.types),
+			Self::V16(v) => Some(&v.types),
+		}
+	}
+}
+
+/// Try extracting [`VersionedRuntimeMetadata`] from [`frame_metadata::RuntimeMetadataPrefixed`].
 pub fn unprefix_metadata(
 	metadata: frame_metadata::RuntimeMetadataPrefixed,
-) -> Result<LatestRuntimeMetadata> {
+) -> Result<VersionedRuntimeMetadata> {
 	match metadata.1 {
-		frame_metadata::RuntimeMetadata::V14(metadata) => Ok(metadata),
+		frame_metadata::RuntimeMetadata::V9(metadata) => Ok(VersionedRuntimeMetadata::V9(metadata)),
+		frame_metadata::RuntimeMetadata::V10(metadata) =>
+			Ok(VersionedRuntimeMetadata::V10(metadata)),
+		frame_metadata::RuntimeMetadata::V11(metadata) =>
+			Ok(VersionedRuntimeMetadata::V11(metadata)),
+		frame_metadata::RuntimeMetadata::V12(metadata) =>
+			Ok(VersionedRuntimeMetadata::V12(metadata)),
+		frame_metadata::RuntimeMetadata::V13(metadata) =>
+			Ok(VersionedRuntimeMetadata::V13(metadata)),
+		frame_metadata::RuntimeMetadata::V14(metadata) =>
+			Ok(VersionedRuntimeMetadata::V14(metadata)),
+		frame_metadata::RuntimeMetadata::V15(metadata) =>
+			Ok(VersionedRuntimeMetadata::V15(metadata)),
+		frame_metadata::RuntimeMetadata::V16(metadata) =>
+			Ok(VersionedRuntimeMetadata::V16(metadata)),
 		metadata => Err(Error::UnsupportedVersion(metadata.version())),
 	}
 }
-/// Try extracting [`LatestRuntimeMetadata`] from [`AsRef<str>`].
-pub fn unprefix_raw_metadata<R>(raw_metadata: R) -> Result<LatestRuntimeMetadata>
+/// Try extracting [`VersionedRuntimeMetadata`] from [`AsRef<str>`].
+pub fn unprefix_raw_metadata<R>(raw_metadata: R) -> Result<VersionedRuntimeMetadata>
 where
 	R: AsRef<str>,
 {
@@ -43,6 +119,29 @@ where
 		.map_err(error::Error::Codec)?,
 	)
 }
+/// Try extracting [`VersionedRuntimeMetadata`] from the result of the
+/// `Metadata_metadata_at_version` runtime API, which is a SCALE encoded `Option<OpaqueMetadata>`.
+///
+/// Return [`None`] if the runtime doesn't provide the requested version.
+pub fn unprefix_raw_metadata_at_version<R>(
+	raw_result: R,
+) -> Result<Option<VersionedRuntimeMetadata>>
+where
+	R: AsRef<str>,
+{
+	let raw_result =
+		array_bytes::hex2bytes(raw_result.as_ref()).map_err(error::Error::ArrayBytes)?;
+	let Some(opaque) = <Option<Vec<u8>>>::decode(&mut &*raw_result).map_err(error::Error::Codec)?
+	else {
+		return Ok(None);
+	};
+
+	unprefix_metadata(
+		frame_metadata::RuntimeMetadataPrefixed::decode(&mut &*opaque)
+			.map_err(error::Error::Codec)?,
+	)
+	.map(Some)
+}
 
 /// Try extracting [`Metadata`] from [`frame_metadata::RuntimeMetadataPrefixed`].
 pub fn unprefix_metadata_minimal(
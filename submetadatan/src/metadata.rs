@@ -3,11 +3,12 @@
 pub use Metadata as MetadataMinimal;
 
 // crates.io
+use frame_metadata::{v14, v15, v16};
 use fxhash::FxHashMap;
-use scale_info::form::PortableForm;
+use scale_info::{form::PortableForm, PortableRegistry, Registry, TypeDef, TypeDefPrimitive};
 use substorager::StorageHasher;
 // substrate-minimal
-use crate::LatestRuntimeMetadata;
+use crate::VersionedRuntimeMetadata;
 
 /// Some useful functions to access the metadata.
 pub trait Meta {
@@ -15,23 +16,103 @@ pub trait Meta {
 	fn storage<'a, 'b>(&'a self, pallet: &str, item: &'b str) -> Option<StorageEntry<'b>>
 	where
 		'a: 'b;
+
+	/// Get the call by the pallet name and the call name.
+	fn call(&self, pallet: &str, name: &str) -> Option<VariantEntry<'_>>;
+
+	/// Get the call by the pallet index and the call index.
+	fn call_by_index(&self, pallet_index: u8, index: u8) -> Option<VariantEntry<'_>>;
+
+	/// Get the event by the pallet name and the event name.
+	fn event(&self, pallet: &str, name: &str) -> Option<VariantEntry<'_>>;
+
+	/// Get the event by the pallet index and the event index.
+	fn event_by_index(&self, pallet_index: u8, index: u8) -> Option<VariantEntry<'_>>;
+
+	/// Get the error by the pallet name and the error name.
+	fn error(&self, pallet: &str, name: &str) -> Option<VariantEntry<'_>>;
+
+	/// Get the error by the pallet index and the error index.
+	fn error_by_index(&self, pallet_index: u8, index: u8) -> Option<VariantEntry<'_>>;
+
+	/// Get the constant.
+	fn constant(&self, pallet: &str, name: &str) -> Option<&ConstantMetadata>;
 }
 
 trait KV {
 	type V;
 
-	fn kv(self) -> (String, Self::V);
+	fn kv(self, types: Option<&PortableRegistry>) -> (String, Self::V);
 }
 
 /// Metadata minimal implementation.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Metadata {
+	/// Version of the metadata which this was converted from.
+	pub version: u32,
 	/// Pallet metadata(s).
 	pub pallets: FxHashMap<String, PalletMetadata>,
+	/// Type registry, which the type IDs refer to.
+	pub types: PortableRegistry,
+}
+impl Default for Metadata {
+	fn default() -> Self {
+		Self { version: 16, pallets: Default::default(), types: Registry::new().into() }
+	}
+}
+macro_rules! impl_from_runtime_metadata {
+	($($version:literal, $t:ty;)+) => {
+		$(
+			impl From<$t> for Metadata {
+				fn from(v: $t) -> Self {
+					let pallets = v.pallets.into_iter().map(|p| p.kv(Some(&v.types))).collect();
+
+					Self { version: $version, pallets, types: v.types }
+				}
+			}
+		)+
+	};
 }
-impl From<LatestRuntimeMetadata> for Metadata {
-	fn from(v: LatestRuntimeMetadata) -> Self {
-		Self { pallets: v.pallets.into_iter().map(KV::kv).collect() }
+impl_from_runtime_metadata! {
+	14, v14::RuntimeMetadataV14;
+	15, v15::RuntimeMetadataV15;
+	16, v16::RuntimeMetadataV16;
+}
+impl From<VersionedRuntimeMetadata> for Metadata {
+	fn from(v: VersionedRuntimeMetadata) -> Self {
+		match v {
+			VersionedRuntimeMetadata::V9(v) => v.into(),
+			VersionedRuntimeMetadata::V10(v) => v.into(),
+			VersionedRuntimeMetadata::V11(v) => v.into(),
+			VersionedRuntimeMetadata::V12(v) => v.into(),
+			VersionedRuntimeMetadata::V13(v) => v.into(),
+			VersionedRuntimeMetadata::V14(v) => v.into(),
+			VersionedRuntimeMetadata::V15(v) => v.into(),
+			VersionedRuntimeMetadata::V16(v) => v.into(),
+		}
+	}
+}
+impl Metadata {
+	fn variant<F>(&self, pallet: &str, name: &str, f: F) -> Option<VariantEntry<'_>>
+	where
+		F: Fn(&PalletMetadata) -> Option<&PalletEnumMetadata>,
+	{
+		let (pallet, e) = self.pallets.get_key_value(pallet).and_then(|(n, p)| Some((n, f(p)?)))?;
+		let (name, variant) = e.variants.get_key_value(name)?;
+
+		Some(VariantEntry { pallet, pallet_index: e.index, name, variant })
+	}
+
+	fn variant_by_index<F>(&self, pallet_index: u8, index: u8, f: F) -> Option<VariantEntry<'_>>
+	where
+		F: Fn(&PalletMetadata) -> Option<&PalletEnumMetadata>,
+	{
+		self.pallets.iter().find_map(|(pallet, p)| {
+			let e = f(p).filter(|e| e.index == pallet_index)?;
+			let (name, variant) = e.variants.iter().find(|(_, v)| v.index == index)?;
+
+			Some(VariantEntry { pallet, pallet_index, name, variant })
+		})
 	}
 }
 impl Meta for Metadata {
@@ -43,6 +124,34 @@ impl Meta for Metadata {
 			s.entries.get(item).map(|e| StorageEntry { prefix: &s.prefix, item, r#type: &e.r#type })
 		})
 	}
+
+	fn call(&self, pallet: &str, name: &str) -> Option<VariantEntry<'_>> {
+		self.variant(pallet, name, |p| p.calls.as_ref())
+	}
+
+	fn call_by_index(&self, pallet_index: u8, index: u8) -> Option<VariantEntry<'_>> {
+		self.variant_by_index(pallet_index, index, |p| p.calls.as_ref())
+	}
+
+	fn event(&self, pallet: &str, name: &str) -> Option<VariantEntry<'_>> {
+		self.variant(pallet, name, |p| p.events.as_ref())
+	}
+
+	fn event_by_index(&self, pallet_index: u8, index: u8) -> Option<VariantEntry<'_>> {
+		self.variant_by_index(pallet_index, index, |p| p.events.as_ref())
+	}
+
+	fn error(&self, pallet: &str, name: &str) -> Option<VariantEntry<'_>> {
+		self.variant(pallet, name, |p| p.error.as_ref())
+	}
+
+	fn error_by_index(&self, pallet_index: u8, index: u8) -> Option<VariantEntry<'_>> {
+		self.variant_by_index(pallet_index, index, |p| p.error.as_ref())
+	}
+
+	fn constant(&self, pallet: &str, name: &str) -> Option<&ConstantMetadata> {
+		self.pallets.get(pallet).and_then(|p| p.constants.get(name))
+	}
 }
 
 /// Storage entry minimal implementation.
@@ -55,6 +164,18 @@ pub struct StorageEntry<'a> {
 	pub r#type: &'a StorageEntryType,
 }
 
+/// Call, event or error entry.
+pub struct VariantEntry<'a> {
+	/// Pallet name.
+	pub pallet: &'a str,
+	/// Pallet index in the outer enum.
+	pub pallet_index: u8,
+	/// Variant name.
+	pub name: &'a str,
+	/// Variant metadata.
+	pub variant: &'a VariantMetadata,
+}
+
 /// Pallet metadata minimal implementation.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PalletMetadata {
@@ -62,19 +183,143 @@ pub struct PalletMetadata {
 	pub index: u8,
 	/// Pallet storage metadata.
 	pub storages: Option<PalletStorageMetadata>,
-	// pub calls: Option<CallMetadata>,
-	// pub events: Option<EventMetadata>,
-	// pub constants: Vec<ConstantMetadata>,
-	// pub error: Option<ErrorMetadata>,
+	/// Pallet call metadata.
+	pub calls: Option<PalletEnumMetadata>,
+	/// Pallet event metadata.
+	pub events: Option<PalletEnumMetadata>,
+	/// Pallet constant metadata.
+	pub constants: FxHashMap<String, ConstantMetadata>,
+	/// Pallet error metadata.
+	pub error: Option<PalletEnumMetadata>,
+}
+macro_rules! impl_kv_for_pallet_metadata {
+	($($t:ty,)+) => {
+		$(
+			impl KV for $t {
+				type V = PalletMetadata;
+
+				fn kv(self, types: Option<&PortableRegistry>) -> (String, Self::V) {
+					let index = self.index;
+					let r#enum = |ty: u32| PalletEnumMetadata::new(ty, index, types);
+
+					(
+						self.name,
+						PalletMetadata {
+							index,
+							storages: self
+								.storage
+								.map(|s| PalletStorageMetadata::new(s.prefix, s.entries, types)),
+							calls: self.calls.map(|c| r#enum(c.ty.id)),
+							events: self.event.map(|e| r#enum(e.ty.id)),
+							constants: self
+								.constants
+								.into_iter()
+								.map(|c| {
+									(
+										c.name,
+										ConstantMetadata {
+											r#type: c.ty.id,
+											type_name: None,
+											value: c.value,
+											docs: c.docs,
+										},
+									)
+								})
+								.collect(),
+							error: self.error.map(|e| r#enum(e.ty.id)),
+						},
+					)
+				}
+			}
+		)+
+	};
+}
+impl_kv_for_pallet_metadata! {
+	v14::PalletMetadata<PortableForm>,
+	v15::PalletMetadata<PortableForm>,
+	v16::PalletMetadata<PortableForm>,
 }
-impl KV for frame_metadata::PalletMetadata<PortableForm> {
-	type V = PalletMetadata;
 
-	fn kv(self) -> (String, Self::V) {
-		(self.name, PalletMetadata { index: self.index, storages: self.storage.map(Into::into) })
+/// Pallet call, event or error enum minimal implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PalletEnumMetadata {
+	/// Type ID of the enum, `0` if the metadata has no type registry (legacy metadata).
+	pub r#type: u32,
+	/// Pallet index in the outer enum.
+	///
+	/// This is the pallet index, except the calls and events of the legacy metadata before V12,
+	/// which are indexed by the position among the modules having them.
+	pub index: u8,
+	/// Enum variants.
+	pub variants: FxHashMap<String, VariantMetadata>,
+}
+impl PalletEnumMetadata {
+	fn new(r#type: u32, index: u8, types: Option<&PortableRegistry>) -> Self {
+		let variants = match types.and_then(|t| t.resolve(r#type)).map(|t| &t.type_def) {
+			Some(TypeDef::Variant(v)) => v
+				.variants
+				.iter()
+				.map(|v| {
+					(
+						v.name.clone(),
+						VariantMetadata {
+							index: v.index,
+							fields: v
+								.fields
+								.iter()
+								.map(|f| FieldMetadata {
+									name: f.name.clone(),
+									r#type: f.ty.id,
+									type_name: f.type_name.clone(),
+								})
+								.collect(),
+							docs: v.docs.clone(),
+						},
+					)
+				})
+				.collect(),
+			_ => Default::default(),
+		};
+
+		Self { r#type, index, variants }
 	}
 }
 
+/// Call, event or error variant minimal implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VariantMetadata {
+	/// Variant index.
+	pub index: u8,
+	/// Variant fields.
+	pub fields: Vec<FieldMetadata>,
+	/// Variant documentation.
+	pub docs: Vec<String>,
+}
+
+/// Variant field minimal implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldMetadata {
+	/// Field name, [`None`] if this is an unnamed field.
+	pub name: Option<String>,
+	/// Type ID of the field, `0` if the metadata has no type registry (legacy metadata).
+	pub r#type: u32,
+	/// Type name of the field.
+	pub type_name: Option<String>,
+}
+
+/// Pallet constant minimal implementation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConstantMetadata {
+	/// Type ID of the constant, `0` if the metadata has no type registry (legacy metadata).
+	pub r#type: u32,
+	/// Type name of the constant, only available in the legacy metadata.
+	pub type_name: Option<String>,
+	/// SCALE encoded value.
+	pub value: Vec<u8>,
+	/// Constant documentation.
+	pub docs: Vec<String>,
+}
+
 /// Pallet storage minimal implementation.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PalletStorageMetadata {
@@ -83,9 +328,24 @@ pub struct PalletStorageMetadata {
 	/// Pallet storage entries.
 	pub entries: FxHashMap<String, StorageEntryMetadata>,
 }
-impl From<frame_metadata::PalletStorageMetadata<PortableForm>> for PalletStorageMetadata {
-	fn from(v: frame_metadata::PalletStorageMetadata<PortableForm>) -> Self {
-		Self { prefix: v.prefix, entries: v.entries.into_iter().map(KV::kv).collect() }
+impl PalletStorageMetadata {
+	fn new<E>(prefix: String, entries: Vec<E>, types: Option<&PortableRegistry>) -> Self
+	where
+		E: KV<V = StorageEntryMetadata>,
+	{
+		Self { prefix, entries: entries.into_iter().map(|e| e.kv(types)).collect() }
+	}
+}
+/// The key sizes can not be resolved without the type registry, they will be [`None`].
+impl From<v14::PalletStorageMetadata<PortableForm>> for PalletStorageMetadata {
+	fn from(v: v14::PalletStorageMetadata<PortableForm>) -> Self {
+		Self::new(v.prefix, v.entries, None)
+	}
+}
+/// The key sizes can not be resolved without the type registry, they will be [`None`].
+impl From<v16::PalletStorageMetadata<PortableForm>> for PalletStorageMetadata {
+	fn from(v: v16::PalletStorageMetadata<PortableForm>) -> Self {
+		Self::new(v.prefix, v.entries, None)
 	}
 }
 
@@ -94,12 +354,87 @@ impl From<frame_metadata::PalletStorageMetadata<PortableForm>> for PalletStorage
 pub struct StorageEntryMetadata {
 	/// Storage entry type.
 	pub r#type: StorageEntryType,
+	/// Storage entry modifier.
+	pub modifier: StorageEntryModifier,
+	/// SCALE encoded default value, which is returned if the key is absent.
+	pub default: Vec<u8>,
+	/// Type ID of the key, `0` if this is a plain storage or the metadata has no type registry
+	/// (legacy metadata).
+	pub key_type: u32,
+	/// Type names of the map keys, only available in the legacy metadata.
+	pub key_type_names: Vec<String>,
+	/// Type ID of the value, `0` if the metadata has no type registry (legacy metadata).
+	pub value_type: u32,
+	/// Type name of the value, only available in the legacy metadata.
+	pub value_type_name: Option<String>,
+	/// SCALE encoded size of each map key, [`None`] if the size is not fixed or unknown.
+	///
+	/// Empty if this is a plain storage.
+	pub key_sizes: Vec<Option<usize>>,
 }
-impl KV for frame_metadata::StorageEntryMetadata<PortableForm> {
+impl StorageEntryMetadata {
+	fn new(
+		modifier: v14::StorageEntryModifier,
+		ty: v14::StorageEntryType<PortableForm>,
+		default: Vec<u8>,
+		types: Option<&PortableRegistry>,
+	) -> Self {
+		let key_sizes = match &ty {
+			v14::StorageEntryType::Plain(_) => Vec::new(),
+			v14::StorageEntryType::Map { hashers, key, .. } => {
+				let size = |id| types.and_then(|t| encoded_size(t, id, 0));
+
+				match types.and_then(|t| t.resolve(key.id)).map(|t| &t.type_def) {
+					Some(TypeDef::Tuple(t))
+						if hashers.len() > 1 && t.fields.len() == hashers.len() =>
+						t.fields.iter().map(|f| size(f.id)).collect(),
+					_ if hashers.len() == 1 => vec![size(key.id)],
+					_ => vec![None; hashers.len()],
+				}
+			},
+		};
+		let (key_type, value_type) = match &ty {
+			v14::StorageEntryType::Plain(v) => (0, v.id),
+			v14::StorageEntryType::Map { key, value, .. } => (key.id, value.id),
+		};
+
+		Self {
+			r#type: ty.into(),
+			modifier: modifier.into(),
+			default,
+			key_type,
+			key_type_names: Vec::new(),
+			value_type,
+			value_type_name: None,
+			key_sizes,
+		}
+	}
+
+	/// Pair each map hasher with its key size.
+	///
+	/// Empty if this is a plain storage.
+	pub fn hashers_with_key_sizes(&self) -> Vec<(&StorageHasher, Option<usize>)> {
+		match &self.r#type {
+			StorageEntryType::Plain => Vec::new(),
+			StorageEntryType::Map(hashers) => hashers
+				.iter()
+				.zip(self.key_sizes.iter().copied().chain(std::iter::repeat(None)))
+				.collect(),
+		}
+	}
+}
+impl KV for v14::StorageEntryMetadata<PortableForm> {
 	type V = StorageEntryMetadata;
 
-	fn kv(self) -> (String, Self::V) {
-		(self.name, StorageEntryMetadata { r#type: self.ty.into() })
+	fn kv(self, types: Option<&PortableRegistry>) -> (String, Self::V) {
+		(self.name, StorageEntryMetadata::new(self.modifier, self.ty, self.default, types))
+	}
+}
+impl KV for v16::StorageEntryMetadata<PortableForm> {
+	type V = StorageEntryMetadata;
+
+	fn kv(self, types: Option<&PortableRegistry>) -> (String, Self::V) {
+		(self.name, StorageEntryMetadata::new(self.modifier, self.ty, self.default, types))
 	}
 }
 
@@ -111,25 +446,78 @@ pub enum StorageEntryType {
 	/// Map storage.
 	Map(Vec<StorageHasher>),
 }
-impl From<frame_metadata::StorageEntryType<PortableForm>> for StorageEntryType {
-	fn from(v: frame_metadata::StorageEntryType<PortableForm>) -> Self {
+impl From<v14::StorageEntryType<PortableForm>> for StorageEntryType {
+	fn from(v: v14::StorageEntryType<PortableForm>) -> Self {
 		match v {
-			frame_metadata::StorageEntryType::Plain(_) => Self::Plain,
-			frame_metadata::StorageEntryType::Map { hashers, .. } => Self::Map(
+			v14::StorageEntryType::Plain(_) => Self::Plain,
+			v14::StorageEntryType::Map { hashers, .. } => Self::Map(
 				hashers
 					.into_iter()
 					.map(|h| match h {
-						frame_metadata::StorageHasher::Blake2_128 => StorageHasher::Blake2_128,
-						frame_metadata::StorageHasher::Blake2_256 => StorageHasher::Blake2_256,
-						frame_metadata::StorageHasher::Blake2_128Concat =>
-							StorageHasher::Blake2_128Concat,
-						frame_metadata::StorageHasher::Twox128 => StorageHasher::Twox128,
-						frame_metadata::StorageHasher::Twox256 => StorageHasher::Twox256,
-						frame_metadata::StorageHasher::Twox64Concat => StorageHasher::Twox64Concat,
-						frame_metadata::StorageHasher::Identity => StorageHasher::Identity,
+						v14::StorageHasher::Blake2_128 => StorageHasher::Blake2_128,
+						v14::StorageHasher::Blake2_256 => StorageHasher::Blake2_256,
+						v14::StorageHasher::Blake2_128Concat => StorageHasher::Blake2_128Concat,
+						v14::StorageHasher::Twox128 => StorageHasher::Twox128,
+						v14::StorageHasher::Twox256 => StorageHasher::Twox256,
+						v14::StorageHasher::Twox64Concat => StorageHasher::Twox64Concat,
+						v14::StorageHasher::Identity => StorageHasher::Identity,
 					})
 					.collect(),
 			),
 		}
 	}
 }
+
+/// Storage entry modifier minimal implementation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageEntryModifier {
+	/// The value is [`None`] if the key is absent.
+	Optional,
+	/// The value is the default value if the key is absent.
+	Default,
+}
+impl From<v14::StorageEntryModifier> for StorageEntryModifier {
+	fn from(v: v14::StorageEntryModifier) -> Self {
+		match v {
+			v14::StorageEntryModifier::Optional => Self::Optional,
+			v14::StorageEntryModifier::Default => Self::Default,
+		}
+	}
+}
+
+// Get the SCALE encoded size of the type, [`None`] if it is not fixed.
+fn encoded_size(types: &PortableRegistry, id: u32, depth: u8) -> Option<usize> {
+	// Recursive types are never fixed size.
+	if depth > 32 {
+		return None;
+	}
+
+	let size = |id| encoded_size(types, id, depth + 1);
+
+	match &types.resolve(id)?.type_def {
+		TypeDef::Composite(c) => c.fields.iter().map(|f| size(f.ty.id)).sum(),
+		TypeDef::Variant(v) => {
+			let mut sizes = v
+				.variants
+				.iter()
+				.map(|v| v.fields.iter().map(|f| size(f.ty.id)).sum::<Option<usize>>());
+			// An enum without variants has no valid encoding.
+			let first = sizes.next()??;
+
+			// The variant index takes one byte.
+			sizes.all(|s| s == Some(first)).then_some(first + 1)
+		},
+		TypeDef::Array(a) => size(a.type_param.id)?.checked_mul(a.len as _),
+		TypeDef::Tuple(t) => t.fields.iter().map(|f| size(f.id)).sum(),
+		TypeDef::Primitive(p) => match p {
+			TypeDefPrimitive::Bool | TypeDefPrimitive::U8 | TypeDefPrimitive::I8 => Some(1),
+			TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => Some(2),
+			TypeDefPrimitive::Char | TypeDefPrimitive::U32 | TypeDefPrimitive::I32 => Some(4),
+			TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => Some(8),
+			TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => Some(16),
+			TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => Some(32),
+			TypeDefPrimitive::Str => None,
+		},
+		TypeDef::Sequence(_) | TypeDef::Compact(_) | TypeDef::BitSequence(_) => None,
+	}
+}
@@ -0,0 +1,305 @@
+//! Decode the extrinsic against the merkleized types and collect the accessed leaves.
+
+// std
+use std::collections::BTreeSet;
+// crates.io
+use parity_scale_codec::{Compact, Decode};
+// substrate-minimal
+use super::{prepare::Prepared, *};
+use crate::{Error, Result};
+
+// The decoding is recursive, limit the depth to prevent the stack overflow.
+const MAX_DEPTH: usize = 1_000;
+
+type Fallible<T> = std::result::Result<T, String>;
+
+pub(super) struct Collector<'a> {
+	prepared: &'a Prepared,
+	accessed: BTreeSet<LeafId>,
+}
+impl<'a> Collector<'a> {
+	pub(super) fn new(prepared: &'a Prepared) -> Self {
+		Self { prepared, accessed: BTreeSet::new() }
+	}
+
+	// Collect the leaves accessed by the extrinsic, and by the additional signed data if provided.
+	pub(super) fn extrinsic(
+		mut self,
+		mut extrinsic: &[u8],
+		additional_signed: Option<&[u8]>,
+	) -> Result<BTreeSet<LeafId>> {
+		let metadata = &self.prepared.extrinsic;
+		let input = &mut extrinsic;
+
+		let len = read::<Compact<u32>>(input)
+			.map_err(|e| Error::MetadataHash(format!("failed to decode the length, {e}")))?
+			.0 as usize;
+
+		if len != input.len() {
+			Err(Error::MetadataHash(format!(
+				"expected {len} bytes of the extrinsic, found {}",
+				input.len()
+			)))?;
+		}
+
+		let version = read::<u8>(input)
+			.map_err(|e| Error::MetadataHash(format!("failed to decode the version, {e}")))?;
+
+		if version & 0b0111_1111 != 4 {
+			Err(Error::MetadataHash(format!(
+				"unsupported extrinsic version {}",
+				version & 0b0111_1111
+			)))?;
+		}
+		if version & 0b1000_0000 != 0 {
+			self.decode_as(metadata.address_ty, input, "address")?;
+			self.decode_as(metadata.signature_ty, input, "signature")?;
+
+			for e in &metadata.signed_extensions {
+				self.decode_as(
+					e.included_in_extrinsic,
+					input,
+					&format!("extension {}", e.identifier),
+				)?;
+			}
+		}
+
+		self.decode_as(metadata.call_ty, input, "call")?;
+
+		if let Some(mut additional_signed) = additional_signed {
+			for e in &metadata.signed_extensions {
+				self.decode_as(
+					e.included_in_signed_data,
+					&mut additional_signed,
+					&format!("extension {} in the signed data", e.identifier),
+				)?;
+			}
+
+			if !additional_signed.is_empty() {
+				Err(Error::MetadataHash(format!(
+					"{} bytes left in the signed data",
+					additional_signed.len()
+				)))?;
+			}
+		}
+
+		if !input.is_empty() {
+			Err(Error::MetadataHash(format!("{} bytes left in the extrinsic", input.len())))?;
+		}
+
+		Ok(self.accessed)
+	}
+
+	// Collect the leaves accessed by the call, and by the signed extrinsic parts if provided.
+	//
+	// The address and the signature are unknown before signing, so all their leaves are collected.
+	pub(super) fn extrinsic_parts(
+		mut self,
+		mut call: &[u8],
+		signed_data: Option<SignedExtrinsicData>,
+	) -> Result<BTreeSet<LeafId>> {
+		let metadata = &self.prepared.extrinsic;
+		let input = &mut call;
+
+		self.decode_as(metadata.call_ty, input, "call")?;
+
+		if let Some(SignedExtrinsicData {
+			mut included_in_extrinsic,
+			mut included_in_signed_data,
+		}) = signed_data
+		{
+			self.collect_all(metadata.address_ty);
+			self.collect_all(metadata.signature_ty);
+
+			for e in &metadata.signed_extensions {
+				self.decode_as(
+					e.included_in_extrinsic,
+					&mut included_in_extrinsic,
+					&format!("extension {}", e.identifier),
+				)?;
+				self.decode_as(
+					e.included_in_signed_data,
+					&mut included_in_signed_data,
+					&format!("extension {} in the signed data", e.identifier),
+				)?;
+			}
+
+			if !included_in_extrinsic.is_empty() {
+				Err(Error::MetadataHash(format!(
+					"{} bytes left in the extensions",
+					included_in_extrinsic.len()
+				)))?;
+			}
+			if !included_in_signed_data.is_empty() {
+				Err(Error::MetadataHash(format!(
+					"{} bytes left in the signed data",
+					included_in_signed_data.len()
+				)))?;
+			}
+		}
+
+		if !input.is_empty() {
+			Err(Error::MetadataHash(format!("{} bytes left in the call", input.len())))?;
+		}
+
+		Ok(self.accessed)
+	}
+
+	fn decode_as(&mut self, ty: TypeRef, input: &mut &[u8], what: &str) -> Result<()> {
+		self.decode(ty, input, 0)
+			.map_err(|e| Error::MetadataHash(format!("failed to decode the {what}, {e}")))
+	}
+
+	fn decode(&mut self, ty: TypeRef, input: &mut &[u8], depth: usize) -> Fallible<()> {
+		let id = match ty {
+			TypeRef::Bool => return read::<bool>(input).map(drop),
+			TypeRef::Char => {
+				let c = read::<u32>(input)?;
+
+				return char::from_u32(c).map(drop).ok_or_else(|| format!("invalid char {c}"));
+			},
+			TypeRef::Str => return read::<String>(input).map(drop),
+			TypeRef::U8 | TypeRef::I8 => return skip(input, 1),
+			TypeRef::U16 | TypeRef::I16 => return skip(input, 2),
+			TypeRef::U32 | TypeRef::I32 => return skip(input, 4),
+			TypeRef::U64 | TypeRef::I64 => return skip(input, 8),
+			TypeRef::U128 | TypeRef::I128 => return skip(input, 16),
+			TypeRef::U256 | TypeRef::I256 => return skip(input, 32),
+			TypeRef::CompactU8 => return read::<Compact<u8>>(input).map(drop),
+			TypeRef::CompactU16 => return read::<Compact<u16>>(input).map(drop),
+			TypeRef::CompactU32 => return read::<Compact<u32>>(input).map(drop),
+			TypeRef::CompactU64 => return read::<Compact<u64>>(input).map(drop),
+			TypeRef::CompactU128 => return read::<Compact<u128>>(input).map(drop),
+			TypeRef::CompactU256 => return Err("compact u256 is not supported".into()),
+			TypeRef::Void => return Ok(()),
+			TypeRef::ById(id) => id.0,
+		};
+
+		if depth >= MAX_DEPTH {
+			Err("reached the maximum depth")?;
+		}
+
+		let prepared = self.prepared;
+		let mut types = prepared.types.range((id, None)..=(id, Some(u32::MAX))).map(|(_, t)| t);
+		let ty = types.clone().next().ok_or_else(|| format!("type #{id} not found"))?;
+
+		match &ty.type_def {
+			TypeDef::Composite(fields) => {
+				self.accessed.insert((id, None));
+
+				fields.iter().try_for_each(|f| self.decode(f.ty, input, depth + 1))
+			},
+			TypeDef::Enumeration(_) => {
+				let index = read::<u8>(input)? as u32;
+				let variant = types
+					.find_map(|t| match &t.type_def {
+						TypeDef::Enumeration(v) if v.index.0 == index => Some(v),
+						_ => None,
+					})
+					.ok_or_else(|| format!("variant {index} of type #{id} not found"))?;
+
+				self.accessed.insert((id, Some(index)));
+
+				variant.fields.iter().try_for_each(|f| self.decode(f.ty, input, depth + 1))
+			},
+			TypeDef::Sequence(element) => {
+				self.accessed.insert((id, None));
+
+				let len = read::<Compact<u32>>(input)?.0;
+
+				// The zero size elements consume no input, decoding one of them collects the same
+				// leaves as decoding all of them.
+				if self.is_zero_size(*element, depth + 1) {
+					return if len == 0 { Ok(()) } else { self.decode(*element, input, depth + 1) };
+				}
+				if len as usize > input.len() {
+					Err(format!("sequence length {len} exceeds the {} bytes left", input.len()))?;
+				}
+
+				(0..len).try_for_each(|_| self.decode(*element, input, depth + 1))
+			},
+			TypeDef::Array(a) => {
+				self.accessed.insert((id, None));
+
+				(0..a.len).try_for_each(|_| self.decode(a.type_param, input, depth + 1))
+			},
+			TypeDef::Tuple(elements) => {
+				self.accessed.insert((id, None));
+
+				elements.iter().try_for_each(|e| self.decode(*e, input, depth + 1))
+			},
+			TypeDef::BitSequence(b) => {
+				self.accessed.insert((id, None));
+
+				if !matches!(b.num_bytes, 1 | 2 | 4 | 8) {
+					Err(format!("unsupported bit store size {}", b.num_bytes))?;
+				}
+
+				let store_bits = 8 * b.num_bytes as usize;
+				let bits = read::<Compact<u32>>(input)?.0 as usize;
+
+				skip(input, bits.div_ceil(store_bits) * b.num_bytes as usize)
+			},
+		}
+	}
+
+	// Whether the type is encoded into zero bytes, the enumerations take at least the index byte.
+	fn is_zero_size(&self, ty: TypeRef, depth: usize) -> bool {
+		let id = match ty {
+			TypeRef::Void => return true,
+			TypeRef::ById(id) => id.0,
+			_ => return false,
+		};
+
+		if depth >= MAX_DEPTH {
+			return false;
+		}
+
+		match self.prepared.types.get(&(id, None)).map(|t| &t.type_def) {
+			Some(TypeDef::Composite(fields)) =>
+				fields.iter().all(|f| self.is_zero_size(f.ty, depth + 1)),
+			Some(TypeDef::Array(a)) => a.len == 0 || self.is_zero_size(a.type_param, depth + 1),
+			Some(TypeDef::Tuple(elements)) =>
+				elements.iter().all(|e| self.is_zero_size(*e, depth + 1)),
+			_ => false,
+		}
+	}
+
+	// Collect all the leaves reachable from the type.
+	fn collect_all(&mut self, ty: TypeRef) {
+		let TypeRef::ById(id) = ty else { return };
+		let prepared = self.prepared;
+
+		for (leaf_id, ty) in prepared.types.range((id.0, None)..=(id.0, Some(u32::MAX))) {
+			if !self.accessed.insert(*leaf_id) {
+				continue;
+			}
+
+			match &ty.type_def {
+				TypeDef::Composite(fields) => fields.iter().for_each(|f| self.collect_all(f.ty)),
+				TypeDef::Enumeration(v) => v.fields.iter().for_each(|f| self.collect_all(f.ty)),
+				TypeDef::Sequence(element) => self.collect_all(*element),
+				TypeDef::Array(a) => self.collect_all(a.type_param),
+				TypeDef::Tuple(elements) => elements.iter().for_each(|e| self.collect_all(*e)),
+				TypeDef::BitSequence(_) => (),
+			}
+		}
+	}
+}
+
+fn read<T>(input: &mut &[u8]) -> Fallible<T>
+where
+	T: Decode,
+{
+	T::decode(input).map_err(|e| e.to_string())
+}
+
+fn skip(input: &mut &[u8], len: usize) -> Fallible<()> {
+	if input.len() < len {
+		Err(format!("expected {len} bytes, found {}", input.len()))?;
+	}
+
+	*input = &input[len..];
+
+	Ok(())
+}
@@ -0,0 +1,107 @@
+//! Metadata hash of the `CheckMetadataHash` signed extension, specified by
+//! [RFC-0078](https://polkadot-fellows.github.io/RFCs/approved/0078-merkleized-metadata.html).
+//!
+//! The types accessible from the extrinsic are converted into the merkleized types, which are the
+//! leaves of a merkle tree. The metadata hash commits to the tree root, the extrinsic metadata and
+//! the chain information, so an offline signer can verify the types used by an extrinsic with a
+//! [`Proof`] instead of the whole metadata.
+//!
+//! Only the V15 metadata is supported.
+
+mod extrinsic;
+use extrinsic::Collector;
+
+mod prepare;
+use prepare::Prepared;
+
+mod tree;
+use tree::MerkleTree;
+pub use tree::Proof;
+
+mod types;
+pub use types::*;
+
+// substrate-minimal
+use crate::{Error, Result, VersionedRuntimeMetadata};
+
+// The merkleized type ID and the variant index if the type is an enum.
+type LeafId = (u32, Option<u32>);
+
+/// Chain information, which is committed by the metadata digest.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainInfo {
+	pub spec_version: u32,
+	pub spec_name: String,
+	pub base58_prefix: u16,
+	pub decimals: u8,
+	pub token_symbol: String,
+}
+
+/// Data of a signed extrinsic besides the call, which is known before signing.
+#[derive(Clone, Copy, Debug)]
+pub struct SignedExtrinsicData<'a> {
+	/// Encoded signed extensions data, which is included in the extrinsic.
+	pub included_in_extrinsic: &'a [u8],
+	/// Encoded signed extensions additional data, which is included in the signed payload.
+	pub included_in_signed_data: &'a [u8],
+}
+
+/// Compute the [`MetadataDigest`] of the metadata.
+pub fn metadata_digest(
+	metadata: &VersionedRuntimeMetadata,
+	chain_info: ChainInfo,
+) -> Result<MetadataDigest> {
+	let prepared = prepare(metadata)?;
+
+	Ok(MetadataDigest::V1 {
+		extrinsic_metadata_hash: prepared.extrinsic.hash(),
+		types_tree_root: MerkleTree::new(prepared.types).root(),
+		spec_version: chain_info.spec_version,
+		spec_name: chain_info.spec_name,
+		base58_prefix: chain_info.base58_prefix,
+		decimals: chain_info.decimals,
+		token_symbol: chain_info.token_symbol,
+	})
+}
+
+/// Compute the metadata hash, which is the hash of the [`MetadataDigest`].
+pub fn metadata_hash(metadata: &VersionedRuntimeMetadata, chain_info: ChainInfo) -> Result<Hash> {
+	Ok(metadata_digest(metadata, chain_info)?.hash())
+}
+
+/// Generate the [`Proof`] of the types used by the encoded extrinsic.
+///
+/// The types of the additional signed data are included if it is provided.
+pub fn proof_for_extrinsic(
+	metadata: &VersionedRuntimeMetadata,
+	extrinsic: &[u8],
+	additional_signed: Option<&[u8]>,
+) -> Result<Proof> {
+	let prepared = prepare(metadata)?;
+	let accessed = Collector::new(&prepared).extrinsic(extrinsic, additional_signed)?;
+
+	MerkleTree::new(prepared.types).proof(accessed)
+}
+
+/// Generate the [`Proof`] of the types used by the encoded call, and by the signed extrinsic parts
+/// if they are provided.
+///
+/// This is useful before signing, while the address and the signature are unknown.
+pub fn proof_for_extrinsic_parts(
+	metadata: &VersionedRuntimeMetadata,
+	call: &[u8],
+	signed_data: Option<SignedExtrinsicData>,
+) -> Result<Proof> {
+	let prepared = prepare(metadata)?;
+	let accessed = Collector::new(&prepared).extrinsic_parts(call, signed_data)?;
+
+	MerkleTree::new(prepared.types).proof(accessed)
+}
+
+fn prepare(metadata: &VersionedRuntimeMetadata) -> Result<Prepared> {
+	match metadata {
+		VersionedRuntimeMetadata::V15(metadata) => Prepared::new(metadata),
+		metadata => Err(Error::UnsupportedVersion(metadata.version())),
+	}
+}
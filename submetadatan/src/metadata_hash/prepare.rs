@@ -0,0 +1,277 @@
+//! Convert the FRAME metadata into the merkleized types.
+
+// std
+use std::collections::{BTreeMap, BTreeSet};
+// crates.io
+use frame_metadata::v15::RuntimeMetadataV15;
+use scale_info::{
+	form::PortableForm, PortableRegistry, Type as FrameType, TypeDef as FrameTypeDef,
+	TypeDefBitSequence as FrameTypeDefBitSequence, TypeDefPrimitive,
+};
+// substrate-minimal
+use super::*;
+use crate::{Error, Result};
+
+// The merkleized extrinsic metadata and types, which are ordered as the leaves.
+pub(super) struct Prepared {
+	pub(super) extrinsic: ExtrinsicMetadata,
+	pub(super) types: BTreeMap<LeafId, Type>,
+}
+impl Prepared {
+	pub(super) fn new(metadata: &RuntimeMetadataV15) -> Result<Self> {
+		let registry = &metadata.types;
+		let extrinsic = &metadata.extrinsic;
+		let mut accessible = BTreeSet::new();
+
+		[extrinsic.call_ty.id, extrinsic.address_ty.id, extrinsic.signature_ty.id]
+			.into_iter()
+			.chain(
+				extrinsic.signed_extensions.iter().flat_map(|e| [e.ty.id, e.additional_signed.id]),
+			)
+			.try_for_each(|id| collect_accessible(registry, id, &mut accessible))?;
+
+		// Only the basic types are leaves, the others are inlined into the references.
+		let ids = accessible
+			.into_iter()
+			.filter(|id| registry.resolve(*id).is_some_and(is_basic))
+			.enumerate()
+			.map(|(new_id, id)| (id, new_id as u32))
+			.collect::<BTreeMap<_, _>>();
+		let converter = Converter { registry, ids: &ids };
+		let extrinsic = ExtrinsicMetadata {
+			version: extrinsic.version,
+			address_ty: converter.type_ref(extrinsic.address_ty.id)?,
+			call_ty: converter.type_ref(extrinsic.call_ty.id)?,
+			signature_ty: converter.type_ref(extrinsic.signature_ty.id)?,
+			signed_extensions: extrinsic
+				.signed_extensions
+				.iter()
+				.map(|e| {
+					Ok(SignedExtensionMetadata {
+						identifier: e.identifier.clone(),
+						included_in_extrinsic: converter.type_ref(e.ty.id)?,
+						included_in_signed_data: converter.type_ref(e.additional_signed.id)?,
+					})
+				})
+				.collect::<Result<_>>()?,
+		};
+		let mut types = BTreeMap::new();
+
+		for (id, new_id) in &ids {
+			for ty in converter.types(converter.resolve(*id)?, *new_id)? {
+				let variant = match &ty.type_def {
+					TypeDef::Enumeration(v) => Some(v.index.0),
+					_ => None,
+				};
+
+				types.insert((*new_id, variant), ty);
+			}
+		}
+
+		Ok(Self { extrinsic, types })
+	}
+}
+
+struct Converter<'a> {
+	registry: &'a PortableRegistry,
+	// Map the registry IDs to the merkleized IDs.
+	ids: &'a BTreeMap<u32, u32>,
+}
+impl<'a> Converter<'a> {
+	fn resolve(&self, id: u32) -> Result<&'a FrameType<PortableForm>> {
+		self.registry
+			.resolve(id)
+			.ok_or_else(|| Error::MetadataHash(format!("type #{id} not found")))
+	}
+
+	fn type_ref(&self, id: u32) -> Result<TypeRef> {
+		let ty = self.resolve(id)?;
+		let type_ref = match &ty.type_def {
+			FrameTypeDef::Primitive(p) => match p {
+				TypeDefPrimitive::Bool => TypeRef::Bool,
+				TypeDefPrimitive::Char => TypeRef::Char,
+				TypeDefPrimitive::Str => TypeRef::Str,
+				TypeDefPrimitive::U8 => TypeRef::U8,
+				TypeDefPrimitive::U16 => TypeRef::U16,
+				TypeDefPrimitive::U32 => TypeRef::U32,
+				TypeDefPrimitive::U64 => TypeRef::U64,
+				TypeDefPrimitive::U128 => TypeRef::U128,
+				TypeDefPrimitive::U256 => TypeRef::U256,
+				TypeDefPrimitive::I8 => TypeRef::I8,
+				TypeDefPrimitive::I16 => TypeRef::I16,
+				TypeDefPrimitive::I32 => TypeRef::I32,
+				TypeDefPrimitive::I64 => TypeRef::I64,
+				TypeDefPrimitive::I128 => TypeRef::I128,
+				TypeDefPrimitive::I256 => TypeRef::I256,
+			},
+			FrameTypeDef::Compact(_) => match self.primitives(id)?.as_slice() {
+				[] => TypeRef::Void,
+				[TypeDefPrimitive::U8] => TypeRef::CompactU8,
+				[TypeDefPrimitive::U16] => TypeRef::CompactU16,
+				[TypeDefPrimitive::U32] => TypeRef::CompactU32,
+				[TypeDefPrimitive::U64] => TypeRef::CompactU64,
+				[TypeDefPrimitive::U128] => TypeRef::CompactU128,
+				[TypeDefPrimitive::U256] => TypeRef::CompactU256,
+				ps => Err(Error::MetadataHash(format!("unsupported compact type #{id}, {ps:?}")))?,
+			},
+			_ if !is_basic(ty) => TypeRef::Void,
+			_ => TypeRef::ById(
+				self.ids
+					.get(&id)
+					.ok_or_else(|| Error::MetadataHash(format!("type #{id} is not accessible")))?
+					.into(),
+			),
+		};
+
+		Ok(type_ref)
+	}
+
+	fn types(&self, ty: &FrameType<PortableForm>, id: u32) -> Result<Vec<Type>> {
+		let path = ty.path.segments.clone();
+		let type_def = match &ty.type_def {
+			FrameTypeDef::Composite(c) => TypeDef::Composite(self.fields(&c.fields)?),
+			FrameTypeDef::Variant(v) => {
+				let mut variants = v.variants.iter().collect::<Vec<_>>();
+
+				variants.sort_by_key(|v| v.index);
+
+				return variants
+					.into_iter()
+					.map(|v| {
+						Ok(Type {
+							path: path.clone(),
+							type_def: TypeDef::Enumeration(EnumerationVariant {
+								name: v.name.clone(),
+								fields: self.fields(&v.fields)?,
+								index: (v.index as u32).into(),
+							}),
+							type_id: id.into(),
+						})
+					})
+					.collect();
+			},
+			FrameTypeDef::Sequence(s) => TypeDef::Sequence(self.type_ref(s.type_param.id)?),
+			FrameTypeDef::Array(a) => TypeDef::Array(TypeDefArray {
+				len: a.len,
+				type_param: self.type_ref(a.type_param.id)?,
+			}),
+			FrameTypeDef::Tuple(t) =>
+				TypeDef::Tuple(t.fields.iter().map(|f| self.type_ref(f.id)).collect::<Result<_>>()?),
+			FrameTypeDef::BitSequence(b) => TypeDef::BitSequence(self.bits(b)?),
+			FrameTypeDef::Primitive(_) | FrameTypeDef::Compact(_) => return Ok(Vec::new()),
+		};
+
+		Ok(vec![Type { path, type_def, type_id: id.into() }])
+	}
+
+	fn fields(&self, fields: &[scale_info::Field<PortableForm>]) -> Result<Vec<Field>> {
+		fields
+			.iter()
+			.map(|f| {
+				Ok(Field {
+					name: f.name.clone(),
+					ty: self.type_ref(f.ty.id)?,
+					type_name: f.type_name.clone(),
+				})
+			})
+			.collect()
+	}
+
+	fn bits(&self, bits: &FrameTypeDefBitSequence<PortableForm>) -> Result<TypeDefBitSequence> {
+		let num_bytes = match self.primitives(bits.bit_store_type.id)?.as_slice() {
+			[TypeDefPrimitive::U8] => 1,
+			[TypeDefPrimitive::U16] => 2,
+			[TypeDefPrimitive::U32] => 4,
+			[TypeDefPrimitive::U64] => 8,
+			ps => Err(Error::MetadataHash(format!("unsupported bit store type, {ps:?}")))?,
+		};
+		let order = self
+			.resolve(bits.bit_order_type.id)?
+			.path
+			.segments
+			.iter()
+			.find(|s| *s == "Lsb0" || *s == "Msb0")
+			.ok_or_else(|| Error::MetadataHash("unsupported bit order type".into()))?;
+
+		Ok(TypeDefBitSequence { num_bytes, least_significant_bit_first: order == "Lsb0" })
+	}
+
+	// Collect the primitives reachable from the type.
+	fn primitives(&self, id: u32) -> Result<Vec<TypeDefPrimitive>> {
+		fn collect(
+			converter: &Converter,
+			id: u32,
+			visited: &mut BTreeSet<u32>,
+			found: &mut Vec<TypeDefPrimitive>,
+		) -> Result<()> {
+			let type_def = &converter.resolve(id)?.type_def;
+
+			if let FrameTypeDef::Primitive(p) = type_def {
+				found.push(p.clone());
+			}
+
+			for id in children(type_def) {
+				if visited.insert(id) {
+					collect(converter, id, visited, found)?;
+				}
+			}
+
+			Ok(())
+		}
+
+		let mut found = Vec::new();
+
+		collect(self, id, &mut BTreeSet::new(), &mut found)?;
+
+		Ok(found)
+	}
+}
+
+// Collect the types reachable from the extrinsic, without the inner types of the compacts and bit
+// sequences, which are inlined.
+fn collect_accessible(
+	registry: &PortableRegistry,
+	id: u32,
+	accessible: &mut BTreeSet<u32>,
+) -> Result<()> {
+	if !accessible.insert(id) {
+		return Ok(());
+	}
+
+	let type_def = &registry
+		.resolve(id)
+		.ok_or_else(|| Error::MetadataHash(format!("type #{id} not found")))?
+		.type_def;
+
+	match type_def {
+		FrameTypeDef::Compact(_) | FrameTypeDef::BitSequence(_) => Ok(()),
+		_ => children(type_def)
+			.into_iter()
+			.try_for_each(|id| collect_accessible(registry, id, accessible)),
+	}
+}
+
+fn children(type_def: &FrameTypeDef<PortableForm>) -> Vec<u32> {
+	match type_def {
+		FrameTypeDef::Composite(c) => c.fields.iter().map(|f| f.ty.id).collect(),
+		FrameTypeDef::Variant(v) =>
+			v.variants.iter().flat_map(|v| &v.fields).map(|f| f.ty.id).collect(),
+		FrameTypeDef::Sequence(s) => vec![s.type_param.id],
+		FrameTypeDef::Array(a) => vec![a.type_param.id],
+		FrameTypeDef::Tuple(t) => t.fields.iter().map(|f| f.id).collect(),
+		FrameTypeDef::Primitive(_) => Vec::new(),
+		FrameTypeDef::Compact(c) => vec![c.type_param.id],
+		FrameTypeDef::BitSequence(b) => vec![b.bit_order_type.id, b.bit_store_type.id],
+	}
+}
+
+// Whether the type is a leaf, the primitives, compacts and empty types are not.
+fn is_basic(ty: &FrameType<PortableForm>) -> bool {
+	match &ty.type_def {
+		FrameTypeDef::Primitive(_) | FrameTypeDef::Compact(_) => false,
+		FrameTypeDef::Composite(c) => !c.fields.is_empty(),
+		FrameTypeDef::Variant(v) => !v.variants.is_empty(),
+		FrameTypeDef::Tuple(t) => !t.fields.is_empty(),
+		_ => true,
+	}
+}
@@ -0,0 +1,140 @@
+//! Merkle tree of the merkleized types.
+
+// std
+use std::{collections::BTreeMap, iter::Peekable};
+// crates.io
+use parity_scale_codec::Encode;
+// substrate-minimal
+use super::*;
+use crate::{Error, Result};
+
+/// Proof of the types used by an extrinsic, against the types tree root of the metadata digest.
+#[derive(Clone, Debug, PartialEq, Eq, Encode)]
+pub struct Proof {
+	/// The leaves, which are the types used by the extrinsic.
+	pub leaves: Vec<Type>,
+	/// Node indices of the leaves, in the same order.
+	pub leaf_indices: Vec<u32>,
+	/// Hashes of the other nodes, which are required to compute the root.
+	pub nodes: Vec<Hash>,
+}
+
+// Complete binary tree in the heap layout, the children of node `i` are `2i + 1` and `2i + 2`.
+//
+// The leaves are placed at the end, in the order of their IDs.
+pub(super) struct MerkleTree {
+	nodes: Vec<Hash>,
+	leaves: Vec<Type>,
+	leaf_indices: BTreeMap<LeafId, usize>,
+}
+impl MerkleTree {
+	pub(super) fn new(types: BTreeMap<LeafId, Type>) -> Self {
+		let (leaf_indices, leaves): (BTreeMap<_, _>, Vec<_>) =
+			types.into_iter().enumerate().map(|(i, (id, ty))| ((id, i), ty)).unzip();
+		let offset = leaves.len().saturating_sub(1);
+		let mut nodes = vec![[0; 32]; offset + leaves.len()];
+
+		leaves.iter().enumerate().for_each(|(i, l)| nodes[offset + i] = l.hash());
+
+		for i in (0..offset).rev() {
+			let mut hasher = blake3::Hasher::new();
+
+			hasher.update(&nodes[2 * i + 1]);
+			hasher.update(&nodes[2 * i + 2]);
+
+			nodes[i] = hasher.finalize().into();
+		}
+
+		Self { nodes, leaves, leaf_indices }
+	}
+
+	pub(super) fn root(&self) -> Hash {
+		self.nodes.first().copied().unwrap_or_default()
+	}
+
+	pub(super) fn proof(&self, ids: impl IntoIterator<Item = LeafId>) -> Result<Proof> {
+		let mut indices = ids
+			.into_iter()
+			.map(|id| {
+				self.leaf_indices.get(&id).map(|i| self.leaves.len() - 1 + i).ok_or_else(|| {
+					Error::MetadataHash(format!("type #{} not found in the tree", id.0))
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+
+		// The deepest leaves first, then from left to right.
+		indices.sort_by(|a, b| level(*b).cmp(&level(*a)).then(a.cmp(b)));
+
+		let mut nodes = Vec::new();
+		let mut leaves = indices.iter().copied().peekable();
+
+		if let Some(leaf) = leaves.next() {
+			self.collect_nodes(0, leaf, &mut leaves, &mut nodes);
+		}
+
+		Ok(Proof {
+			leaves: indices
+				.iter()
+				.map(|i| self.leaves[i + 1 - self.leaves.len()].clone())
+				.collect(),
+			leaf_indices: indices.into_iter().map(|i| i as _).collect(),
+			nodes,
+		})
+	}
+
+	// Walk from the leaf up to the stop node and collect the sibling hashes, which can't be
+	// computed from the leaves.
+	//
+	// The left siblings are collected in front of the current subtree, the right ones after it. A
+	// right sibling containing the next leaf is walked recursively instead.
+	fn collect_nodes<I>(
+		&self,
+		stop_at: usize,
+		leaf: usize,
+		leaves: &mut Peekable<I>,
+		nodes: &mut Vec<Hash>,
+	) where
+		I: Iterator<Item = usize>,
+	{
+		let left_most = nodes.len();
+		let mut i = leaf;
+
+		// The tree has a single leaf.
+		if i == stop_at {
+			return;
+		}
+
+		loop {
+			let parent = (i - 1) / 2;
+
+			if i % 2 == 1 {
+				let right = 2 * parent + 2;
+
+				if leaves.next_if_eq(&right).is_none() {
+					if let Some(next) = leaves.next_if(|l| is_descendant(right, *l)) {
+						self.collect_nodes(right, next, leaves, nodes);
+					} else {
+						nodes.push(self.nodes[right]);
+					}
+				}
+			} else {
+				nodes.insert(left_most, self.nodes[2 * parent + 1]);
+			}
+
+			if parent == stop_at {
+				return;
+			}
+
+			i = parent;
+		}
+	}
+}
+
+fn level(i: usize) -> u32 {
+	(i + 1).ilog2()
+}
+
+fn is_descendant(ancestor: usize, node: usize) -> bool {
+	ancestor == 0
+		|| ancestor <= node && ancestor + 1 == (node + 1) >> (level(node) - level(ancestor))
+}
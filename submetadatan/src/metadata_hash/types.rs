@@ -0,0 +1,186 @@
+//! The merkleized types, whose SCALE encodings are hashed.
+
+// crates.io
+use parity_scale_codec::{Compact, Encode};
+
+/// BLAKE3 hash.
+pub type Hash = [u8; 32];
+
+/// Reference to a type in the merkleized registry.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode)]
+pub enum TypeRef {
+	#[codec(index = 0)]
+	Bool,
+	#[codec(index = 1)]
+	Char,
+	#[codec(index = 2)]
+	Str,
+	#[codec(index = 3)]
+	U8,
+	#[codec(index = 4)]
+	U16,
+	#[codec(index = 5)]
+	U32,
+	#[codec(index = 6)]
+	U64,
+	#[codec(index = 7)]
+	U128,
+	#[codec(index = 8)]
+	U256,
+	#[codec(index = 9)]
+	I8,
+	#[codec(index = 10)]
+	I16,
+	#[codec(index = 11)]
+	I32,
+	#[codec(index = 12)]
+	I64,
+	#[codec(index = 13)]
+	I128,
+	#[codec(index = 14)]
+	I256,
+	#[codec(index = 15)]
+	CompactU8,
+	#[codec(index = 16)]
+	CompactU16,
+	#[codec(index = 17)]
+	CompactU32,
+	#[codec(index = 18)]
+	CompactU64,
+	#[codec(index = 19)]
+	CompactU128,
+	#[codec(index = 20)]
+	CompactU256,
+	/// A type without any data, such as `()` or an empty enum.
+	#[codec(index = 21)]
+	Void,
+	/// A type in the registry.
+	#[codec(index = 22)]
+	ById(Compact<u32>),
+}
+
+/// Merkleized type definition.
+#[derive(Clone, Debug, PartialEq, Eq, Encode)]
+pub enum TypeDef {
+	/// A struct or a tuple struct.
+	#[codec(index = 0)]
+	Composite(Vec<Field>),
+	/// A single variant of an enum, each variant is a leaf.
+	#[codec(index = 1)]
+	Enumeration(EnumerationVariant),
+	/// A sequence with the runtime known length.
+	#[codec(index = 2)]
+	Sequence(TypeRef),
+	/// An array with the compile-time known length.
+	#[codec(index = 3)]
+	Array(TypeDefArray),
+	/// A tuple.
+	#[codec(index = 4)]
+	Tuple(Vec<TypeRef>),
+	/// A bit sequence.
+	#[codec(index = 5)]
+	BitSequence(TypeDefBitSequence),
+}
+
+/// Merkleized field.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode)]
+pub struct Field {
+	pub name: Option<String>,
+	pub ty: TypeRef,
+	pub type_name: Option<String>,
+}
+
+/// Merkleized enum variant.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode)]
+pub struct EnumerationVariant {
+	pub name: String,
+	pub fields: Vec<Field>,
+	pub index: Compact<u32>,
+}
+
+/// Merkleized array.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode)]
+pub struct TypeDefArray {
+	pub len: u32,
+	pub type_param: TypeRef,
+}
+
+/// Merkleized bit sequence.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode)]
+pub struct TypeDefBitSequence {
+	/// Size of the bit store type.
+	pub num_bytes: u8,
+	/// Whether the bit order is `Lsb0`.
+	pub least_significant_bit_first: bool,
+}
+
+/// Merkleized type, which is a leaf of the merkle tree.
+#[derive(Clone, Debug, PartialEq, Eq, Encode)]
+pub struct Type {
+	/// Path of the type, empty for the built-in types.
+	pub path: Vec<String>,
+	/// Type definition.
+	pub type_def: TypeDef,
+	/// Type ID in the merkleized registry.
+	pub type_id: Compact<u32>,
+}
+impl Type {
+	/// Get the hash of the type.
+	pub fn hash(&self) -> Hash {
+		blake3::hash(&self.encode()).into()
+	}
+}
+
+/// Merkleized extrinsic metadata.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode)]
+pub struct ExtrinsicMetadata {
+	pub version: u8,
+	pub address_ty: TypeRef,
+	pub call_ty: TypeRef,
+	pub signature_ty: TypeRef,
+	/// Signed extensions in the order of the extrinsic.
+	pub signed_extensions: Vec<SignedExtensionMetadata>,
+}
+impl ExtrinsicMetadata {
+	/// Get the hash of the extrinsic metadata.
+	pub fn hash(&self) -> Hash {
+		blake3::hash(&self.encode()).into()
+	}
+}
+
+/// Merkleized signed extension metadata.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode)]
+pub struct SignedExtensionMetadata {
+	pub identifier: String,
+	pub included_in_extrinsic: TypeRef,
+	pub included_in_signed_data: TypeRef,
+}
+
+/// Metadata digest, whose hash is the metadata hash of `CheckMetadataHash`.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq, Encode)]
+pub enum MetadataDigest {
+	Disabled,
+	V1 {
+		types_tree_root: Hash,
+		extrinsic_metadata_hash: Hash,
+		spec_version: u32,
+		spec_name: String,
+		base58_prefix: u16,
+		decimals: u8,
+		token_symbol: String,
+	},
+}
+impl MetadataDigest {
+	/// Get the hash of the digest, which is the metadata hash.
+	pub fn hash(&self) -> Hash {
+		blake3::hash(&self.encode()).into()
+	}
+}
@@ -0,0 +1,99 @@
+//! Storage key decoding.
+
+// std
+use std::collections::BTreeMap;
+// crates.io
+use fxhash::FxHashMap;
+use substorager::{
+	snapshot::{DiffEntry, StateDiff},
+	StorageKeyPart,
+};
+// substrate-minimal
+use crate::{Metadata, StorageEntryMetadata};
+
+/// Decoded storage key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedStorageKey<'a> {
+	/// Pallet name.
+	pub pallet: &'a str,
+	/// Storage item name.
+	pub item: &'a str,
+	/// Storage entry.
+	pub entry: &'a StorageEntryMetadata,
+	/// Hashed map keys.
+	///
+	/// Empty if this is a plain storage.
+	pub keys: Vec<StorageKeyPart>,
+}
+
+/// Storage key decoder.
+///
+/// Index the storage prefixes of all the entries once, then decode the raw storage keys against
+/// the index.
+#[derive(Clone, Debug)]
+pub struct StorageKeyDecoder<'a> {
+	entries: FxHashMap<[u8; 32], (&'a str, &'a str, &'a StorageEntryMetadata)>,
+}
+impl<'a> StorageKeyDecoder<'a> {
+	/// Create a new [`StorageKeyDecoder`] from the [`Metadata`].
+	pub fn new(metadata: &'a Metadata) -> Self {
+		let entries = metadata
+			.pallets
+			.iter()
+			.filter_map(|(n, p)| p.storages.as_ref().map(|s| (n, s)))
+			.flat_map(|(n, s)| {
+				let pallet_prefix = subhasher::twox128(&s.prefix);
+
+				s.entries.iter().map(move |(i, e)| {
+					let mut prefix = [0; 32];
+
+					prefix[..16].copy_from_slice(&pallet_prefix);
+					prefix[16..].copy_from_slice(&subhasher::twox128(i));
+
+					(prefix, (n.as_str(), i.as_str(), e))
+				})
+			})
+			.collect();
+
+		Self { entries }
+	}
+
+	/// Decode the raw storage key.
+	///
+	/// For the transparent hashers, the original SCALE encoded keys will be recovered.
+	/// Return [`None`] if the key does not belong to any entry or does not match the entry's
+	/// hashers.
+	pub fn decode(&self, key: &[u8]) -> Option<DecodedStorageKey<'a>> {
+		let (prefix, hashed_keys) = key.split_first_chunk::<32>()?;
+		let (pallet, item, entry) = *self.entries.get(prefix)?;
+		let keys = substorager::split_map_keys(hashed_keys, &entry.hashers_with_key_sizes())?;
+
+		Some(DecodedStorageKey { pallet, item, entry, keys })
+	}
+
+	/// Get the pallet and the item names of the raw storage key by its prefix.
+	///
+	/// Unlike [`Self::decode`], the map keys are not checked.
+	pub fn pallet_item(&self, key: &[u8]) -> Option<(&'a str, &'a str)> {
+		let (pallet, item, _) = *self.entries.get(key.first_chunk::<32>()?)?;
+
+		Some((pallet, item))
+	}
+
+	/// Group the [`StateDiff`] entries by the pallet and the item names.
+	///
+	/// The child trie entries and the unknown keys are grouped under [`None`].
+	pub fn group_diff<'b>(
+		&self,
+		diff: &'b StateDiff,
+	) -> BTreeMap<Option<(&'a str, &'a str)>, Vec<&'b DiffEntry>> {
+		diff.group_by(|e| if e.child.is_none() { self.pallet_item(&e.key) } else { None })
+	}
+}
+
+/// Decode the raw storage key with the [`Metadata`].
+///
+/// Use [`StorageKeyDecoder`] directly, if there are many keys to decode.
+pub fn decode_storage_key<'a>(metadata: &'a Metadata, key: &[u8]) -> Option<DecodedStorageKey<'a>> {
+	StorageKeyDecoder::new(metadata).decode(key)
+}
@@ -0,0 +1,86 @@
+//! Storage value resolution.
+
+// crates.io
+use parity_scale_codec::{Decode, DecodeAll};
+// substrate-minimal
+use crate::{Error, Metadata, Result, StorageEntryModifier};
+
+/// Storage value, which has the entry's modifier and default value applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageValue<'a> {
+	/// Type ID of the value in [`Metadata::types`].
+	pub type_id: u32,
+	/// SCALE encoded value.
+	pub bytes: &'a [u8],
+	/// Whether the key is absent and this is the entry's default value.
+	pub is_default: bool,
+}
+impl StorageValue<'_> {
+	/// SCALE decode the value into `T`, the bytes must be consumed entirely.
+	pub fn decode<T>(&self) -> Result<T>
+	where
+		T: Decode,
+	{
+		T::decode_all(&mut &*self.bytes).map_err(Error::Codec)
+	}
+
+	/// Dynamically decode the value against [`Metadata::types`], the bytes must be consumed
+	/// entirely.
+	///
+	/// The legacy metadata has no type registry, which is unsupported.
+	#[cfg(feature = "dynamic")]
+	pub fn decode_dynamic(&self, metadata: &Metadata) -> Result<serde_json::Value> {
+		if metadata.version < 14 {
+			Err(Error::UnsupportedVersion(metadata.version))?;
+		}
+
+		crate::dynamic::Decoder::new(&metadata.types).decode_all(self.type_id, self.bytes)
+	}
+}
+
+/// Resolve the raw storage value of the entry, such as the result of `state_getStorage`.
+///
+/// If the key is absent, the value is the entry's default value for a `Default` entry, or
+/// [`None`] for an `Optional` entry, the same as Substrate.
+/// Then decode it with [`StorageValue::decode`] into a typed value, or with
+/// `StorageValue::decode_dynamic` into a dynamic value if the `dynamic` feature is enabled.
+pub fn resolve_storage_value<'a>(
+	metadata: &'a Metadata,
+	pallet: &str,
+	item: &str,
+	raw: Option<&'a [u8]>,
+) -> Result<Option<StorageValue<'a>>> {
+	let entry = metadata
+		.pallets
+		.get(pallet)
+		.and_then(|p| p.storages.as_ref())
+		.and_then(|s| s.entries.get(item))
+		.ok_or_else(|| Error::StorageEntryNotFound(pallet.into(), item.into()))?;
+	let value = match (raw, entry.modifier) {
+		(Some(bytes), _) =>
+			Some(StorageValue { type_id: entry.value_type, bytes, is_default: false }),
+		(None, StorageEntryModifier::Default) => Some(StorageValue {
+			type_id: entry.value_type,
+			bytes: &entry.default,
+			is_default: true,
+		}),
+		(None, StorageEntryModifier::Optional) => None,
+	};
+
+	Ok(value)
+}
+
+/// Resolve the raw storage value of the entry, then decode it into `T`.
+///
+/// Check [`resolve_storage_value`] for the detail.
+pub fn decode_storage_value<T>(
+	metadata: &Metadata,
+	pallet: &str,
+	item: &str,
+	raw: Option<&[u8]>,
+) -> Result<Option<T>>
+where
+	T: Decode,
+{
+	resolve_storage_value(metadata, pallet, item, raw)?.map(|v| v.decode()).transpose()
+}
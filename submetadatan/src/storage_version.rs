@@ -0,0 +1,122 @@
+//! Pallet storage versions.
+//!
+//! Substrate reference(s):
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/frame/support/src/traits/metadata.rs>
+
+// std
+use std::collections::BTreeMap;
+// crates.io
+use parity_scale_codec::DecodeAll;
+use substorager::{well_known_keys, StorageKey};
+// substrate-minimal
+use crate::{Error, Metadata, Result};
+
+/// Calculate the storage version keys of all the pallets, in the pallet name order.
+pub fn storage_version_keys(metadata: &Metadata) -> Vec<(&str, StorageKey)> {
+	let mut keys = metadata
+		.pallets
+		.keys()
+		.map(|p| (p.as_str(), well_known_keys::storage_version_key(p)))
+		.collect::<Vec<_>>();
+
+	keys.sort_unstable();
+
+	keys
+}
+
+/// Decode the raw storage version.
+///
+/// The version is `0` if the key is absent, the same as FRAME.
+pub fn decode_storage_version(raw: Option<&[u8]>) -> Result<u16> {
+	raw.map(|mut r| u16::decode_all(&mut r).map_err(Error::Codec)).unwrap_or(Ok(0))
+}
+
+/// Read the storage versions of all the pallets, through `get`.
+///
+/// `get` returns the raw value of the key, from a storage snapshot or the `state_getStorage`
+/// results for instance.
+pub fn read_storage_versions<F, V>(metadata: &Metadata, mut get: F) -> Result<BTreeMap<String, u16>>
+where
+	F: FnMut(&StorageKey) -> Option<V>,
+	V: AsRef<[u8]>,
+{
+	storage_version_keys(metadata)
+		.into_iter()
+		.map(|(p, k)| Ok((p.to_owned(), decode_storage_version(get(&k).as_ref().map(V::as_ref))?)))
+		.collect()
+}
+
+/// Migration status of a pallet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MigrationStatus {
+	/// The on-chain version is the same as the expected one.
+	UpToDate,
+	/// The on-chain version is lower than the expected one, the migration looks missing.
+	Missing,
+	/// The on-chain version is higher than the expected one.
+	Ahead,
+	/// The pallet is only in the new runtime.
+	Added,
+	/// The pallet is only on-chain.
+	Removed,
+}
+
+/// Storage version comparison of a pallet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PalletMigration {
+	/// Pallet name.
+	pub pallet: String,
+	/// On-chain storage version.
+	pub on_chain: Option<u16>,
+	/// Storage version which the new runtime expects.
+	pub expected: Option<u16>,
+	/// Migration status.
+	pub status: MigrationStatus,
+}
+
+/// Storage version comparison of all the pallets.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MigrationReport(pub Vec<PalletMigration>);
+impl MigrationReport {
+	/// Compare the on-chain storage versions with the versions which the new runtime expects.
+	///
+	/// The expected versions are the in-code versions of the new runtime, which could be read from
+	/// its genesis state with [`read_storage_versions`].
+	pub fn new(on_chain: &BTreeMap<String, u16>, expected: &BTreeMap<String, u16>) -> Self {
+		let mut pallets = on_chain.keys().chain(expected.keys()).collect::<Vec<_>>();
+
+		pallets.sort_unstable();
+		pallets.dedup();
+
+		Self(
+			pallets
+				.into_iter()
+				.map(|p| {
+					let on_chain = on_chain.get(p).copied();
+					let expected = expected.get(p).copied();
+					let status = match (on_chain, expected) {
+						(Some(o), Some(e)) if o == e => MigrationStatus::UpToDate,
+						(Some(o), Some(e)) if o < e => MigrationStatus::Missing,
+						(Some(_), Some(_)) => MigrationStatus::Ahead,
+						(None, _) => MigrationStatus::Added,
+						(_, None) => MigrationStatus::Removed,
+					};
+
+					PalletMigration { pallet: p.to_owned(), on_chain, expected, status }
+				})
+				.collect(),
+		)
+	}
+
+	/// Iterate the pallets whose migration looks missing.
+	pub fn missing(&self) -> impl Iterator<Item = &PalletMigration> {
+		self.0.iter().filter(|p| p.status == MigrationStatus::Missing)
+	}
+
+	/// Check if all the pallets which exist on both sides are up to date.
+	pub fn is_up_to_date(&self) -> bool {
+		self.0
+			.iter()
+			.all(|p| !matches!(p.status, MigrationStatus::Missing | MigrationStatus::Ahead))
+	}
+}
@@ -0,0 +1,1571 @@
+// crates.io
+use frame_metadata::{
+	decode_different::DecodeDifferent,
+	v11, v13,
+	v14::{
+		ExtrinsicMetadata, PalletCallMetadata, PalletConstantMetadata, PalletErrorMetadata,
+		PalletEventMetadata, PalletMetadata, PalletStorageMetadata, RuntimeMetadataV14,
+		StorageEntryMetadata, StorageEntryModifier, StorageEntryType, StorageHasher as Hasher,
+	},
+	v15, v16, RuntimeMetadataPrefixed,
+};
+use parity_scale_codec::{Decode, Encode};
+use scale_info::{meta_type, MetaType, TypeDef, TypeDefPrimitive, TypeInfo};
+use substorager::{StorageHasher, StorageKeyPart};
+// substrate-minimal
+use crate::*;
+
+const ALICE: [u8; 32] = [
+	212, 53, 147, 199, 21, 253, 211, 28, 97, 20, 26, 189, 4, 169, 159, 214, 130, 44, 133, 88, 133,
+	76, 205, 227, 154, 86, 132, 231, 165, 109, 162, 125,
+];
+
+#[allow(dead_code)]
+#[derive(Encode, TypeInfo)]
+enum BalancesCall {
+	#[codec(index = 3)]
+	TransferKeepAlive {
+		dest: [u8; 32],
+		#[codec(compact)]
+		value: u128,
+	},
+}
+
+#[allow(dead_code)]
+#[derive(Encode, TypeInfo)]
+enum BalancesEvent {
+	#[codec(index = 2)]
+	Transfer { from: [u8; 32], to: [u8; 32], amount: u128 },
+}
+
+#[allow(dead_code)]
+#[derive(Encode, TypeInfo)]
+enum BalancesError {
+	#[codec(index = 2)]
+	InsufficientBalance,
+}
+
+fn entry(name: &'static str, ty: StorageEntryType) -> StorageEntryMetadata {
+	entry_with_modifier(name, ty, StorageEntryModifier::Default, Vec::new())
+}
+
+fn entry_with_modifier(
+	name: &'static str,
+	ty: StorageEntryType,
+	modifier: StorageEntryModifier,
+	default: Vec<u8>,
+) -> StorageEntryMetadata {
+	StorageEntryMetadata { name, modifier, ty, default, docs: Vec::new() }
+}
+
+fn map(hashers: Vec<Hasher>, key: MetaType) -> StorageEntryType {
+	StorageEntryType::Map { hashers, key, value: meta_type::<u128>() }
+}
+
+fn pallet(name: &'static str, index: u8, entries: Vec<StorageEntryMetadata>) -> PalletMetadata {
+	PalletMetadata {
+		name,
+		storage: Some(PalletStorageMetadata { prefix: name, entries }),
+		calls: None,
+		event: None,
+		constants: Vec::new(),
+		error: None,
+		index,
+	}
+}
+
+fn metadata() -> RuntimeMetadataV14 {
+	RuntimeMetadataV14::new(
+		vec![
+			pallet(
+				"System",
+				0,
+				vec![
+					entry("Number", StorageEntryType::Plain(meta_type::<u32>())),
+					entry("Account", map(vec![Hasher::Blake2_128Concat], meta_type::<[u8; 32]>())),
+				],
+			),
+			PalletMetadata {
+				calls: Some(PalletCallMetadata { ty: meta_type::<BalancesCall>() }),
+				event: Some(PalletEventMetadata { ty: meta_type::<BalancesEvent>() }),
+				constants: vec![PalletConstantMetadata {
+					name: "ExistentialDeposit",
+					ty: meta_type::<u128>(),
+					value: 1_000_000_000_u128.encode(),
+					docs: vec!["The minimum amount required to keep an account open."],
+				}],
+				error: Some(PalletErrorMetadata { ty: meta_type::<BalancesError>() }),
+				..pallet(
+					"Balances",
+					5,
+					vec![
+						entry_with_modifier(
+							"TotalIssuance",
+							StorageEntryType::Plain(meta_type::<u128>()),
+							StorageEntryModifier::Default,
+							1_000_u128.to_le_bytes().to_vec(),
+						),
+						entry_with_modifier(
+							"Inactive",
+							StorageEntryType::Plain(meta_type::<u128>()),
+							StorageEntryModifier::Optional,
+							vec![0],
+						),
+					],
+				)
+			},
+			pallet(
+				"Staking",
+				7,
+				vec![
+					entry(
+						"ErasStakers",
+						map(
+							vec![Hasher::Twox64Concat, Hasher::Twox64Concat],
+							meta_type::<(u32, [u8; 32])>(),
+						),
+					),
+					entry(
+						"ErasStakersPaged",
+						map(
+							vec![Hasher::Twox64Concat, Hasher::Blake2_128, Hasher::Twox64Concat],
+							meta_type::<(u32, [u8; 32], u32)>(),
+						),
+					),
+				],
+			),
+			pallet(
+				"Preimage",
+				10,
+				vec![
+					entry("PreimageFor", map(vec![Hasher::Identity], meta_type::<Vec<u8>>())),
+					entry(
+						"Variable",
+						map(
+							vec![Hasher::Blake2_128Concat, Hasher::Twox64Concat],
+							meta_type::<(Vec<u8>, u32)>(),
+						),
+					),
+				],
+			),
+		],
+		ExtrinsicMetadata { ty: meta_type::<()>(), version: 4, signed_extensions: Vec::new() },
+		meta_type::<()>(),
+	)
+}
+
+fn metadata_v15() -> v15::RuntimeMetadataV15 {
+	let v14 = metadata();
+
+	v15::RuntimeMetadataV15 {
+		types: v14.types,
+		pallets: v14
+			.pallets
+			.into_iter()
+			.map(|p| v15::PalletMetadata {
+				name: p.name,
+				storage: p.storage,
+				calls: p.calls,
+				event: p.event,
+				constants: p.constants,
+				error: p.error,
+				index: p.index,
+				docs: Vec::new(),
+			})
+			.collect(),
+		extrinsic: v15::ExtrinsicMetadata {
+			version: 4,
+			address_ty: v14.ty,
+			call_ty: v14.ty,
+			signature_ty: v14.ty,
+			extra_ty: v14.ty,
+			signed_extensions: Vec::new(),
+		},
+		ty: v14.ty,
+		apis: Vec::new(),
+		outer_enums: v15::OuterEnums {
+			call_enum_ty: v14.ty,
+			event_enum_ty: v14.ty,
+			error_enum_ty: v14.ty,
+		},
+		custom: v15::CustomMetadata { map: Default::default() },
+	}
+}
+
+fn metadata_v16() -> v16::RuntimeMetadataV16 {
+	let v15 = metadata_v15();
+
+	v16::RuntimeMetadataV16 {
+		types: v15.types,
+		pallets: v15
+			.pallets
+			.into_iter()
+			.map(|p| v16::PalletMetadata {
+				name: p.name,
+				storage: p.storage.map(|s| v16::PalletStorageMetadata {
+					prefix: s.prefix,
+					entries: s
+						.entries
+						.into_iter()
+						.map(|e| v16::StorageEntryMetadata {
+							name: e.name,
+							modifier: e.modifier,
+							ty: e.ty,
+							default: e.default,
+							docs: e.docs,
+							deprecation_info: v16::ItemDeprecationInfo::NotDeprecated,
+						})
+						.collect(),
+				}),
+				calls: p.calls.map(|c| v16::PalletCallMetadata {
+					ty: c.ty,
+					deprecation_info: v16::EnumDeprecationInfo::nothing_deprecated(),
+				}),
+				event: p.event.map(|e| v16::PalletEventMetadata {
+					ty: e.ty,
+					deprecation_info: v16::EnumDeprecationInfo::nothing_deprecated(),
+				}),
+				constants: p
+					.constants
+					.into_iter()
+					.map(|c| v16::PalletConstantMetadata {
+						name: c.name,
+						ty: c.ty,
+						value: c.value,
+						docs: c.docs,
+						deprecation_info: v16::ItemDeprecationInfo::NotDeprecated,
+					})
+					.collect(),
+				error: p.error.map(|e| v16::PalletErrorMetadata {
+					ty: e.ty,
+					deprecation_info: v16::EnumDeprecationInfo::nothing_deprecated(),
+				}),
+				associated_types: Vec::new(),
+				view_functions: Vec::new(),
+				index: p.index,
+				docs: p.docs,
+				deprecation_info: v16::ItemDeprecationInfo::NotDeprecated,
+			})
+			.collect(),
+		extrinsic: v16::ExtrinsicMetadata {
+			versions: vec![4],
+			address_ty: v15.ty,
+			call_ty: v15.ty,
+			signature_ty: v15.ty,
+			transaction_extensions_by_version: Default::default(),
+			transaction_extensions: Vec::new(),
+		},
+		apis: Vec::new(),
+		outer_enums: v15.outer_enums,
+		custom: v15.custom,
+	}
+}
+
+#[test]
+fn key_sizes_should_work() {
+	let metadata = Metadata::from(metadata());
+	let key_sizes = |p: &str, i: &str| {
+		metadata.pallets[p].storages.as_ref().unwrap().entries[i].key_sizes.clone()
+	};
+
+	assert_eq!(key_sizes("System", "Number"), []);
+	assert_eq!(key_sizes("System", "Account"), [Some(32)]);
+	assert_eq!(key_sizes("Staking", "ErasStakers"), [Some(4), Some(32)]);
+	assert_eq!(key_sizes("Staking", "ErasStakersPaged"), [Some(4), Some(32), Some(4)]);
+	assert_eq!(key_sizes("Preimage", "PreimageFor"), [None]);
+	assert_eq!(key_sizes("Preimage", "Variable"), [None, Some(4)]);
+}
+
+#[test]
+fn decode_storage_key_should_work() {
+	let metadata = Metadata::from(metadata());
+	let decoder = StorageKeyDecoder::new(&metadata);
+	let decode = |k: &[u8]| decoder.decode(k).map(|k| (k.pallet, k.item, k.keys));
+
+	assert_eq!(
+		decode(&substorager::storage_value_key("System", "Number")),
+		Some(("System", "Number", Vec::new()))
+	);
+	assert_eq!(
+		decode(&array_bytes::hex2bytes_unchecked("0x26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9de1e86a9a8c739864cf3cc5ec2bea59fd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")),
+		Some((
+			"System",
+			"Account",
+			vec![StorageKeyPart {
+				hasher: StorageHasher::Blake2_128Concat,
+				hash: subhasher::blake2_128(ALICE).to_vec(),
+				key: Some(ALICE.to_vec()),
+			}]
+		))
+	);
+	assert_eq!(
+		decode(&substorager::storage_n_map_key(
+			"Staking",
+			"ErasStakersPaged",
+			[
+				(StorageHasher::Twox64Concat, &1_u32.to_le_bytes()[..]),
+				(StorageHasher::Blake2_128, &ALICE),
+				(StorageHasher::Twox64Concat, &2_u32.to_le_bytes())
+			]
+		)),
+		Some((
+			"Staking",
+			"ErasStakersPaged",
+			vec![
+				StorageKeyPart {
+					hasher: StorageHasher::Twox64Concat,
+					hash: subhasher::twox64(1_u32.to_le_bytes()).to_vec(),
+					key: Some(1_u32.to_le_bytes().to_vec()),
+				},
+				StorageKeyPart {
+					hasher: StorageHasher::Blake2_128,
+					hash: subhasher::blake2_128(ALICE).to_vec(),
+					key: None,
+				},
+				StorageKeyPart {
+					hasher: StorageHasher::Twox64Concat,
+					hash: subhasher::twox64(2_u32.to_le_bytes()).to_vec(),
+					key: Some(2_u32.to_le_bytes().to_vec()),
+				}
+			]
+		))
+	);
+	// The last variable length key could be recovered.
+	assert_eq!(
+		decode(&substorager::storage_n_map_key(
+			"Preimage",
+			"PreimageFor",
+			[(StorageHasher::Identity, [8, 1, 2])]
+		))
+		.unwrap()
+		.2[0]
+			.key,
+		Some(vec![8, 1, 2])
+	);
+	// The variable length key in the middle could not be split.
+	assert_eq!(
+		decode(&substorager::storage_n_map_key(
+			"Preimage",
+			"Variable",
+			[
+				(StorageHasher::Blake2_128Concat, &[4, 0][..]),
+				(StorageHasher::Twox64Concat, &[0; 4])
+			]
+		)),
+		None
+	);
+	// Unknown prefix.
+	assert_eq!(decode(&substorager::storage_value_key("System", "Unknown")), None);
+	// Malformed keys.
+	assert_eq!(decode(&[0; 16]), None);
+	assert_eq!(
+		decode(&[substorager::storage_value_key("System", "Number").0, vec![0]].concat()),
+		None
+	);
+	assert_eq!(decode(&substorager::storage_value_key("System", "Account")), None);
+	// The shortcut.
+	assert_eq!(
+		decode_storage_key(&metadata, &substorager::storage_value_key("System", "Number"))
+			.map(|k| (k.pallet, k.item)),
+		Some(("System", "Number"))
+	);
+}
+
+#[test]
+fn group_diff_should_work() {
+	let metadata = Metadata::from(metadata());
+	let decoder = StorageKeyDecoder::new(&metadata);
+	let number = substorager::storage_value_key("System", "Number").0;
+	let account = substorager::storage_n_map_key(
+		"System",
+		"Account",
+		[(StorageHasher::Blake2_128Concat, ALICE)],
+	)
+	.0;
+	let mut old = substorager::trie::MemoryState::default();
+	let mut new = old.clone();
+
+	old.top_mut().insert(number.clone(), vec![0]);
+	new.top_mut().insert(number.clone(), vec![1]);
+	new.top_mut().insert(account.clone(), vec![2]);
+	new.top_mut().insert(b":code".to_vec(), vec![3]);
+	new.child_mut(substorager::ChildInfo::new_default(b"child")).insert(number.clone(), vec![4]);
+
+	let diff = substorager::snapshot::StateDiff::new(&old, &new);
+	let groups = decoder
+		.group_diff(&diff)
+		.into_iter()
+		.map(|(g, es)| (g, es.into_iter().map(|e| e.key.clone()).collect::<Vec<_>>()))
+		.collect::<Vec<_>>();
+
+	assert_eq!(decoder.pallet_item(&account), Some(("System", "Account")));
+	assert_eq!(decoder.pallet_item(b":code"), None);
+	assert_eq!(
+		groups,
+		[
+			(None, vec![b":code".to_vec(), number.clone()]),
+			(Some(("System", "Account")), vec![account]),
+			(Some(("System", "Number")), vec![number]),
+		]
+	);
+}
+
+#[test]
+fn resolve_storage_value_should_work() {
+	let metadata = Metadata::from(metadata());
+	let issuance = 2_000_u128.to_le_bytes();
+
+	// Present.
+	let v = resolve_storage_value(&metadata, "Balances", "TotalIssuance", Some(&issuance))
+		.unwrap()
+		.unwrap();
+
+	assert!(!v.is_default);
+	assert_eq!(v.decode::<u128>().unwrap(), 2_000);
+	assert!(matches!(
+		metadata.types.resolve(v.type_id).map(|t| &t.type_def),
+		Some(scale_info::TypeDef::Primitive(scale_info::TypeDefPrimitive::U128))
+	));
+	// Absent with the `Default` modifier.
+	let v = resolve_storage_value(&metadata, "Balances", "TotalIssuance", None).unwrap().unwrap();
+
+	assert!(v.is_default);
+	assert_eq!(v.decode::<u128>().unwrap(), 1_000);
+	#[cfg(feature = "dynamic")]
+	assert_eq!(v.decode_dynamic(&metadata).unwrap(), serde_json::json!(1_000));
+	// Absent with the `Optional` modifier.
+	assert_eq!(resolve_storage_value(&metadata, "Balances", "Inactive", None).unwrap(), None);
+	assert_eq!(
+		decode_storage_value::<u128>(&metadata, "Balances", "Inactive", Some(&issuance)).unwrap(),
+		Some(2_000)
+	);
+	// Trailing bytes.
+	assert!(matches!(
+		decode_storage_value::<u128>(&metadata, "Balances", "Inactive", Some(&[0; 17])),
+		Err(Error::Codec(_))
+	));
+	assert!(matches!(
+		resolve_storage_value(&metadata, "Balances", "Unknown", None),
+		Err(Error::StorageEntryNotFound(..))
+	));
+
+	// The legacy metadata can't be decoded dynamically.
+	#[cfg(feature = "dynamic")]
+	{
+		let metadata = Metadata::from(metadata_v13());
+		let v =
+			resolve_storage_value(&metadata, "System", "Number", Some(&[0; 4])).unwrap().unwrap();
+
+		assert!(matches!(v.decode_dynamic(&metadata), Err(Error::UnsupportedVersion(13))));
+	}
+}
+
+#[test]
+fn storage_versions_should_work() {
+	let metadata = Metadata::from(metadata());
+	let keys = storage_version_keys(&metadata);
+
+	assert_eq!(
+		keys.iter().map(|(p, _)| *p).collect::<Vec<_>>(),
+		["Balances", "Preimage", "Staking", "System"]
+	);
+	assert_eq!(
+		keys[3].1.to_string(),
+		"0x26aa394eea5630e07c48ae0c9558cef74e7b9012096b41c4eb3aaf947f6ea429"
+	);
+
+	// Such as a storage snapshot.
+	let mut state = substorager::trie::MemoryState::default();
+
+	state.top_mut().insert(keys[0].1.to_vec(), 1_u16.to_le_bytes().to_vec());
+	state.top_mut().insert(keys[2].1.to_vec(), 13_u16.to_le_bytes().to_vec());
+	state.top_mut().insert(keys[3].1.to_vec(), 2_u16.to_le_bytes().to_vec());
+
+	let on_chain = read_storage_versions(&metadata, |k| state.top().get(k)).unwrap();
+
+	assert_eq!(
+		on_chain.iter().map(|(p, v)| (p.as_str(), *v)).collect::<Vec<_>>(),
+		[("Balances", 1), ("Preimage", 0), ("Staking", 13), ("System", 2)]
+	);
+	assert!(matches!(decode_storage_version(Some(&[0; 3])), Err(Error::Codec(_))));
+
+	let expected = [("Balances", 1), ("Preimage", 1), ("Staking", 14), ("Treasury", 0)]
+		.into_iter()
+		.map(|(p, v)| (p.to_owned(), v))
+		.collect();
+	let report = MigrationReport::new(&on_chain, &expected);
+
+	assert_eq!(
+		report.0.iter().map(|p| (p.pallet.as_str(), p.status)).collect::<Vec<_>>(),
+		[
+			("Balances", MigrationStatus::UpToDate),
+			("Preimage", MigrationStatus::Missing),
+			("Staking", MigrationStatus::Missing),
+			("System", MigrationStatus::Removed),
+			("Treasury", MigrationStatus::Added),
+		]
+	);
+	assert_eq!(report.missing().map(|p| p.expected).collect::<Vec<_>>(), [Some(1), Some(14)]);
+	assert!(!report.is_up_to_date());
+}
+
+#[test]
+fn versioned_metadata_should_work() {
+	let v14 = Metadata::from(metadata());
+	let v15 = Metadata::from(metadata_v15());
+	let v16 = Metadata::from(metadata_v16());
+
+	assert_eq!(v14.version, 14);
+	assert_eq!(v15.version, 15);
+	assert_eq!(v16.version, 16);
+	assert_eq!(v14.pallets, v15.pallets);
+	assert_eq!(v14.pallets, v16.pallets);
+
+	for (version, metadata) in [
+		(14, frame_metadata::RuntimeMetadata::V14(metadata())),
+		(15, frame_metadata::RuntimeMetadata::V15(metadata_v15())),
+		(16, frame_metadata::RuntimeMetadata::V16(metadata_v16())),
+	] {
+		let encoded = RuntimeMetadataPrefixed(frame_metadata::META_RESERVED, metadata).encode();
+		let raw = array_bytes::bytes2hex("0x", &encoded);
+
+		assert_eq!(unprefix_raw_metadata(&raw).unwrap().version(), version);
+		assert_eq!(unprefix_raw_metadata_minimal(&raw).unwrap().pallets, v14.pallets);
+		// Such as the result of `Metadata_metadata_at_version`.
+		assert_eq!(
+			unprefix_raw_metadata_at_version(array_bytes::bytes2hex("0x", Some(encoded).encode()))
+				.unwrap()
+				.map(|m| m.version()),
+			Some(version)
+		);
+	}
+
+	assert!(unprefix_raw_metadata_at_version("0x00").unwrap().is_none());
+
+	let v8 = RuntimeMetadataPrefixed(
+		frame_metadata::META_RESERVED,
+		frame_metadata::RuntimeMetadata::V8(frame_metadata::v8::RuntimeMetadataV8 {
+			modules: DecodeDifferent::Decoded(Vec::new()),
+		}),
+	);
+
+	assert!(matches!(unprefix_metadata(v8), Err(Error::UnsupportedVersion(8))));
+}
+
+fn decoded<B, O>(v: impl Into<O>) -> DecodeDifferent<B, O> {
+	DecodeDifferent::Decoded(v.into())
+}
+
+macro_rules! legacy_entry {
+	($m:ident, $name:expr, $ty:expr) => {
+		$m::StorageEntryMetadata {
+			name: decoded($name),
+			modifier: $m::StorageEntryModifier::Default,
+			ty: $ty,
+			default: decoded(vec![0; 4]),
+			documentation: decoded(Vec::new()),
+		}
+	};
+}
+
+macro_rules! legacy_module {
+	($m:ident, $name:expr, $entries:expr $(, $index:expr)?) => {
+		$m::ModuleMetadata {
+			name: decoded($name),
+			storage: Some(decoded($m::StorageMetadata {
+				prefix: decoded($name),
+				entries: decoded($entries),
+			})),
+			calls: None,
+			event: None,
+			constants: decoded(Vec::new()),
+			errors: decoded(Vec::new()),
+			$(index: $index,)?
+		}
+	};
+}
+
+fn metadata_v11() -> v11::RuntimeMetadataV11 {
+	v11::RuntimeMetadataV11 {
+		modules: decoded(vec![
+			v11::ModuleMetadata {
+				event: Some(decoded(vec![v11::EventMetadata {
+					name: decoded("ExtrinsicSuccess"),
+					arguments: decoded(vec!["DispatchInfo".into()]),
+					documentation: decoded(Vec::new()),
+				}])),
+				..legacy_module!(
+					v11,
+					"System",
+					vec![legacy_entry!(
+						v11,
+						"Account",
+						v11::StorageEntryType::Map {
+							hasher: v11::StorageHasher::Blake2_128Concat,
+							key: decoded("T::AccountId"),
+							value: decoded("AccountInfo"),
+							unused: false,
+						}
+					)]
+				)
+			},
+			v11::ModuleMetadata {
+				calls: Some(decoded(vec![v11::FunctionMetadata {
+					name: decoded("bond"),
+					arguments: decoded(vec![v11::FunctionArgumentMetadata {
+						name: decoded("value"),
+						ty: decoded("Compact<BalanceOf<T>>"),
+					}]),
+					documentation: decoded(vec!["Take the origin account as a stash.".into()]),
+				}])),
+				event: Some(decoded(vec![v11::EventMetadata {
+					name: decoded("Bonded"),
+					arguments: decoded(vec!["AccountId".into(), "Balance".into()]),
+					documentation: decoded(Vec::new()),
+				}])),
+				constants: decoded(vec![v11::ModuleConstantMetadata {
+					name: decoded("BondingDuration"),
+					ty: decoded("EraIndex"),
+					value: decoded(28_u32.encode()),
+					documentation: decoded(Vec::new()),
+				}]),
+				errors: decoded(vec![
+					v11::ErrorMetadata {
+						name: decoded("NotController"),
+						documentation: decoded(Vec::new()),
+					},
+					v11::ErrorMetadata {
+						name: decoded("NotStash"),
+						documentation: decoded(Vec::new()),
+					},
+				]),
+				..legacy_module!(
+					v11,
+					"Staking",
+					vec![legacy_entry!(
+						v11,
+						"ErasStakers",
+						v11::StorageEntryType::DoubleMap {
+							hasher: v11::StorageHasher::Twox64Concat,
+							key1: decoded("u32"),
+							key2: decoded("[u8; 32]"),
+							value: decoded("Exposure"),
+							key2_hasher: v11::StorageHasher::Twox64Concat,
+						}
+					)]
+				)
+			},
+		]),
+		extrinsic: v11::ExtrinsicMetadata { version: 4, signed_extensions: Vec::new() },
+	}
+}
+
+fn metadata_v13() -> v13::RuntimeMetadataV13 {
+	v13::RuntimeMetadataV13 {
+		modules: decoded(vec![
+			legacy_module!(
+				v13,
+				"System",
+				vec![legacy_entry!(v13, "Number", v13::StorageEntryType::Plain(decoded("u32")))],
+				0
+			),
+			legacy_module!(
+				v13,
+				"Staking",
+				vec![legacy_entry!(
+					v13,
+					"ErasStakersPaged",
+					v13::StorageEntryType::NMap {
+						keys: decoded(vec!["u32".into(), "[u8; 32]".into(), "Vec<u8>".into()]),
+						hashers: decoded(vec![
+							v13::StorageHasher::Twox64Concat,
+							v13::StorageHasher::Blake2_128,
+							v13::StorageHasher::Identity,
+						]),
+						value: decoded("ExposurePage"),
+					}
+				)],
+				6
+			),
+		]),
+		extrinsic: v13::ExtrinsicMetadata { version: 4, signed_extensions: Vec::new() },
+	}
+}
+
+#[test]
+fn legacy_metadata_should_work() {
+	let encode = |metadata| {
+		array_bytes::bytes2hex(
+			"0x",
+			RuntimeMetadataPrefixed(frame_metadata::META_RESERVED, metadata).encode(),
+		)
+	};
+	let v11 =
+		unprefix_raw_metadata_minimal(encode(frame_metadata::RuntimeMetadata::V11(metadata_v11())))
+			.unwrap();
+	let v13 =
+		unprefix_raw_metadata_minimal(encode(frame_metadata::RuntimeMetadata::V13(metadata_v13())))
+			.unwrap();
+
+	assert_eq!(v11.version, 11);
+	assert_eq!(v13.version, 13);
+	// Before V12, the index is the position of the module.
+	assert_eq!(v11.pallets["Staking"].index, 1);
+	assert_eq!(v13.pallets["Staking"].index, 6);
+
+	let entry =
+		|m: &Metadata, p: &str, i: &str| m.pallets[p].storages.as_ref().unwrap().entries[i].clone();
+	let account = entry(&v11, "System", "Account");
+
+	assert_eq!(
+		account.r#type,
+		metadata::StorageEntryType::Map(vec![StorageHasher::Blake2_128Concat])
+	);
+	assert_eq!(account.key_sizes, [None]);
+	assert_eq!(account.key_type_names, ["T::AccountId"]);
+	assert_eq!(account.value_type_name.as_deref(), Some("AccountInfo"));
+	assert_eq!(account.default, [0; 4]);
+	assert_eq!(entry(&v11, "Staking", "ErasStakers").key_sizes, [Some(4), Some(32)]);
+	assert_eq!(entry(&v13, "System", "Number").r#type, metadata::StorageEntryType::Plain);
+	assert_eq!(
+		entry(&v13, "Staking", "ErasStakersPaged").r#type,
+		metadata::StorageEntryType::Map(vec![
+			StorageHasher::Twox64Concat,
+			StorageHasher::Blake2_128,
+			StorageHasher::Identity
+		])
+	);
+	assert_eq!(entry(&v13, "Staking", "ErasStakersPaged").key_sizes, [Some(4), Some(32), None]);
+
+	let key = substorager::storage_n_map_key(
+		"Staking",
+		"ErasStakers",
+		[
+			(StorageHasher::Twox64Concat, &1_u32.to_le_bytes()[..]),
+			(StorageHasher::Twox64Concat, &ALICE),
+		],
+	);
+	let decoded = StorageKeyDecoder::new(&v11).decode(&key).unwrap();
+
+	assert_eq!((decoded.pallet, decoded.item), ("Staking", "ErasStakers"));
+	assert_eq!(decoded.keys[1].key.as_deref(), Some(&ALICE[..]));
+	assert_eq!(decode_storage_value::<u32>(&v13, "System", "Number", None).unwrap(), Some(0));
+
+	// Before V12, the calls and events are indexed by the position among the modules having them.
+	let bond = v11.call("Staking", "bond").unwrap();
+
+	assert_eq!((bond.pallet_index, bond.variant.index), (0, 0));
+	assert_eq!(bond.variant.fields[0].type_name.as_deref(), Some("Compact<BalanceOf<T>>"));
+	assert_eq!(bond.variant.docs, ["Take the origin account as a stash."]);
+	assert_eq!(v11.event_by_index(1, 0).map(|e| e.name), Some("Bonded"));
+	assert_eq!(v11.error_by_index(1, 1).map(|e| e.name), Some("NotStash"));
+	assert!(v11.call_by_index(1, 0).is_none());
+	assert_eq!(v11.constant("Staking", "BondingDuration").unwrap().value, 28_u32.encode());
+}
+
+#[test]
+fn pallet_items_should_work() {
+	let metadata = Metadata::from(metadata());
+	let call = metadata.call("Balances", "TransferKeepAlive").unwrap();
+
+	assert_eq!((call.pallet, call.pallet_index, call.variant.index), ("Balances", 5, 3));
+	assert_eq!(
+		call.variant.fields.iter().map(|f| f.name.as_deref()).collect::<Vec<_>>(),
+		[Some("dest"), Some("value")]
+	);
+	assert_eq!(metadata.call_by_index(5, 3).map(|c| c.name), Some("TransferKeepAlive"));
+	assert_eq!(metadata.event_by_index(5, 2).map(|e| e.name), Some("Transfer"));
+	assert_eq!(metadata.event("Balances", "Transfer").map(|e| e.variant.fields.len()), Some(3));
+	assert_eq!(metadata.error_by_index(5, 2).map(|e| e.name), Some("InsufficientBalance"));
+	assert!(metadata.call_by_index(0, 3).is_none());
+	assert!(metadata.error("Balances", "Transfer").is_none());
+
+	let constant = metadata.constant("Balances", "ExistentialDeposit").unwrap();
+
+	assert_eq!(u128::decode(&mut &*constant.value).unwrap(), 1_000_000_000);
+	assert_eq!(
+		metadata.types.resolve(constant.r#type).map(|t| &t.type_def),
+		Some(&TypeDef::Primitive(TypeDefPrimitive::U128))
+	);
+	assert_eq!(constant.docs, ["The minimum amount required to keep an account open."]);
+}
+
+#[cfg(feature = "dynamic")]
+#[derive(Encode, TypeInfo)]
+struct AccountId32([u8; 32]);
+#[cfg(feature = "dynamic")]
+#[derive(Encode, TypeInfo)]
+struct AccountData {
+	free: u128,
+	flags: u128,
+}
+#[cfg(feature = "dynamic")]
+#[derive(Encode, TypeInfo)]
+struct AccountInfo {
+	nonce: u32,
+	data: AccountData,
+}
+#[cfg(feature = "dynamic")]
+#[allow(dead_code)]
+#[derive(Encode, TypeInfo)]
+enum Event {
+	Transfer { from: AccountId32, to: AccountId32, amount: u128 },
+	Remarked(Vec<u8>, [u8; 4]),
+	Killed,
+	Dispatched(std::result::Result<(), u8>),
+	Tip(Option<u64>, #[codec(compact)] u128, (bool, i8, String), i128),
+}
+
+// Register the dynamic test types, return the registry and the IDs of `AccountInfo` and `Event`.
+#[cfg(feature = "dynamic")]
+fn dynamic_types() -> (scale_info::PortableRegistry, u32, u32) {
+	let mut registry = scale_info::Registry::new();
+	let account_info = registry.register_type(&meta_type::<AccountInfo>()).id;
+	let event = registry.register_type(&meta_type::<Event>()).id;
+
+	(registry.into(), account_info, event)
+}
+
+#[cfg(feature = "dynamic")]
+#[test]
+fn dynamic_decode_should_work() {
+	use parity_scale_codec::Compact;
+	use serde_json::json;
+
+	let (types, account_info, event) = dynamic_types();
+	let decoder = dynamic::Decoder::new(&types);
+	let info =
+		AccountInfo { nonce: 1, data: AccountData { free: 1_000, flags: 1 << 127 } }.encode();
+	let transfer = Event::Transfer { from: AccountId32(ALICE), to: AccountId32(ALICE), amount: 1 };
+
+	assert_eq!(
+		decoder.decode_all(account_info, &info).unwrap(),
+		json!({
+			"nonce": 1,
+			"data": { "free": 1_000, "flags": "170141183460469231731687303715884105728" }
+		})
+	);
+	assert_eq!(
+		decoder.decode_all(event, &transfer.encode()).unwrap(),
+		json!({
+			"Transfer": {
+				"from": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+				"to": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+				"amount": 1
+			}
+		})
+	);
+	assert_eq!(
+		decoder.with_network("polkadot").decode_all(event, &transfer.encode()).unwrap()["Transfer"]
+			["from"],
+		"15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"
+	);
+	assert_eq!(
+		decoder.decode_all(event, &Event::Remarked(vec![1, 2], [3; 4]).encode()).unwrap(),
+		json!({ "Remarked": ["0x0102", "0x03030303"] })
+	);
+	assert_eq!(decoder.decode_all(event, &Event::Killed.encode()).unwrap(), json!("Killed"));
+	assert_eq!(
+		decoder.decode_all(event, &Event::Dispatched(Err(1)).encode()).unwrap(),
+		json!({ "Dispatched": { "Err": 1 } })
+	);
+	assert_eq!(
+		decoder
+			.decode_all(event, &Event::Tip(None, 5, (true, -2, "x".into()), -1).encode())
+			.unwrap(),
+		json!({ "Tip": [null, 5, [true, -2, "x"], -1] })
+	);
+	assert_eq!(
+		decoder
+			.decode_all(event, &Event::Tip(Some(7), 5, (true, -2, "x".into()), -1).encode())
+			.unwrap()["Tip"][0],
+		7
+	);
+
+	let error = |r: Result<_>| r.unwrap_err().to_string();
+
+	assert_eq!(
+		error(decoder.decode_all(account_info, &info[..12])),
+		"failed to decode submetadatan::test::AccountInfo.data.free, expected u128, Not enough \
+		 data to fill buffer"
+	);
+	assert_eq!(
+		error(decoder.decode_all(event, &[9])),
+		"failed to decode submetadatan::test::Event, unknown variant index 9 of \
+		 submetadatan::test::Event"
+	);
+	assert_eq!(
+		error(decoder.decode_all(event, &[2, 0])),
+		"failed to decode submetadatan::test::Event, 1 trailing bytes"
+	);
+
+	#[derive(Encode, TypeInfo)]
+	enum Nested {
+		Leaf,
+		Node(Box<Nested>),
+	}
+
+	let mut registry = scale_info::Registry::new();
+	let nested = registry.register_type(&meta_type::<Nested>()).id;
+	let units = registry.register_type(&meta_type::<Vec<()>>()).id;
+	let numbers = registry.register_type(&meta_type::<Vec<u32>>()).id;
+	let types = registry.into();
+	let decoder = dynamic::Decoder::new(&types);
+
+	assert_eq!(
+		decoder
+			.decode_all(
+				nested,
+				&Nested::Node(Box::new(Nested::Node(Box::new(Nested::Leaf)))).encode()
+			)
+			.unwrap(),
+		json!({ "Node": { "Node": "Leaf" } })
+	);
+	assert!(error(decoder.decode_all(nested, &[[1; 2_000].as_slice(), &[0]].concat()))
+		.ends_with("reached the maximum depth"));
+	assert_eq!(
+		error(decoder.decode_all(units, &Compact(u32::MAX).encode())),
+		"failed to decode Vec<()>, expected Vec<()>, length 4294967295 exceeds the remaining input"
+	);
+	assert_eq!(
+		error(decoder.decode_all(numbers, &[Compact(2_u32).encode(), 1_u32.encode()].concat())),
+		"failed to decode Vec<u32>, expected Vec<u32>, length 2 exceeds the remaining input"
+	);
+	assert_eq!(
+		dynamic::u256_to_string([u8::MAX; 32]),
+		"115792089237316195423570985008687907853269984665640564039457584007913129639935"
+	);
+	assert_eq!(dynamic::u256_to_string([0; 32]), "0");
+	assert_eq!(dynamic::i256_to_string([u8::MAX; 32]), "-1");
+}
+
+#[cfg(feature = "dynamic")]
+#[test]
+fn dynamic_encode_should_work() {
+	use parity_scale_codec::Compact;
+	use scale_info::PortableRegistry;
+	use serde_json::{json, Value};
+
+	fn round_trip(types: &PortableRegistry, id: u32, value: Value, encoded: Vec<u8>) {
+		let bytes = dynamic::Encoder::new(types).encode(id, &value).unwrap();
+
+		assert_eq!(bytes, encoded);
+		assert_eq!(dynamic::Decoder::new(types).decode_all(id, &bytes).unwrap(), value);
+	}
+
+	let metadata = Metadata::from(metadata());
+	let balances = &metadata.pallets["Balances"];
+	let alice = array_bytes::bytes2hex("0x", ALICE);
+
+	round_trip(
+		&metadata.types,
+		balances.calls.as_ref().unwrap().r#type,
+		json!({ "TransferKeepAlive": { "dest": alice, "value": 1_000_000_000_000_u64 } }),
+		BalancesCall::TransferKeepAlive { dest: ALICE, value: 1_000_000_000_000 }.encode(),
+	);
+	round_trip(
+		&metadata.types,
+		balances.events.as_ref().unwrap().r#type,
+		json!({
+			"Transfer": { "from": alice, "to": alice, "amount": u128::MAX.to_string() }
+		}),
+		BalancesEvent::Transfer { from: ALICE, to: ALICE, amount: u128::MAX }.encode(),
+	);
+	round_trip(
+		&metadata.types,
+		balances.error.as_ref().unwrap().r#type,
+		json!("InsufficientBalance"),
+		BalancesError::InsufficientBalance.encode(),
+	);
+
+	let constant = &balances.constants["ExistentialDeposit"];
+
+	round_trip(&metadata.types, constant.r#type, json!(1_000_000_000), constant.value.clone());
+
+	let (types, account_info, event) = dynamic_types();
+	let encoder = dynamic::Encoder::new(&types);
+	let info = AccountInfo { nonce: 1, data: AccountData { free: 1_000, flags: 1 << 127 } };
+	let transfer = Event::Transfer { from: AccountId32(ALICE), to: AccountId32(ALICE), amount: 1 };
+
+	round_trip(
+		&types,
+		account_info,
+		json!({
+			"nonce": 1,
+			"data": { "free": 1_000, "flags": "170141183460469231731687303715884105728" }
+		}),
+		info.encode(),
+	);
+	round_trip(
+		&types,
+		event,
+		json!({
+			"Transfer": {
+				"from": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+				"to": "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+				"amount": 1
+			}
+		}),
+		transfer.encode(),
+	);
+	round_trip(
+		&types,
+		event,
+		json!({ "Remarked": ["0x0102", "0x03030303"] }),
+		Event::Remarked(vec![1, 2], [3; 4]).encode(),
+	);
+	round_trip(&types, event, json!("Killed"), Event::Killed.encode());
+	round_trip(
+		&types,
+		event,
+		json!({ "Dispatched": { "Ok": null } }),
+		Event::Dispatched(Ok(())).encode(),
+	);
+	round_trip(
+		&types,
+		event,
+		json!({ "Tip": [7, 5, [true, -2, "x"], i128::MIN.to_string()] }),
+		Event::Tip(Some(7), 5, (true, -2, "x".into()), i128::MIN).encode(),
+	);
+	round_trip(
+		&types,
+		event,
+		json!({ "Tip": [null, 5, [false, 0, ""], -1] }),
+		Event::Tip(None, 5, (false, 0, String::new()), -1).encode(),
+	);
+
+	// `Some` of a nullable value is wrapped.
+	let mut registry = scale_info::Registry::new();
+	let unit = registry.register_type(&meta_type::<Option<()>>()).id;
+	let nested = registry.register_type(&meta_type::<Option<Option<u32>>>()).id;
+	let types = registry.into();
+
+	round_trip(&types, unit, json!(null), None::<()>.encode());
+	round_trip(&types, unit, json!({ "Some": null }), Some(()).encode());
+	round_trip(&types, nested, json!(null), None::<Option<u32>>.encode());
+	round_trip(&types, nested, json!({ "Some": null }), Some(None::<u32>).encode());
+	round_trip(&types, nested, json!({ "Some": 1 }), Some(Some(1_u32)).encode());
+
+	// The lenient representations.
+	assert_eq!(
+		encoder
+			.encode(
+				event,
+				&json!({
+					"Transfer": {
+						"from": "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5",
+						"to": alice,
+						"amount": "1"
+					}
+				})
+			)
+			.unwrap(),
+		transfer.encode()
+	);
+	assert_eq!(
+		encoder.encode(event, &json!({ "Remarked": [[1, 2], [3, 3, 3, 3]] })).unwrap(),
+		Event::Remarked(vec![1, 2], [3; 4]).encode()
+	);
+	assert_eq!(encoder.encode(event, &json!({ "Killed": null })).unwrap(), Event::Killed.encode());
+
+	let error = |r: Result<_>| r.unwrap_err().to_string();
+
+	assert_eq!(
+		error(encoder.encode(event, &json!("Unknown"))),
+		"failed to encode submetadatan::test::Event, unknown variant Unknown of \
+		 submetadatan::test::Event"
+	);
+	assert_eq!(
+		error(encoder.encode(account_info, &json!({ "nonce": 1 }))),
+		"failed to encode submetadatan::test::AccountInfo, missing field data"
+	);
+	assert_eq!(
+		error(encoder.encode(
+			account_info,
+			&json!({ "nonce": 4_294_967_296_u64, "data": { "free": 0, "flags": 0 } })
+		)),
+		"failed to encode submetadatan::test::AccountInfo.nonce, 4294967296 out of range of u32"
+	);
+	assert_eq!(
+		error(encoder.encode(event, &json!({ "Remarked": ["0x", "0x010203"] }))),
+		"failed to encode submetadatan::test::Event.Remarked.1, expected 4 elements, found 3"
+	);
+	// A mistyped address must not be encoded to another account.
+	let from_error = |from: &str| {
+		error(
+			encoder
+				.encode(event, &json!({ "Transfer": { "from": from, "to": alice, "amount": 1 } })),
+		)
+	};
+
+	assert_eq!(
+		from_error("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ"),
+		"failed to encode submetadatan::test::Event.Transfer.from, invalid SS58 address \
+		 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ, checksum mismatch"
+	);
+	assert_eq!(
+		from_error("4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi"),
+		"failed to encode submetadatan::test::Event.Transfer.from, invalid SS58 address \
+		 4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi"
+	);
+	assert_eq!(
+		error(encoder.encode(event, &json!({ "Tip": [null, 5, [1, 0, ""], 0] }))),
+		"failed to encode submetadatan::test::Event.Tip.2.0, expected bool, found 1"
+	);
+
+	// A malformed registry with a self-referential wrapper.
+	let mut builder = scale_info::PortableRegistryBuilder::new();
+	let wrapper = builder.register_type(scale_info::Type {
+		path: Default::default(),
+		type_params: Vec::new(),
+		type_def: TypeDef::Composite(scale_info::TypeDefComposite {
+			fields: vec![scale_info::Field {
+				name: None,
+				ty: 0.into(),
+				type_name: None,
+				docs: Vec::new(),
+			}],
+		}),
+		docs: Vec::new(),
+	});
+	let compact = builder.register_type(scale_info::Type {
+		path: Default::default(),
+		type_params: Vec::new(),
+		type_def: TypeDef::Compact(scale_info::TypeDefCompact { type_param: wrapper.into() }),
+		docs: Vec::new(),
+	});
+	let malformed = builder.finish();
+	let malformed_encoder = dynamic::Encoder::new(&malformed);
+
+	assert_eq!(
+		error(malformed_encoder.encode(wrapper, &json!(1))),
+		"failed to encode #0, reached the maximum depth"
+	);
+	assert_eq!(malformed_encoder.encode(compact, &json!(1)).unwrap(), Compact(1_u128).encode());
+
+	let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+
+	assert_eq!(dynamic::string_to_u256(max), Some([u8::MAX; 32]));
+	assert_eq!(
+		dynamic::string_to_u256(
+			"115792089237316195423570985008687907853269984665640564039457584007913129639936"
+		),
+		None
+	);
+	assert_eq!(dynamic::string_to_u256(""), None);
+	assert_eq!(dynamic::string_to_u256("1a"), None);
+	assert_eq!(
+		dynamic::u256_to_string(
+			dynamic::string_to_u256("1234567890123456789012345678901234567890").unwrap()
+		),
+		"1234567890123456789012345678901234567890"
+	);
+	assert_eq!(dynamic::string_to_i256("-1"), Some([u8::MAX; 32]));
+	assert_eq!(dynamic::string_to_i256("-0"), Some([0; 32]));
+
+	let min = "-57896044618658097711785492504343953926634992332820282019728792003956564819968";
+
+	assert_eq!(dynamic::i256_to_string(dynamic::string_to_i256(min).unwrap()), min);
+	assert_eq!(
+		dynamic::string_to_i256(
+			"-57896044618658097711785492504343953926634992332820282019728792003956564819969"
+		),
+		None
+	);
+	assert_eq!(dynamic::string_to_i256(&min[1..]), None);
+
+	// Build the sample bytes of the type, the first buildable variant is picked.
+	fn sample(types: &PortableRegistry, id: u32, depth: u8) -> Option<Vec<u8>> {
+		if depth > 32 {
+			return None;
+		}
+
+		let sample = |id| sample(types, id, depth + 1);
+		let fields = |ids: &mut dyn Iterator<Item = u32>| {
+			ids.map(sample).collect::<Option<Vec<_>>>().map(|b| b.concat())
+		};
+		let one = |len: usize| [vec![1], vec![0; len - 1]].concat();
+		let bytes = match &types.resolve(id)?.type_def {
+			TypeDef::Composite(c) => fields(&mut c.fields.iter().map(|f| f.ty.id))?,
+			TypeDef::Variant(v) => v.variants.iter().find_map(|v| {
+				Some([vec![v.index], fields(&mut v.fields.iter().map(|f| f.ty.id))?].concat())
+			})?,
+			TypeDef::Sequence(s) => [Compact(1_u32).encode(), sample(s.type_param.id)?].concat(),
+			TypeDef::Array(a) => sample(a.type_param.id)?.repeat(a.len as _),
+			TypeDef::Tuple(t) => fields(&mut t.fields.iter().map(|f| f.id))?,
+			TypeDef::Primitive(p) => match p {
+				TypeDefPrimitive::Bool | TypeDefPrimitive::U8 | TypeDefPrimitive::I8 => one(1),
+				TypeDefPrimitive::Char => ('a' as u32).encode(),
+				TypeDefPrimitive::Str => "a".encode(),
+				TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => one(2),
+				TypeDefPrimitive::U32 | TypeDefPrimitive::I32 => one(4),
+				TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => one(8),
+				TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => one(16),
+				TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => one(32),
+			},
+			TypeDef::Compact(_) => Compact(1_u32).encode(),
+			TypeDef::BitSequence(_) => Compact(0_u32).encode(),
+		};
+
+		Some(bytes)
+	}
+
+	// Round trip the calls, events and storage values of a real metadata.
+	const METADATA: &str = include_str!("../fixtures/rococo_metadata_v15");
+
+	let metadata =
+		Metadata::from(unprefix_raw_metadata_at_version(METADATA.trim_end()).unwrap().unwrap());
+	let types = &metadata.types;
+	let (decoder, encoder) = (dynamic::Decoder::new(types), dynamic::Encoder::new(types));
+	let mut samples = Vec::new();
+
+	for pallet in metadata.pallets.values() {
+		for e in [&pallet.calls, &pallet.events].into_iter().flatten() {
+			let TypeDef::Variant(v) = &types.resolve(e.r#type).unwrap().type_def else {
+				panic!("calls and events must be enums");
+			};
+
+			// Each variant is sampled, instead of the first buildable one only.
+			for variant in &v.variants {
+				let fields = variant.fields.iter().map(|f| sample(types, f.ty.id, 0));
+
+				if let Some(fields) = fields.collect::<Option<Vec<_>>>() {
+					samples.push((e.r#type, [vec![variant.index], fields.concat()].concat()));
+				}
+			}
+		}
+		for entry in pallet.storages.iter().flat_map(|s| s.entries.values()) {
+			if let Some(bytes) = sample(types, entry.value_type, 0) {
+				samples.push((entry.value_type, bytes));
+			}
+		}
+	}
+
+	// All the calls, events and storage values of the fixture are buildable.
+	assert_eq!(samples.len(), 921);
+
+	for (id, bytes) in samples {
+		let value = decoder.decode_all(id, &bytes).unwrap();
+
+		assert_eq!(encoder.encode(id, &value).unwrap(), bytes, "{value}");
+	}
+}
+
+#[cfg(feature = "cmp")]
+#[test]
+fn metadata_diff_should_work() {
+	use scale_info::{PortableRegistry, Registry};
+
+	fn entry<'a>(
+		metadata: &'a mut Metadata,
+		pallet: &str,
+		item: &str,
+	) -> &'a mut metadata::StorageEntryMetadata {
+		metadata
+			.pallets
+			.get_mut(pallet)
+			.unwrap()
+			.storages
+			.as_mut()
+			.unwrap()
+			.entries
+			.get_mut(item)
+			.unwrap()
+	}
+
+	mod a {
+		use super::*;
+
+		#[allow(dead_code)]
+		#[derive(TypeInfo)]
+		pub enum Call {
+			Batch(Vec<Call>),
+			Remark(Vec<u8>),
+		}
+	}
+	mod b {
+		use super::*;
+
+		#[allow(dead_code)]
+		#[derive(TypeInfo)]
+		pub enum Call {
+			Batch(Vec<Call>),
+			Remark(Vec<u8>),
+			Kill,
+		}
+	}
+
+	let register = |t: MetaType| {
+		let mut registry = Registry::new();
+		let symbol = registry.register_type(&t);
+
+		(PortableRegistry::from(registry), symbol)
+	};
+	let (a_types, a_call) = register(meta_type::<a::Call>());
+	let (b_types, b_call) = register(meta_type::<b::Call>());
+
+	// Recursive types terminate and the variants are compared.
+	assert!(cmp::untracked_symbol(&a_types, &a_types, &a_call, &a_call));
+	assert!(!cmp::untracked_symbol(&a_types, &b_types, &a_call, &b_call));
+
+	let a = Metadata::from(metadata());
+	let mut b = a.clone();
+	let u32_type = a.pallets["System"].storages.as_ref().unwrap().entries["Number"].value_type;
+
+	assert!(cmp::diff(&a, &b).is_empty());
+	assert_eq!(cmp::diff(&a, &b).to_string(), "no changes");
+
+	b.pallets.remove("Preimage");
+	b.pallets.insert(
+		"Sudo".into(),
+		metadata::PalletMetadata { index: 20, ..a.pallets["System"].clone() },
+	);
+
+	let balances = b.pallets.get_mut("Balances").unwrap();
+
+	balances.index = 6;
+	balances.calls.as_mut().unwrap().variants.get_mut("TransferKeepAlive").unwrap().index = 4;
+	balances.events.as_mut().unwrap().variants.get_mut("Transfer").unwrap().fields[2].r#type =
+		u32_type;
+	balances.constants.get_mut("ExistentialDeposit").unwrap().value = 1_u128.encode();
+
+	entry(&mut b, "Balances", "Inactive").modifier = metadata::StorageEntryModifier::Default;
+	entry(&mut b, "Balances", "TotalIssuance").default = vec![0; 16];
+	entry(&mut b, "Staking", "ErasStakers").r#type =
+		metadata::StorageEntryType::Map(vec![StorageHasher::Twox64Concat, StorageHasher::Identity]);
+
+	let diff = cmp::diff(&a, &b);
+
+	assert!(diff.is_breaking());
+	assert_eq!(diff.breaking_changes().count(), 6);
+	assert_eq!(
+		diff.to_string(),
+		"[breaking] Balances: index changed from 5 to 6\n\
+		 [breaking] Balances::TransferKeepAlive (call): index changed from 3 to 4\n\
+		 [breaking] Balances::Transfer (event): fields changed\n\
+		 [non-breaking] Balances::ExistentialDeposit (constant): value changed\n\
+		 [breaking] Balances::Inactive (storage): modifier changed from Optional to Default\n\
+		 [non-breaking] Balances::TotalIssuance (storage): default value changed\n\
+		 [breaking] Preimage: removed\n\
+		 [breaking] Staking::ErasStakers (storage): hashers changed from [Twox64Concat, \
+		 Twox64Concat] to [Twox64Concat, Identity]\n\
+		 [non-breaking] Sudo: added"
+	);
+	assert_eq!(
+		diff.to_json()["changes"][1],
+		serde_json::json!({
+			"pallet": "Balances",
+			"item": { "type": "call", "name": "TransferKeepAlive" },
+			"kind": "index_changed",
+			"from": 3,
+			"to": 4,
+			"breaking": true
+		})
+	);
+	assert_eq!(
+		diff.to_json()["changes"][6],
+		serde_json::json!({ "pallet": "Preimage", "item": null, "kind": "removed", "breaking": true })
+	);
+
+	// Legacy metadata compares the type names.
+	let a = Metadata::from(metadata_v11());
+	let mut b = a.clone();
+
+	entry(&mut b, "System", "Account").value_type_name = Some("AccountInfoV2".into());
+
+	assert_eq!(cmp::diff(&a, &b).to_string(), "[breaking] System::Account (storage): type changed");
+
+	// The types are not compared across the type system change.
+	let diff = cmp::diff(&a, &Metadata::from(metadata()));
+
+	assert!(diff.type_system_changed);
+	assert!(!diff.changes.iter().any(|c| {
+		matches!(c.kind, cmp::ChangeKind::TypeChanged | cmp::ChangeKind::FieldsChanged)
+	}));
+	assert!(diff.to_string().starts_with("type system changed, the types are not compared\n"));
+	assert_eq!(diff.to_json()["type_system_changed"], true);
+}
+
+#[cfg(feature = "metadata-hash")]
+#[test]
+fn metadata_hash_should_work() {
+	use merkleized_metadata::{ExtraInfo, SignedExtrinsicData};
+	use metadata_hash::ChainInfo;
+	use parity_scale_codec::Compact;
+
+	// Vectors of https://github.com/bkchr/merkleized-metadata.
+	const METADATA: &str = include_str!("../fixtures/rococo_metadata_v15");
+	const EXTRINSIC: &str = "0x2d028400d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d01bce7c8f572d39cee240e3d50958f68a5c129e0ac0d4eb9222de70abdfa8c44382a78eded433782e6b614a97d8fd609a3f20162f3f3b3c16e7e8489b2bd4fa98c070000000403008eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a4828";
+	const CALL: &str =
+		"0x04030052bc71c1eca5353749542dfdf0af97bf764f9c2f44e860cd485f1cd86400f6490f0080c6a47e8d03";
+	// Without the trailing byte of the upstream vector, which is not a part of the signed data.
+	const ADDITIONAL_SIGNED: &str = "0x00b2590f0018000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000";
+
+	let bytes = |s| array_bytes::hex2bytes(s).unwrap();
+	let metadata = unprefix_raw_metadata_at_version(METADATA.trim_end()).unwrap().unwrap();
+	let runtime_metadata = {
+		let opaque = <Option<Vec<u8>>>::decode(&mut &*bytes(METADATA.trim_end())).unwrap();
+
+		RuntimeMetadataPrefixed::decode(&mut &*opaque.unwrap()).unwrap().1
+	};
+	let chain_info = ChainInfo {
+		spec_version: 1,
+		spec_name: "nice".into(),
+		base58_prefix: 1,
+		decimals: 1,
+		token_symbol: "lol".into(),
+	};
+	let (extrinsic, call, additional_signed) =
+		(bytes(EXTRINSIC), bytes(CALL), bytes(ADDITIONAL_SIGNED));
+	let included_in_extrinsic = bytes("0x07000000");
+
+	assert_eq!(
+		array_bytes::bytes2hex(
+			"0x",
+			metadata_hash::metadata_hash(&metadata, chain_info.clone()).unwrap()
+		),
+		"0x6619a31025a9a14086a34da4ede7ed61258b9f55c12baae8bc801317869d2dfb"
+	);
+	assert_eq!(
+		metadata_hash::metadata_digest(&metadata, chain_info.clone()).unwrap().encode(),
+		merkleized_metadata::generate_metadata_digest(
+			&runtime_metadata,
+			ExtraInfo {
+				spec_version: chain_info.spec_version,
+				spec_name: chain_info.spec_name.clone(),
+				base58_prefix: chain_info.base58_prefix,
+				decimals: chain_info.decimals,
+				token_symbol: chain_info.token_symbol.clone(),
+			}
+		)
+		.unwrap()
+		.encode()
+	);
+
+	for additional_signed in [None, Some(&*additional_signed)] {
+		let proof =
+			metadata_hash::proof_for_extrinsic(&metadata, &extrinsic, additional_signed).unwrap();
+
+		assert!(!proof.leaves.is_empty());
+		assert_eq!(
+			proof.encode(),
+			merkleized_metadata::generate_proof_for_extrinsic(
+				&extrinsic,
+				additional_signed,
+				&runtime_metadata
+			)
+			.unwrap()
+			.encode()
+		);
+	}
+	for with_signed_data in [false, true] {
+		let proof = metadata_hash::proof_for_extrinsic_parts(
+			&metadata,
+			&call,
+			with_signed_data.then_some(metadata_hash::SignedExtrinsicData {
+				included_in_extrinsic: &included_in_extrinsic,
+				included_in_signed_data: &additional_signed,
+			}),
+		)
+		.unwrap();
+
+		assert_eq!(
+			proof.encode(),
+			merkleized_metadata::generate_proof_for_extrinsic_parts(
+				&call,
+				with_signed_data.then_some(SignedExtrinsicData {
+					included_in_extrinsic: &included_in_extrinsic,
+					included_in_signed_data: &additional_signed,
+				}),
+				&runtime_metadata
+			)
+			.unwrap()
+			.encode()
+		);
+	}
+
+	let body = {
+		let input = &mut &*extrinsic;
+
+		<Compact<u32>>::decode(input).unwrap();
+
+		input.to_vec()
+	};
+	let with_len = |b: &[u8]| [Compact(b.len() as u32).encode(), b.to_vec()].concat();
+	let mut trailing = body.clone();
+
+	trailing.push(0);
+
+	assert_eq!(
+		metadata_hash::proof_for_extrinsic(&metadata, &with_len(&trailing), None)
+			.unwrap_err()
+			.to_string(),
+		"failed to merkleize the metadata, 1 bytes left in the extrinsic"
+	);
+	assert_eq!(
+		metadata_hash::proof_for_extrinsic(&metadata, &with_len(&body[..body.len() - 1]), None)
+			.unwrap_err()
+			.to_string(),
+		"failed to merkleize the metadata, failed to decode the call, Not enough data to fill buffer"
+	);
+	assert_eq!(
+		metadata_hash::proof_for_extrinsic(&metadata, &extrinsic[..extrinsic.len() - 1], None)
+			.unwrap_err()
+			.to_string(),
+		"failed to merkleize the metadata, expected 139 bytes of the extrinsic, found 138"
+	);
+
+	// `System::remark` with a length far beyond the input.
+	assert_eq!(
+		metadata_hash::proof_for_extrinsic_parts(
+			&metadata,
+			&[vec![0, 0], Compact(u32::MAX).encode()].concat(),
+			None
+		)
+		.unwrap_err()
+		.to_string(),
+		"failed to merkleize the metadata, failed to decode the call, sequence length 4294967295 \
+		 exceeds the 0 bytes left"
+	);
+
+	let mut trailing = additional_signed.clone();
+
+	trailing.push(0);
+
+	assert_eq!(
+		metadata_hash::proof_for_extrinsic(&metadata, &extrinsic, Some(&trailing))
+			.unwrap_err()
+			.to_string(),
+		"failed to merkleize the metadata, 1 bytes left in the signed data"
+	);
+	assert_eq!(
+		metadata_hash::proof_for_extrinsic_parts(
+			&metadata,
+			&call,
+			Some(metadata_hash::SignedExtrinsicData {
+				included_in_extrinsic: &included_in_extrinsic,
+				included_in_signed_data: &trailing,
+			}),
+		)
+		.unwrap_err()
+		.to_string(),
+		"failed to merkleize the metadata, 1 bytes left in the signed data"
+	);
+	assert_eq!(
+		metadata_hash::proof_for_extrinsic_parts(
+			&metadata,
+			&call,
+			Some(metadata_hash::SignedExtrinsicData {
+				included_in_extrinsic: &[&*included_in_extrinsic, &[0]].concat(),
+				included_in_signed_data: &additional_signed,
+			}),
+		)
+		.unwrap_err()
+		.to_string(),
+		"failed to merkleize the metadata, 1 bytes left in the extensions"
+	);
+	assert!(matches!(
+		metadata_hash::metadata_hash(&VersionedRuntimeMetadata::V14(self::metadata()), chain_info),
+		Err(Error::UnsupportedVersion(14))
+	));
+}
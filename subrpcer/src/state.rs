@@ -44,3 +44,89 @@ pub fn get_keys_paged_raw(
 ) -> (&'static str, serde_json::Value) {
 	("state_getKeysPaged", serde_json::json!([prefix, count, start_key, hash]))
 }
+
+/// Pagination state of [`get_keys_paged`], for iterating all the keys under a prefix.
+///
+/// # Example
+/// ```
+/// let prefix = "0x26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9";
+/// let mut pager = subrpcer::state::KeysPager::new(prefix, 2);
+///
+/// let request = pager.next_page(0).unwrap();
+///
+/// assert_eq!(request["method"], "state_getKeysPaged");
+///
+/// // Send the request, and feed the returned keys back.
+/// // Here, the page contains less than 2 keys, which ends the iteration.
+/// let keys = ["0x26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9de1e86a9a8c739864cf3cc5ec2bea59fd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"];
+///
+/// pager.advance(&keys);
+///
+/// assert!(pager.is_done());
+/// assert!(pager.next_page(1).is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct KeysPager {
+	prefix: serde_json::Value,
+	count: u32,
+	start_key: Option<String>,
+	hash: Option<serde_json::Value>,
+	done: bool,
+}
+impl KeysPager {
+	/// Create a new [`KeysPager`], which fetches `count` keys per page.
+	///
+	/// The prefix is usually a hex string.
+	pub fn new(prefix: impl serde::Serialize, count: u32) -> Self {
+		Self { prefix: serde_json::json!(prefix), count, start_key: None, hash: None, done: false }
+	}
+
+	/// Iterate the keys at the given block hash.
+	pub fn at(mut self, hash: impl serde::Serialize) -> Self {
+		self.hash = Some(serde_json::json!(hash));
+
+		self
+	}
+
+	/// Build the request of the next page.
+	///
+	/// Return [`None`] if all the keys have been fetched.
+	pub fn next_page(&self, id: usize) -> Option<serde_json::Value> {
+		let (method, params) = self.next_page_raw()?;
+
+		Some(crate::rpc(id, method, params))
+	}
+
+	/// Similar to [`KeysPager::next_page`], but return the method name and parameters directly.
+	pub fn next_page_raw(&self) -> Option<(&'static str, serde_json::Value)> {
+		(!self.done).then(|| {
+			get_keys_paged_raw(
+				Some(&self.prefix),
+				self.count,
+				self.start_key.as_ref(),
+				self.hash.as_ref(),
+			)
+		})
+	}
+
+	/// Feed the keys of the last page, the next page will start after the last key.
+	///
+	/// The keys could be anything displayed as the hex strings, such as the `StorageKey`s of
+	/// `substorager`.
+	/// An empty page or a page with less than `count` keys is the last page.
+	pub fn advance<K>(&mut self, keys: &[K])
+	where
+		K: std::fmt::Display,
+	{
+		if let Some(k) = keys.last() {
+			self.start_key = Some(k.to_string());
+		}
+
+		self.done = keys.is_empty() || keys.len() < self.count as usize;
+	}
+
+	/// Check if all the keys have been fetched.
+	pub fn is_done(&self) -> bool {
+		self.done
+	}
+}
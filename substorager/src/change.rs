@@ -0,0 +1,131 @@
+//! Storage change tracking.
+//!
+//! Substrate reference(s):
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/primitives/storage/src/lib.rs>
+
+// std
+use std::collections::BTreeMap;
+// crates.io
+#[cfg(feature = "serde")] use serde::{Deserialize, Serialize};
+// substrate-minimal
+use crate::{StorageData, StorageKey};
+
+/// Storage changes of a block.
+///
+/// With the `serde` feature, the results of `state_queryStorage`, `state_queryStorageAt` and the
+/// notifications of `state_subscribeStorage` could be deserialized into this directly.
+/// `H` is the block hash type, such as [`String`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct StorageChangeSet<H> {
+	/// Block hash.
+	pub block: H,
+	/// Changed keys and their new values, [`None`] if the key was removed.
+	pub changes: Vec<(StorageKey, Option<StorageData>)>,
+}
+
+/// Apply the [`StorageChangeSet`]s in the block order to an in-memory view, and keep the history
+/// of each key.
+#[derive(Clone, Debug)]
+pub struct ChangeTracker<H> {
+	blocks: Vec<H>,
+	values: BTreeMap<StorageKey, StorageData>,
+	// The block index and the new value of each change.
+	history: BTreeMap<StorageKey, Vec<(usize, Option<StorageData>)>>,
+}
+impl<H> ChangeTracker<H>
+where
+	H: PartialEq,
+{
+	/// Create an empty [`ChangeTracker`].
+	pub fn new() -> Self {
+		Self { blocks: Vec::new(), values: BTreeMap::new(), history: BTreeMap::new() }
+	}
+
+	/// Apply the changes of the next block.
+	pub fn apply(&mut self, change_set: StorageChangeSet<H>) {
+		let i = self.blocks.len();
+
+		self.blocks.push(change_set.block);
+
+		for (k, v) in change_set.changes {
+			match &v {
+				Some(v) => self.values.insert(k.clone(), v.clone()),
+				None => self.values.remove(&k),
+			};
+
+			self.history.entry(k).or_default().push((i, v));
+		}
+	}
+
+	/// Get the applied blocks in order.
+	pub fn blocks(&self) -> &[H] {
+		&self.blocks
+	}
+
+	/// Get the latest value of the key.
+	pub fn get(&self, key: &StorageKey) -> Option<&StorageData> {
+		self.values.get(key)
+	}
+
+	/// Iterate the latest key/value pairs in the key order.
+	pub fn iter(&self) -> impl Iterator<Item = (&StorageKey, &StorageData)> {
+		self.values.iter()
+	}
+
+	/// Get all the changes of the key, in the block order.
+	///
+	/// Each change is the block hash along with the new value, [`None`] if the key was removed.
+	pub fn history(&self, key: &StorageKey) -> Vec<(&H, Option<&StorageData>)> {
+		self.history_in(key, 0, usize::MAX)
+	}
+
+	/// Get the changes of the key within the block range, both ends are inclusive.
+	///
+	/// Return [`None`] if any end of the range has not been applied.
+	pub fn history_between(
+		&self,
+		key: &StorageKey,
+		from: &H,
+		to: &H,
+	) -> Option<Vec<(&H, Option<&StorageData>)>> {
+		let from = self.blocks.iter().position(|b| b == from)?;
+		let to = self.blocks.iter().rposition(|b| b == to)?;
+
+		Some(self.history_in(key, from, to))
+	}
+
+	fn history_in(
+		&self,
+		key: &StorageKey,
+		from: usize,
+		to: usize,
+	) -> Vec<(&H, Option<&StorageData>)> {
+		self.history
+			.get(key)
+			.into_iter()
+			.flatten()
+			.filter(|(i, _)| (from..=to).contains(i))
+			.map(|(i, v)| (&self.blocks[*i], v.as_ref()))
+			.collect()
+	}
+}
+impl<H> Default for ChangeTracker<H>
+where
+	H: PartialEq,
+{
+	fn default() -> Self {
+		Self::new()
+	}
+}
+impl<H> Extend<StorageChangeSet<H>> for ChangeTracker<H>
+where
+	H: PartialEq,
+{
+	fn extend<I>(&mut self, iter: I)
+	where
+		I: IntoIterator<Item = StorageChangeSet<H>>,
+	{
+		iter.into_iter().for_each(|c| self.apply(c));
+	}
+}
@@ -0,0 +1,112 @@
+//! Child trie.
+//!
+//! Substrate reference(s):
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/primitives/storage/src/lib.rs>
+
+// crates.io
+#[cfg(feature = "codec")] use parity_scale_codec::{Decode, Encode};
+// substrate-minimal
+use crate::{well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX, StorageKey};
+
+/// Type of the child trie.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ChildType {
+	/// The child trie is identified by the unprefixed storage key in the parent trie.
+	ParentKeyId = 1,
+}
+impl ChildType {
+	/// Get the prefix of the storage keys of this child type.
+	pub fn parent_prefix(&self) -> &'static [u8] {
+		match self {
+			Self::ParentKeyId => DEFAULT_CHILD_STORAGE_KEY_PREFIX,
+		}
+	}
+
+	/// Split the prefixed storage key into the child type and the unprefixed storage key.
+	///
+	/// Return [`None`] if the key is not a known child storage key.
+	pub fn from_prefixed_key(key: &[u8]) -> Option<(Self, &[u8])> {
+		key.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX).map(|k| (Self::ParentKeyId, k))
+	}
+}
+
+/// Information of a child trie.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+pub enum ChildInfo {
+	/// Child trie, which is identified by the unprefixed storage key in the parent trie.
+	ParentKeyId(ChildTrieParentKeyId),
+}
+impl ChildInfo {
+	/// Create a default child trie with the unique ID.
+	pub fn new_default<I>(storage_key: I) -> Self
+	where
+		I: AsRef<[u8]>,
+	{
+		Self::ParentKeyId(ChildTrieParentKeyId { data: storage_key.as_ref().to_vec() })
+	}
+
+	/// Create a default child trie, whose unique ID is the `blake2_256` hash of the data.
+	///
+	/// This is how most of the pallets derive their child trie IDs.
+	/// For example, the crowdloan fund `index` is stored in the child trie of
+	/// `ChildInfo::new_default_hashed([&b"crowdloan"[..], &index.to_le_bytes()].concat())`.
+	pub fn new_default_hashed<D>(data: D) -> Self
+	where
+		D: AsRef<[u8]>,
+	{
+		Self::new_default(subhasher::blake2_256(data))
+	}
+
+	/// Parse the prefixed storage key.
+	///
+	/// Return [`None`] if the key is not a known child storage key.
+	pub fn from_prefixed_key<K>(key: K) -> Option<Self>
+	where
+		K: AsRef<[u8]>,
+	{
+		ChildType::from_prefixed_key(key.as_ref()).map(|(t, k)| match t {
+			ChildType::ParentKeyId => Self::new_default(k),
+		})
+	}
+
+	/// Get the unprefixed storage key, which is the unique ID of the child trie.
+	pub fn storage_key(&self) -> &[u8] {
+		match self {
+			Self::ParentKeyId(i) => &i.data,
+		}
+	}
+
+	/// Get the prefixed storage key, under which the child trie root is stored in the parent trie.
+	///
+	/// This is also the key which the child storage RPCs accept.
+	pub fn prefixed_storage_key(&self) -> StorageKey {
+		let mut k = self.child_type().parent_prefix().to_vec();
+
+		k.extend_from_slice(self.storage_key());
+
+		k.into()
+	}
+
+	/// Get the keyspace of the child trie.
+	///
+	/// The keyspace is the unique ID of the child trie.
+	pub fn keyspace(&self) -> &[u8] {
+		self.storage_key()
+	}
+
+	/// Get the type of the child trie.
+	pub fn child_type(&self) -> ChildType {
+		match self {
+			Self::ParentKeyId(_) => ChildType::ParentKeyId,
+		}
+	}
+}
+
+/// Unique ID of a child trie, which is the unprefixed storage key in the parent trie.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+pub struct ChildTrieParentKeyId {
+	data: Vec<u8>,
+}
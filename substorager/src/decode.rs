@@ -0,0 +1,56 @@
+//! Storage key decoding.
+
+// substrate-minimal
+use crate::StorageHasher;
+
+/// Hashed part of a map storage key.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageKeyPart {
+	/// Hasher of this part.
+	pub hasher: StorageHasher,
+	/// Hash of the key.
+	///
+	/// Empty if the hasher is [`StorageHasher::Identity`].
+	pub hash: Vec<u8>,
+	/// SCALE encoded original key.
+	///
+	/// Only available if the hasher is transparent, check [`StorageHasher::is_transparent`].
+	pub key: Option<Vec<u8>>,
+}
+
+/// Split the hashed keys, which are placed after the 32-bytes storage prefix, of a map storage key.
+///
+/// Each hasher comes with the SCALE encoded size of its key, [`None`] if the size is not fixed.
+/// The original key could only be recovered if the size is known or it is the last part.
+/// Return [`None`] if the keys do not match the hashers.
+pub fn split_map_keys<H>(
+	mut hashed_keys: &[u8],
+	hashers: &[(H, Option<usize>)],
+) -> Option<Vec<StorageKeyPart>>
+where
+	H: AsRef<StorageHasher>,
+{
+	let mut parts = Vec::with_capacity(hashers.len());
+
+	for (i, (hasher, size)) in hashers.iter().enumerate() {
+		let hasher = hasher.as_ref();
+		let (hash, rest) = hashed_keys.split_at_checked(hasher.hash_len())?;
+		let (key, rest) = if hasher.is_transparent() {
+			let size = match size {
+				Some(s) => *s,
+				None if i == hashers.len() - 1 => rest.len(),
+				None => return None,
+			};
+			let (key, rest) = rest.split_at_checked(size)?;
+
+			(Some(key.to_vec()), rest)
+		} else {
+			(None, rest)
+		};
+
+		parts.push(StorageKeyPart { hasher: hasher.to_owned(), hash: hash.to_vec(), key });
+		hashed_keys = rest;
+	}
+
+	hashed_keys.is_empty().then_some(parts)
+}
@@ -0,0 +1,27 @@
+//! Substorager error collections.
+
+// crates.io
+use thiserror::Error as ThisError;
+
+/// Main error.
+#[allow(missing_docs)]
+#[derive(Debug, ThisError)]
+pub enum Error {
+	#[error("[substorager] {0:?}")]
+	ArrayBytes(array_bytes::Error),
+	#[cfg(feature = "codec")]
+	#[error(transparent)]
+	Codec(parity_scale_codec::Error),
+	#[error("[substorager] incomplete trie proof, missing node {}", array_bytes::bytes2hex("0x", .0))]
+	IncompleteTrieProof([u8; 32]),
+	#[error(transparent)]
+	Io(#[from] std::io::Error),
+	#[error("[substorager] invalid child trie root")]
+	InvalidChildTrieRoot,
+	#[error("[substorager] invalid snapshot, {0}")]
+	InvalidSnapshot(&'static str),
+	#[error("[substorager] invalid trie node, {0}")]
+	InvalidTrieNode(&'static str),
+	#[error("[substorager] unsupported state version, {0}")]
+	UnsupportedStateVersion(u8),
+}
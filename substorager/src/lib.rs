@@ -4,59 +4,175 @@
 #![deny(unused_crate_dependencies)]
 
 #[cfg(test)] mod test;
+#[cfg(test)] use serde_json as _;
+
+mod change;
+pub use change::*;
+
+mod child;
+pub use child::*;
+
+mod decode;
+pub use decode::*;
+
+pub mod error;
+pub use error::Error;
+
+pub mod snapshot;
+
+pub mod trie;
+
+pub mod well_known_keys;
 
 // std
 use std::{
 	fmt::{Display, Formatter, Result as FmtResult},
 	ops::Deref,
+	str::FromStr,
 };
 // crates.io
-#[cfg(feature = "codec")] use parity_scale_codec::{Decode, Encode};
+#[cfg(feature = "codec")] use parity_scale_codec::{Decode, DecodeAll, Encode};
+#[cfg(feature = "serde")]
+use serde::{de::Error as DeError, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Main result.
+pub type Result<T> = std::result::Result<T, Error>;
+
+// Implement the common traits for the bytes wrappers.
+//
+// They are displayed, parsed and serialized as `0x` prefixed hex strings.
+macro_rules! impl_bytes {
+	($t:ident) => {
+		impl AsRef<[u8]> for $t {
+			fn as_ref(&self) -> &[u8] {
+				&self.0
+			}
+		}
+		impl Deref for $t {
+			type Target = [u8];
+
+			fn deref(&self) -> &Self::Target {
+				&self.0
+			}
+		}
+		impl Display for $t {
+			fn fmt(&self, f: &mut Formatter) -> FmtResult {
+				write!(f, "{}", array_bytes::bytes2hex("0x", &self.0))
+			}
+		}
+		impl FromStr for $t {
+			type Err = Error;
+
+			fn from_str(s: &str) -> Result<Self> {
+				Ok(Self(array_bytes::hex2bytes(s).map_err(Error::ArrayBytes)?))
+			}
+		}
+		impl From<Vec<u8>> for $t {
+			fn from(v: Vec<u8>) -> Self {
+				Self(v)
+			}
+		}
+		impl<const N: usize> From<[u8; N]> for $t {
+			fn from(v: [u8; N]) -> Self {
+				Self(v.to_vec())
+			}
+		}
+		impl From<&[u8]> for $t {
+			fn from(v: &[u8]) -> Self {
+				Self(v.to_vec())
+			}
+		}
+		impl From<$t> for Vec<u8> {
+			fn from(v: $t) -> Self {
+				v.0
+			}
+		}
+		#[cfg(feature = "serde")]
+		impl Serialize for $t {
+			fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+			where
+				S: Serializer,
+			{
+				serializer.serialize_str(&self.to_string())
+			}
+		}
+		#[cfg(feature = "serde")]
+		impl<'de> Deserialize<'de> for $t {
+			fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+			where
+				D: Deserializer<'de>,
+			{
+				String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+			}
+		}
+	};
+}
 
 /// Storage key.
 ///
 /// Substrate reference(s):
 /// - <https://github.com/paritytech/substrate/blob/c4d36065764ee23aeb3ccd181c4b6ecea8d2447a/primitives/storage/src/lib.rs#L35-L43>
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
 pub struct StorageKey(pub Vec<u8>);
 impl StorageKey {
 	/// Create an empty [`StorageKey`].
 	pub fn new() -> Self {
 		Default::default()
 	}
-}
-impl AsRef<[u8]> for StorageKey {
-	fn as_ref(&self) -> &[u8] {
-		&self.0
-	}
-}
-impl Deref for StorageKey {
-	type Target = [u8];
 
-	fn deref(&self) -> &Self::Target {
-		&self.0
+	/// Check if the key starts with the prefix, such as a pallet or an item prefix.
+	pub fn starts_with<P>(&self, prefix: P) -> bool
+	where
+		P: AsRef<[u8]>,
+	{
+		self.0.starts_with(prefix.as_ref())
 	}
-}
-impl Display for StorageKey {
-	fn fmt(&self, f: &mut Formatter) -> FmtResult {
-		write!(f, "{}", array_bytes::bytes2hex("0x", &self.0))
+
+	/// Strip the prefix, such as an item prefix, from the key.
+	///
+	/// Return [`None`] if the key does not start with the prefix.
+	pub fn strip_prefix<P>(&self, prefix: P) -> Option<&[u8]>
+	where
+		P: AsRef<[u8]>,
+	{
+		self.0.strip_prefix(prefix.as_ref())
 	}
 }
-impl From<Vec<u8>> for StorageKey {
-	fn from(v: Vec<u8>) -> Self {
-		Self(v)
+impl_bytes!(StorageKey);
+
+/// Storage data.
+///
+/// Substrate reference(s):
+/// - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/primitives/storage/src/lib.rs>
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+pub struct StorageData(pub Vec<u8>);
+impl StorageData {
+	/// Create an empty [`StorageData`].
+	pub fn new() -> Self {
+		Default::default()
 	}
-}
-impl<const N: usize> From<[u8; N]> for StorageKey {
-	fn from(v: [u8; N]) -> Self {
-		Self(v.to_vec())
+
+	/// SCALE encode the value into a [`StorageData`].
+	#[cfg(feature = "codec")]
+	pub fn encode_from<T>(value: &T) -> Self
+	where
+		T: Encode,
+	{
+		Self(value.encode())
 	}
-}
-impl From<&[u8]> for StorageKey {
-	fn from(v: &[u8]) -> Self {
-		Self(v.to_vec())
+
+	/// SCALE decode the data into `T`, the data must be consumed entirely.
+	#[cfg(feature = "codec")]
+	pub fn decode<T>(&self) -> Result<T>
+	where
+		T: Decode,
+	{
+		T::decode_all(&mut &*self.0).map_err(Error::Codec)
 	}
 }
+impl_bytes!(StorageData);
 
 /// Storage hasher.
 ///
@@ -97,6 +213,22 @@ impl StorageHasher {
 		}
 	}
 }
+impl StorageHasher {
+	/// Get the length of the hash, which is placed before the original key, if there is any.
+	pub fn hash_len(&self) -> usize {
+		match self {
+			Self::Blake2_128 | Self::Blake2_128Concat | Self::Twox128 => 16,
+			Self::Blake2_256 | Self::Twox256 => 32,
+			Self::Twox64Concat => 8,
+			Self::Identity => 0,
+		}
+	}
+
+	/// Check if the original key could be recovered from the hashed key.
+	pub fn is_transparent(&self) -> bool {
+		matches!(self, Self::Blake2_128Concat | Self::Twox64Concat | Self::Identity)
+	}
+}
 impl AsRef<StorageHasher> for StorageHasher {
 	fn as_ref(&self) -> &Self {
 		self
@@ -118,6 +250,9 @@ where
 }
 
 /// Calculate the storage key of a pallet `StorageNMap` item.
+///
+/// If only the first few keys are given, the result is the prefix shared by the entries with these
+/// keys.
 pub fn storage_n_map_key<A, B, C, C1, C2>(pallet: A, item: B, keys: C) -> StorageKey
 where
 	A: AsRef<[u8]>,
@@ -132,3 +267,137 @@ where
 
 	k
 }
+
+/// Calculate the prefix of a pallet `StorageMap`, `StorageDoubleMap` or `StorageNMap` item.
+///
+/// All the entries of the item share this prefix.
+/// To get the prefix shared by the entries with the same first few keys, use
+/// [`storage_n_map_key`] with these keys only, or the encoding variants.
+pub fn storage_map_prefix<A, B>(pallet: A, item: B) -> StorageKey
+where
+	A: AsRef<[u8]>,
+	B: AsRef<[u8]>,
+{
+	storage_value_key(pallet, item)
+}
+
+/// Calculate the storage key of a pallet `StorageMap` item.
+///
+/// The key will be SCALE encoded before hashing.
+#[cfg(feature = "codec")]
+pub fn storage_map_key<A, B, H, K>(pallet: A, item: B, hasher: H, key: K) -> StorageKey
+where
+	A: AsRef<[u8]>,
+	B: AsRef<[u8]>,
+	H: AsRef<StorageHasher>,
+	K: Encode,
+{
+	storage_encoded_n_map_key(pallet, item, ((hasher, key),))
+}
+
+/// Calculate the storage key of a pallet `StorageDoubleMap` item.
+///
+/// The keys will be SCALE encoded before hashing.
+#[cfg(feature = "codec")]
+pub fn storage_double_map_key<A, B, H1, K1, H2, K2>(
+	pallet: A,
+	item: B,
+	(hasher1, key1): (H1, K1),
+	(hasher2, key2): (H2, K2),
+) -> StorageKey
+where
+	A: AsRef<[u8]>,
+	B: AsRef<[u8]>,
+	H1: AsRef<StorageHasher>,
+	K1: Encode,
+	H2: AsRef<StorageHasher>,
+	K2: Encode,
+{
+	storage_encoded_n_map_key(pallet, item, ((hasher1, key1), (hasher2, key2)))
+}
+
+/// Calculate the prefix shared by the entries of a pallet `StorageDoubleMap` item, whose first key
+/// is `key1`.
+///
+/// The key will be SCALE encoded before hashing.
+#[cfg(feature = "codec")]
+pub fn storage_double_map_prefix<A, B, H1, K1>(
+	pallet: A,
+	item: B,
+	hasher1: H1,
+	key1: K1,
+) -> StorageKey
+where
+	A: AsRef<[u8]>,
+	B: AsRef<[u8]>,
+	H1: AsRef<StorageHasher>,
+	K1: Encode,
+{
+	storage_encoded_n_map_key(pallet, item, ((hasher1, key1),))
+}
+
+/// Calculate the storage key of a pallet `StorageNMap` item.
+///
+/// Similar to [`storage_n_map_key`], but the keys are a tuple of `(hasher, key)` pairs, and each
+/// key will be SCALE encoded before hashing.
+/// Likewise, the result is a prefix if only the first few keys are given.
+///
+/// # Example
+/// ```
+/// use substorager::StorageHasher;
+///
+/// let era = (StorageHasher::Twox64Concat, 0_u32);
+/// let account = (StorageHasher::Twox64Concat, [0_u8; 32]);
+/// let page = (StorageHasher::Twox64Concat, 0_u32);
+/// let keys = (era, account, page);
+/// let k = substorager::storage_encoded_n_map_key("Staking", "ErasStakersPaged", keys);
+/// ```
+#[cfg(feature = "codec")]
+pub fn storage_encoded_n_map_key<A, B, K>(pallet: A, item: B, keys: K) -> StorageKey
+where
+	A: AsRef<[u8]>,
+	B: AsRef<[u8]>,
+	K: EncodeKeys,
+{
+	let mut k = storage_value_key(pallet, item);
+
+	keys.encode_keys_to(&mut k.0);
+
+	k
+}
+
+/// Tuple of `(hasher, key)` pairs, which could be encoded into a storage key suffix.
+#[cfg(feature = "codec")]
+pub trait EncodeKeys {
+	/// Encode and hash each key, then append the results to the given buffer.
+	fn encode_keys_to(&self, dest: &mut Vec<u8>);
+}
+#[cfg(feature = "codec")]
+macro_rules! impl_encode_keys {
+	($(($($h:ident, $k:ident),+),)+) => {
+		$(
+			impl<$($h, $k),+> EncodeKeys for ($(($h, $k),)+)
+			where
+				$($h: AsRef<StorageHasher>, $k: Encode,)+
+			{
+				#[allow(non_snake_case)]
+				fn encode_keys_to(&self, dest: &mut Vec<u8>) {
+					let ($(($h, $k),)+) = self;
+
+					$(dest.extend_from_slice(&$k.using_encoded(|k| $h.as_ref().hash(k)));)+
+				}
+			}
+		)+
+	};
+}
+#[cfg(feature = "codec")]
+impl_encode_keys! {
+	(H1, K1),
+	(H1, K1, H2, K2),
+	(H1, K1, H2, K2, H3, K3),
+	(H1, K1, H2, K2, H3, K3, H4, K4),
+	(H1, K1, H2, K2, H3, K3, H4, K4, H5, K5),
+	(H1, K1, H2, K2, H3, K3, H4, K4, H5, K5, H6, K6),
+	(H1, K1, H2, K2, H3, K3, H4, K4, H5, K5, H6, K6, H7, K7),
+	(H1, K1, H2, K2, H3, K3, H4, K4, H5, K5, H6, K6, H7, K7, H8, K8),
+}
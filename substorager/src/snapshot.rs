@@ -0,0 +1,363 @@
+//! Storage snapshot.
+//!
+//! A snapshot is a stream of the key/value pairs of a state at a block, prefixed with a
+//! [`SnapshotHeader`].
+//!
+//! Layout, all the integers are little endian:
+//! - magic `b"SNAP"`, format version `u8`
+//! - header, chain name `u32` length prefixed, block number `u64`, block hash `[u8; 32]`, state
+//!   version `u8`
+//! - records until the end of the stream, each record is a tag `u8`, `0` for the top trie and `1`
+//!   for the default child trie followed by the `u32` length prefixed child storage key, then the
+//!   `u32` length prefixed key and value
+
+// std
+use std::{
+	collections::BTreeMap,
+	io::{ErrorKind, Read, Write},
+};
+// substrate-minimal
+use crate::{
+	trie::{MemoryState, MemoryTrie, StateVersion},
+	ChildInfo, Error, Result,
+};
+
+const MAGIC: &[u8; 4] = b"SNAP";
+const FORMAT_VERSION: u8 = 1;
+const TOP_TAG: u8 = 0;
+const DEFAULT_CHILD_TAG: u8 = 1;
+
+/// Snapshot header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotHeader {
+	/// Chain name.
+	pub chain: String,
+	/// Block number.
+	pub block_number: u64,
+	/// Block hash.
+	pub block_hash: [u8; 32],
+	/// State version.
+	pub state_version: StateVersion,
+}
+
+/// Snapshot entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotEntry {
+	/// Child trie of the entry, [`None`] if the entry is in the top trie.
+	pub child: Option<ChildInfo>,
+	/// Storage key.
+	pub key: Vec<u8>,
+	/// Storage value.
+	pub value: Vec<u8>,
+}
+
+/// Streaming snapshot writer.
+#[derive(Debug)]
+pub struct SnapshotWriter<W> {
+	inner: W,
+}
+impl<W> SnapshotWriter<W>
+where
+	W: Write,
+{
+	/// Create a new [`SnapshotWriter`] and write the header.
+	pub fn new(mut inner: W, header: &SnapshotHeader) -> Result<Self> {
+		inner.write_all(MAGIC)?;
+		inner.write_all(&[FORMAT_VERSION])?;
+		write_bytes(&mut inner, header.chain.as_bytes())?;
+		inner.write_all(&header.block_number.to_le_bytes())?;
+		inner.write_all(&header.block_hash)?;
+		inner.write_all(&[header.state_version as u8])?;
+
+		Ok(Self { inner })
+	}
+
+	/// Write a key/value pair of the top trie.
+	pub fn write(&mut self, key: &[u8], value: &[u8]) -> Result<()> {
+		self.inner.write_all(&[TOP_TAG])?;
+		write_bytes(&mut self.inner, key)?;
+		write_bytes(&mut self.inner, value)
+	}
+
+	/// Write a key/value pair of the child trie.
+	pub fn write_child(&mut self, child_info: &ChildInfo, key: &[u8], value: &[u8]) -> Result<()> {
+		self.inner.write_all(&[DEFAULT_CHILD_TAG])?;
+		write_bytes(&mut self.inner, child_info.storage_key())?;
+		write_bytes(&mut self.inner, key)?;
+		write_bytes(&mut self.inner, value)
+	}
+
+	/// Write all the key/value pairs of the state.
+	pub fn write_state(&mut self, state: &MemoryState) -> Result<()> {
+		for (k, v) in state.top().iter() {
+			self.write(k, v)?;
+		}
+		for (i, c) in state.children() {
+			for (k, v) in c.iter() {
+				self.write_child(i, k, v)?;
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Flush and return the inner writer.
+	pub fn finish(mut self) -> Result<W> {
+		self.inner.flush()?;
+
+		Ok(self.inner)
+	}
+}
+
+/// Streaming snapshot reader.
+///
+/// Iterate the [`SnapshotEntry`]s after the header has been read.
+#[derive(Debug)]
+pub struct SnapshotReader<R> {
+	inner: R,
+	header: SnapshotHeader,
+	done: bool,
+}
+impl<R> SnapshotReader<R>
+where
+	R: Read,
+{
+	/// Create a new [`SnapshotReader`] and read the header.
+	pub fn new(mut inner: R) -> Result<Self> {
+		let mut magic = [0; 5];
+
+		inner.read_exact(&mut magic)?;
+
+		if &magic[..4] != MAGIC {
+			Err(Error::InvalidSnapshot("invalid magic"))?;
+		}
+		if magic[4] != FORMAT_VERSION {
+			Err(Error::InvalidSnapshot("unsupported format version"))?;
+		}
+
+		let chain = String::from_utf8(read_bytes(&mut inner)?)
+			.map_err(|_| Error::InvalidSnapshot("invalid chain name"))?;
+		let block_number = u64::from_le_bytes(read_array(&mut inner)?);
+		let block_hash = read_array(&mut inner)?;
+		let state_version = StateVersion::try_from(read_array::<1>(&mut inner)?[0])?;
+
+		Ok(Self {
+			inner,
+			header: SnapshotHeader { chain, block_number, block_hash, state_version },
+			done: false,
+		})
+	}
+
+	/// Get the header.
+	pub fn header(&self) -> &SnapshotHeader {
+		&self.header
+	}
+
+	/// Read all the remaining entries into a [`MemoryState`].
+	pub fn into_state(self) -> Result<MemoryState> {
+		let mut state = MemoryState::new(self.header.state_version);
+
+		for e in self {
+			let e = e?;
+
+			match e.child {
+				None => state.top_mut().insert(e.key, e.value),
+				Some(i) => state.child_mut(i).insert(e.key, e.value),
+			};
+		}
+
+		Ok(state)
+	}
+
+	fn read_entry(&mut self) -> Result<Option<SnapshotEntry>> {
+		let mut tag = [0];
+
+		loop {
+			match self.inner.read(&mut tag) {
+				Ok(0) => return Ok(None),
+				Ok(_) => break,
+				Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+				Err(e) => Err(e)?,
+			}
+		}
+
+		let child = match tag[0] {
+			TOP_TAG => None,
+			DEFAULT_CHILD_TAG => Some(ChildInfo::new_default(read_bytes(&mut self.inner)?)),
+			_ => Err(Error::InvalidSnapshot("unknown record tag"))?,
+		};
+		let key = read_bytes(&mut self.inner)?;
+		let value = read_bytes(&mut self.inner)?;
+
+		Ok(Some(SnapshotEntry { child, key, value }))
+	}
+}
+impl<R> Iterator for SnapshotReader<R>
+where
+	R: Read,
+{
+	type Item = Result<SnapshotEntry>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done {
+			return None;
+		}
+
+		let entry = self.read_entry().transpose();
+
+		self.done = !matches!(entry, Some(Ok(_)));
+
+		entry
+	}
+}
+
+/// Change of a storage value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Change {
+	/// The key was added with the value.
+	Added(Vec<u8>),
+	/// The key was removed, with the previous value.
+	Removed(Vec<u8>),
+	/// The value was changed.
+	Changed {
+		/// Previous value.
+		old: Vec<u8>,
+		/// Current value.
+		new: Vec<u8>,
+	},
+}
+
+/// Changed key of the [`StateDiff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DiffEntry {
+	/// Child trie of the key, [`None`] if the key is in the top trie.
+	pub child: Option<ChildInfo>,
+	/// Storage key.
+	pub key: Vec<u8>,
+	/// Change of the value.
+	pub change: Change,
+}
+
+/// Difference between two states.
+///
+/// The top trie entries come first, then the child trie entries, both in the key order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateDiff(pub Vec<DiffEntry>);
+impl StateDiff {
+	/// Compare the two states.
+	pub fn new(old: &MemoryState, new: &MemoryState) -> Self {
+		let mut entries = Vec::new();
+		let empty = MemoryTrie::default();
+		let mut children = old.children().map(|(i, _)| i).collect::<Vec<_>>();
+
+		children.extend(new.children().map(|(i, _)| i));
+		children.sort();
+		children.dedup();
+
+		diff_trie(None, old.top(), new.top(), &mut entries);
+
+		for i in children {
+			diff_trie(
+				Some(i),
+				old.child(i).unwrap_or(&empty),
+				new.child(i).unwrap_or(&empty),
+				&mut entries,
+			);
+		}
+
+		Self(entries)
+	}
+
+	/// Check if the states are identical.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Group the entries by the key which `f` returns, such as the pallet and the item.
+	pub fn group_by<G, F>(&self, mut f: F) -> BTreeMap<G, Vec<&DiffEntry>>
+	where
+		G: Ord,
+		F: FnMut(&DiffEntry) -> G,
+	{
+		self.0.iter().fold(BTreeMap::new(), |mut groups, e| {
+			groups.entry(f(e)).or_insert_with(Vec::new).push(e);
+
+			groups
+		})
+	}
+}
+
+fn diff_trie(
+	child: Option<&ChildInfo>,
+	old: &MemoryTrie,
+	new: &MemoryTrie,
+	entries: &mut Vec<DiffEntry>,
+) {
+	let mut old = old.iter().peekable();
+	let mut new = new.iter().peekable();
+
+	loop {
+		let (key, change) = match (old.peek(), new.peek()) {
+			(None, None) => break,
+			(Some((o, _)), Some((n, _))) if o == n => {
+				let ((k, o), (_, n)) = (old.next().unwrap(), new.next().unwrap());
+
+				if o == n {
+					continue;
+				}
+
+				(k, Change::Changed { old: o.to_vec(), new: n.to_vec() })
+			},
+			(Some((o, _)), Some((n, _))) if o > n => {
+				let (k, v) = new.next().unwrap();
+
+				(k, Change::Added(v.to_vec()))
+			},
+			(None, Some(_)) => {
+				let (k, v) = new.next().unwrap();
+
+				(k, Change::Added(v.to_vec()))
+			},
+			_ => {
+				let (k, v) = old.next().unwrap();
+
+				(k, Change::Removed(v.to_vec()))
+			},
+		};
+
+		entries.push(DiffEntry { child: child.cloned(), key: key.to_vec(), change });
+	}
+}
+
+fn write_bytes<W>(w: &mut W, bytes: &[u8]) -> Result<()>
+where
+	W: Write,
+{
+	let len = u32::try_from(bytes.len()).map_err(|_| Error::InvalidSnapshot("record too long"))?;
+
+	w.write_all(&len.to_le_bytes())?;
+	w.write_all(bytes)?;
+
+	Ok(())
+}
+
+fn read_array<const N: usize>(r: &mut impl Read) -> Result<[u8; N]> {
+	let mut a = [0; N];
+
+	r.read_exact(&mut a)?;
+
+	Ok(a)
+}
+
+fn read_bytes(r: &mut impl Read) -> Result<Vec<u8>> {
+	let len = u32::from_le_bytes(read_array(r)?) as usize;
+	let mut bytes = Vec::new();
+
+	r.take(len as u64).read_to_end(&mut bytes)?;
+
+	if bytes.len() != len {
+		Err(Error::InvalidSnapshot("unexpected end of stream"))?;
+	}
+
+	Ok(bytes)
+}
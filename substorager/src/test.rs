@@ -11,3 +11,725 @@ fn storage_key_should_work() {
 		]
 	);
 }
+
+#[cfg(feature = "codec")]
+const ALICE: [u8; 32] = [
+	212, 53, 147, 199, 21, 253, 211, 28, 97, 20, 26, 189, 4, 169, 159, 214, 130, 44, 133, 88, 133,
+	76, 205, 227, 154, 86, 132, 231, 165, 109, 162, 125,
+];
+
+#[cfg(feature = "codec")]
+#[test]
+fn storage_map_key_should_work() {
+	assert_eq!(
+		storage_map_key("System", "Account", StorageHasher::Blake2_128Concat, ALICE).to_string(),
+		"0x26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9de1e86a9a8c739864cf3cc5ec2bea59fd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"
+	);
+	assert_eq!(
+		storage_map_key("System", "Account", StorageHasher::Blake2_128Concat, ALICE).0,
+		storage_n_map_key("System", "Account", [(StorageHasher::Blake2_128Concat, ALICE)]).0
+	);
+}
+
+#[cfg(feature = "codec")]
+#[test]
+fn storage_double_map_key_should_work() {
+	let k = storage_double_map_key(
+		"Staking",
+		"ErasStakers",
+		(StorageHasher::Twox64Concat, 0_u32),
+		(StorageHasher::Twox64Concat, ALICE),
+	)
+	.to_string();
+
+	assert_eq!(
+		&k[..90],
+		"0x5f3e4907f716ac89b6347d15ececedca8bde0a0ea8864605e3b68ed9cb2da01bb4def25cfda6ef3a00000000"
+	);
+	assert_eq!(
+		k,
+		storage_n_map_key(
+			"Staking",
+			"ErasStakers",
+			[(StorageHasher::Twox64Concat, &[0; 4][..]), (StorageHasher::Twox64Concat, &ALICE)]
+		)
+		.to_string()
+	);
+}
+
+#[cfg(feature = "codec")]
+#[test]
+fn storage_encoded_n_map_key_should_work() {
+	let era = 1_234_u32;
+	let page = 1_u32;
+
+	assert_eq!(
+		storage_encoded_n_map_key(
+			"Staking",
+			"ErasStakersPaged",
+			(
+				(StorageHasher::Twox64Concat, era),
+				(StorageHasher::Twox64Concat, ALICE),
+				(StorageHasher::Twox64Concat, page)
+			)
+		)
+		.0,
+		storage_n_map_key(
+			"Staking",
+			"ErasStakersPaged",
+			[
+				(StorageHasher::Twox64Concat, &era.to_le_bytes()[..]),
+				(StorageHasher::Twox64Concat, &ALICE),
+				(StorageHasher::Twox64Concat, &page.to_le_bytes())
+			]
+		)
+		.0
+	);
+	// Compact and variable length types are encoded as well.
+	assert_eq!(
+		storage_encoded_n_map_key(
+			"Pallet",
+			"Item",
+			((StorageHasher::Identity, "abc"), (StorageHasher::Identity, vec![1_u8]))
+		)
+		.0[32..],
+		[12, b'a', b'b', b'c', 4, 1]
+	);
+}
+
+#[test]
+fn split_map_keys_should_work() {
+	let era = 1_234_u32.to_le_bytes();
+	let account = [1; 32];
+	let hashed_keys = [
+		StorageHasher::Twox64Concat.hash(era).0,
+		StorageHasher::Blake2_128.hash(account).0,
+		StorageHasher::Identity.hash([0, 1, 2]).0,
+	]
+	.concat();
+	let hashers = [
+		(StorageHasher::Twox64Concat, Some(4)),
+		(StorageHasher::Blake2_128, Some(32)),
+		(StorageHasher::Identity, None),
+	];
+
+	assert_eq!(
+		split_map_keys(&hashed_keys, &hashers).unwrap(),
+		[
+			StorageKeyPart {
+				hasher: StorageHasher::Twox64Concat,
+				hash: subhasher::twox64(era).to_vec(),
+				key: Some(era.to_vec()),
+			},
+			StorageKeyPart {
+				hasher: StorageHasher::Blake2_128,
+				hash: subhasher::blake2_128(account).to_vec(),
+				key: None,
+			},
+			StorageKeyPart {
+				hasher: StorageHasher::Identity,
+				hash: Vec::new(),
+				key: Some(vec![0, 1, 2])
+			},
+		]
+	);
+	// Unknown size in the middle.
+	assert!(split_map_keys(
+		&hashed_keys,
+		&[
+			(StorageHasher::Twox64Concat, None),
+			(StorageHasher::Blake2_128, Some(32)),
+			(StorageHasher::Identity, None)
+		]
+	)
+	.is_none());
+	// Too short.
+	assert!(split_map_keys(&hashed_keys[..20], &hashers).is_none());
+	// Too long.
+	assert!(split_map_keys(&hashed_keys, &hashers[..2]).is_none());
+}
+
+#[test]
+fn storage_map_prefix_should_work() {
+	let prefix = storage_map_prefix("System", "Account");
+
+	assert_eq!(prefix.0, storage_value_key("System", "Account").0);
+	assert!(storage_n_map_key("System", "Account", [(StorageHasher::Blake2_128Concat, [0; 32])])
+		.starts_with(&prefix));
+}
+
+#[cfg(feature = "codec")]
+#[test]
+fn storage_partial_key_prefix_should_work() {
+	let era_prefix =
+		storage_double_map_prefix("Staking", "ErasStakers", StorageHasher::Twox64Concat, 0_u32);
+
+	assert_eq!(
+		era_prefix.to_string(),
+		"0x5f3e4907f716ac89b6347d15ececedca8bde0a0ea8864605e3b68ed9cb2da01bb4def25cfda6ef3a00000000"
+	);
+	assert!(storage_double_map_key(
+		"Staking",
+		"ErasStakers",
+		(StorageHasher::Twox64Concat, 0_u32),
+		(StorageHasher::Twox64Concat, ALICE),
+	)
+	.starts_with(&era_prefix));
+
+	let era_prefix = storage_encoded_n_map_key(
+		"Staking",
+		"ErasStakersPaged",
+		((StorageHasher::Twox64Concat, 0_u32),),
+	);
+	let page_prefix = storage_encoded_n_map_key(
+		"Staking",
+		"ErasStakersPaged",
+		((StorageHasher::Twox64Concat, 0_u32), (StorageHasher::Twox64Concat, ALICE)),
+	);
+
+	assert!(page_prefix.starts_with(&era_prefix));
+	assert!(storage_encoded_n_map_key(
+		"Staking",
+		"ErasStakersPaged",
+		(
+			(StorageHasher::Twox64Concat, 0_u32),
+			(StorageHasher::Twox64Concat, ALICE),
+			(StorageHasher::Twox64Concat, 1_u32)
+		),
+	)
+	.starts_with(&page_prefix));
+}
+
+#[test]
+fn well_known_keys_should_work() {
+	assert_eq!(well_known_keys::CODE, b":code");
+	assert_eq!(
+		well_known_keys::storage_version_key("System").to_string(),
+		"0x26aa394eea5630e07c48ae0c9558cef74e7b9012096b41c4eb3aaf947f6ea429"
+	);
+	assert!(well_known_keys::is_child_storage_key(b":child_storage:default:crowdloan"));
+	assert!(!well_known_keys::is_child_storage_key(well_known_keys::CODE));
+}
+
+#[test]
+fn child_info_should_work() {
+	let child_info = ChildInfo::new_default(b"unique_id");
+
+	assert_eq!(child_info.storage_key(), b"unique_id");
+	assert_eq!(child_info.keyspace(), b"unique_id");
+	assert_eq!(child_info.child_type(), ChildType::ParentKeyId);
+	assert_eq!(child_info.prefixed_storage_key().0, b":child_storage:default:unique_id");
+	assert_eq!(ChildInfo::from_prefixed_key(child_info.prefixed_storage_key()), Some(child_info));
+	assert_eq!(ChildInfo::from_prefixed_key(b":child_storage:unknown:unique_id"), None);
+	assert_eq!(ChildInfo::from_prefixed_key(b":code"), None);
+
+	let crowdloan =
+		ChildInfo::new_default_hashed([&b"crowdloan"[..], &0_u32.to_le_bytes()].concat());
+
+	assert_eq!(crowdloan.storage_key(), subhasher::blake2_256(b"crowdloan\0\0\0\0"));
+}
+
+#[cfg(feature = "codec")]
+#[test]
+fn child_info_codec_should_work() {
+	let child_info = ChildInfo::new_default([1, 2]);
+	let encoded = child_info.encode();
+
+	// Variant index, then the length prefixed unique ID.
+	assert_eq!(encoded, [0, 8, 1, 2]);
+	assert_eq!(ChildInfo::decode(&mut &*encoded).unwrap(), child_info);
+}
+
+const TRIE_PROOF_KEYS: [&[u8]; 5] = [b"dog", b"doge", b"dot", b"house", &[b'k', 3]];
+// Generated by `sp_state_machine::prove_read`.
+const TRIE_V0_ROOT: &str = "0x4d0cc4b621203f9b629cdcfce2203a0b524771bfbfc52137cbf2f4f5abc44430";
+const TRIE_V0_PROOF: [&str; 7] = [
+	"0x4105a0aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+	"0x8100ff003040280000000000000000000030402801010101010101010101304028020202020202020202023040280303030303030303030330402804040404040404040404304028050505050505050505053040280606060606060606060630402807070707070707070707",
+	"0x8106100980112d57a82b104b004af302efee4d972629a193a3ed846695804dd7e413acce8a809b624a6274916f15b780862dca6bd111f7380a241dae948db9e5b7aef948b5e28004d1c03c4ff0d98474dcd01a582c5c0d66285e6ecd185085ee19946b53ec937c",
+	"0x8306f7240030447365207374616c6c696f6e80f2ac0653c2446f72f3bf90b828c1570cc58af2ea6c75e6bdb6019f012f67ab14",
+	"0xc10740001470757070798007b47fd1cc58533facbcbd0d8346596f418ff50ed19bd1b37a8f32acba251586",
+	"0xc26f4000107665726280c444ec281c5e8d1427bc43b1ce1ddb501e6ee6794df5de7bc9f1eec2770585b7",
+	"0xc47365800084bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb80caf2625e9addeaad4e6196c0bcf3f2ad4e34b623a2004d70fc9b19a0759a2a96",
+];
+const TRIE_V1_ROOT: &str = "0x85f10ad35dc552ea5a7abe36a2faabebacb74c31e852f748b54372ad06c5f983";
+const TRIE_V1_PROOF: [&str; 9] = [
+	"0x14736580002b3b015d064b13a08123274cd236ab3a9596fe5368ae97d2a72b7ba5f57fb96680caf2625e9addeaad4e6196c0bcf3f2ad4e34b623a2004d70fc9b19a0759a2a96",
+	"0x2105508a8fdde50b38f20847f0b8c05eb5bb7f4f5ff86aad1987010846e16046e940",
+	"0x8100ff003040280000000000000000000030402801010101010101010101304028020202020202020202023040280303030303030303030330402804040404040404040404304028050505050505050505053040280606060606060606060630402807070707070707070707",
+	"0x8106100980b72121b8ff6d08cf2784e919849e352a244999c793fdff6d667369f63355272e80f3a4353d9c6c72886ed6bb2da751d2436bdafacfdeba07f20c82fb6ebd94f1858004d1c03c4ff0d98474dcd01a582c5c0d66285e6ecd185085ee19946b53ec937c",
+	"0x8306f7240030447365207374616c6c696f6e80ce07681420d15431a44a890c04a574bae86ece35a7b70cdaf053d8b2d812295e",
+	"0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+	"0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+	"0xc10740001470757070798083274d2395bc06699fbf449a5f2a0909d2125fb9193815c69e504ef5724b2f4c",
+	"0xc26f40001076657262803bdd132fbbda0ddad0229424a5bc5f7cc2f386dfac3167aa265f44c75778b1c4",
+];
+
+fn trie_proof(proof: &[&str]) -> Vec<Vec<u8>> {
+	proof.iter().map(array_bytes::hex2bytes_unchecked).collect()
+}
+
+#[test]
+fn verify_read_proof_should_work() {
+	let expected = vec![
+		Some(b"puppy".to_vec()),
+		Some(vec![0xaa; 40]),
+		None,
+		Some(vec![0xbb; 33]),
+		Some(vec![3; 10]),
+	];
+
+	for (root, proof) in [(TRIE_V0_ROOT, &TRIE_V0_PROOF[..]), (TRIE_V1_ROOT, &TRIE_V1_PROOF[..])] {
+		let root = array_bytes::hex2array_unchecked(root);
+		let mut proof = trie_proof(proof);
+
+		assert_eq!(trie::verify_read_proof(&root, &proof, &TRIE_PROOF_KEYS).unwrap(), expected);
+		// Keys which are not covered by the proof.
+		assert!(matches!(
+			trie::verify_read_proof(&root, &proof, &[b"houses"]),
+			Err(Error::IncompleteTrieProof(_))
+		));
+		// Wrong root.
+		assert!(matches!(
+			trie::verify_read_proof(&[0; 32], &proof, &TRIE_PROOF_KEYS),
+			Err(Error::IncompleteTrieProof(_))
+		));
+
+		// Tampered node.
+		let node = proof.iter_mut().find(|n| n.windows(5).any(|w| w == b"puppy")).unwrap();
+		let i = node.windows(5).position(|w| w == b"puppy").unwrap();
+
+		node[i] = b'g';
+
+		assert!(matches!(
+			trie::verify_read_proof(&root, &proof, &[b"dog"]),
+			Err(Error::IncompleteTrieProof(_))
+		));
+	}
+
+	// Invalid node.
+	let node = vec![0b0000_1000];
+
+	assert!(matches!(
+		trie::verify_read_proof(&subhasher::blake2_256(&node), &[node], &[b"dog"]),
+		Err(Error::InvalidTrieNode(_))
+	));
+	// Empty trie.
+	let node = vec![0];
+
+	assert_eq!(
+		trie::verify_read_proof(&subhasher::blake2_256(&node), &[node], &[b"dog"]).unwrap(),
+		[None]
+	);
+}
+
+#[test]
+fn verify_child_read_proof_should_work() {
+	let child_info = ChildInfo::new_default(b"child1");
+	let keys: [&[u8]; 2] = [b"alfa", b"charlie"];
+	let expected = vec![Some(vec![1; 40]), None];
+	// Generated by `sp_state_machine::prove_child_read`.
+	let v0_root = "0x8f85b1875ce6b10452b99d441b53d11204ad0c3212ee660c86b734833e585b74";
+	let v0_proof = [
+		"0x466c6661a001010101010101010101010101010101010101010101010101010101010101010101010101010101",
+		"0x790a6368696c645f73746f726167653a64656661756c743a6368696c643180e630c114436e0d193e8fa05bf0778b8b979439593b01ff41b26e356696c00ef6",
+		"0x80480080227689c2b2711503d1a8f7a3bbb2689f639ee3e3cb4ca8e400496ded50fb77c6807b53c2bdf9ebe9ff8e2b2afc633d3b249fe430372c94487cb2c6f3de396a5c31",
+		"0x8106060080f856fa11c7319fece6cb0c8d4a888b5b6fe43375a9fce0c3235d1e3afed9f4251c487261766f0432",
+	];
+	let v1_root = "0xa5866282825cb1677e2c78b93ba4e580805443a60cd8aa6b25a08bb718b3c861";
+	let v1_proof = [
+		"0x01010101010101010101010101010101010101010101010101010101010101010101010101010101",
+		"0x266c66610c48310385d9d6d7071a4730660558f89f82e988475988a78dedd521c7a5ba06",
+		"0x790a6368696c645f73746f726167653a64656661756c743a6368696c64318047f4fa31887b1ff8927fe0bd4565d426295a8a55e5af30101e1f01ab70fad33b",
+		"0x80480080c133d01570bb571515927f84d8fc7a0bc6136eec75f7e5fd0213506aa90019ec80d90db1f9c513728a175facb559485c64c6b649bfa342ef7f4461dbdff2c36c98",
+		"0x8106060080e1383bf66d1a86779f9555901f31f3e5813a739a0255552deb61d53b16a667491c487261766f0432",
+	];
+
+	for (root, proof) in [(v0_root, &v0_proof[..]), (v1_root, &v1_proof[..])] {
+		let root = array_bytes::hex2array_unchecked(root);
+		let proof = trie_proof(proof);
+
+		assert_eq!(
+			trie::verify_child_read_proof(&root, &proof, &child_info, &keys).unwrap(),
+			expected
+		);
+	}
+
+	// The child trie doesn't exist.
+	assert_eq!(
+		trie::verify_child_read_proof(
+			&array_bytes::hex2array_unchecked(TRIE_V0_ROOT),
+			&trie_proof(&TRIE_V0_PROOF),
+			&ChildInfo::new_default(b"child2"),
+			&keys
+		)
+		.unwrap(),
+		[None, None]
+	);
+}
+
+fn trie_state(version: trie::StateVersion) -> trie::MemoryState {
+	let mut state = trie::MemoryState::new(version);
+
+	state.top_mut().extend([
+		(b"do".to_vec(), b"verb".to_vec()),
+		(b"dog".to_vec(), b"puppy".to_vec()),
+		(b"doge".to_vec(), vec![0xaa; 40]),
+		(b"horse".to_vec(), b"stallion".to_vec()),
+		(b"house".to_vec(), vec![0xbb; 33]),
+		(b"houses".to_vec(), vec![0xcc; 32]),
+	]);
+	state.top_mut().extend((0..8).map(|i| (vec![b'k', i], vec![i; 10])));
+
+	state
+}
+
+#[test]
+fn memory_trie_root_should_work() {
+	// Generated by `sp_state_machine::InMemoryBackend`.
+	for (version, root, big_root) in [
+		(
+			trie::StateVersion::V0,
+			TRIE_V0_ROOT,
+			"0xd7499e41e6b171165ac8752142c296fe7db492138b58753180d85229f0fed1c4",
+		),
+		(
+			trie::StateVersion::V1,
+			TRIE_V1_ROOT,
+			"0x03b618448a041189235ed6c7dcff0e97a8d6a0779fdad8bc18cd90c5da31e21e",
+		),
+	] {
+		assert_eq!(
+			trie::MemoryTrie::new(version).root(),
+			array_bytes::hex2array_unchecked::<_, 32>(
+				"0x03170a2e7597b7b7e3d84c05391d139a62b157e78786d8c082f29dcf4c111314"
+			)
+		);
+		assert_eq!(trie_state(version).root(), array_bytes::hex2array_unchecked::<_, 32>(root));
+
+		// Variable length keys, long partial keys and empty values.
+		let mut trie = trie::MemoryTrie::new(version);
+
+		trie.extend((0_u32..500).map(|i| {
+			let k = subhasher::blake2_256(i.to_le_bytes());
+
+			(k[..(i % 32) as usize + 1].to_vec(), vec![i as u8; (i % 70) as usize])
+		}));
+
+		assert_eq!(trie.len(), 500);
+		assert_eq!(trie.root(), array_bytes::hex2array_unchecked::<_, 32>(big_root));
+
+		let keys = trie.iter().step_by(7).map(|(k, _)| k.to_vec()).collect::<Vec<_>>();
+		let values = keys.iter().map(|k| trie.get(k).map(<[u8]>::to_vec)).collect::<Vec<_>>();
+
+		assert_eq!(
+			trie::verify_read_proof(&trie.root(), &trie.prove(&keys), &keys).unwrap(),
+			values
+		);
+	}
+}
+
+#[test]
+fn memory_state_should_work() {
+	let child_info = ChildInfo::new_default(b"child1");
+	// Generated by `sp_state_machine::{prove_read, prove_child_read}`.
+	let v0_child_proof = [
+		"0x466c6661a001010101010101010101010101010101010101010101010101010101010101010101010101010101",
+		"0x790a6368696c645f73746f726167653a64656661756c743a6368696c643180e630c114436e0d193e8fa05bf0778b8b979439593b01ff41b26e356696c00ef6",
+		"0x80480080227689c2b2711503d1a8f7a3bbb2689f639ee3e3cb4ca8e400496ded50fb77c6807b53c2bdf9ebe9ff8e2b2afc633d3b249fe430372c94487cb2c6f3de396a5c31",
+		"0x8106060080f856fa11c7319fece6cb0c8d4a888b5b6fe43375a9fce0c3235d1e3afed9f4251c487261766f0432",
+	];
+	let v1_child_proof = [
+		"0x01010101010101010101010101010101010101010101010101010101010101010101010101010101",
+		"0x266c66610c48310385d9d6d7071a4730660558f89f82e988475988a78dedd521c7a5ba06",
+		"0x790a6368696c645f73746f726167653a64656661756c743a6368696c64318047f4fa31887b1ff8927fe0bd4565d426295a8a55e5af30101e1f01ab70fad33b",
+		"0x80480080c133d01570bb571515927f84d8fc7a0bc6136eec75f7e5fd0213506aa90019ec80d90db1f9c513728a175facb559485c64c6b649bfa342ef7f4461dbdff2c36c98",
+		"0x8106060080e1383bf66d1a86779f9555901f31f3e5813a739a0255552deb61d53b16a667491c487261766f0432",
+	];
+	let v1_proof = [
+		"0x14736580002b3b015d064b13a08123274cd236ab3a9596fe5368ae97d2a72b7ba5f57fb96680caf2625e9addeaad4e6196c0bcf3f2ad4e34b623a2004d70fc9b19a0759a2a96",
+		"0x2105508a8fdde50b38f20847f0b8c05eb5bb7f4f5ff86aad1987010846e16046e940",
+		"0x80100980b72121b8ff6d08cf2784e919849e352a244999c793fdff6d667369f63355272e80f3a4353d9c6c72886ed6bb2da751d2436bdafacfdeba07f20c82fb6ebd94f1858004d1c03c4ff0d98474dcd01a582c5c0d66285e6ecd185085ee19946b53ec937c",
+		"0x80480080c133d01570bb571515927f84d8fc7a0bc6136eec75f7e5fd0213506aa90019ec80d90db1f9c513728a175facb559485c64c6b649bfa342ef7f4461dbdff2c36c98",
+		"0x8100ff003040280000000000000000000030402801010101010101010101304028020202020202020202023040280303030303030303030330402804040404040404040404304028050505050505050505053040280606060606060606060630402807070707070707070707",
+		"0x8306f7240030447365207374616c6c696f6e80ce07681420d15431a44a890c04a574bae86ece35a7b70cdaf053d8b2d812295e",
+		"0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+		"0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb",
+		"0xc10740001470757070798083274d2395bc06699fbf449a5f2a0909d2125fb9193815c69e504ef5724b2f4c",
+		"0xc26f40001076657262803bdd132fbbda0ddad0229424a5bc5f7cc2f386dfac3167aa265f44c75778b1c4",
+	];
+
+	for (version, root, child_proof) in [
+		(
+			trie::StateVersion::V0,
+			"0x8f85b1875ce6b10452b99d441b53d11204ad0c3212ee660c86b734833e585b74",
+			&v0_child_proof[..],
+		),
+		(
+			trie::StateVersion::V1,
+			"0xa5866282825cb1677e2c78b93ba4e580805443a60cd8aa6b25a08bb718b3c861",
+			&v1_child_proof[..],
+		),
+	] {
+		let mut state = trie_state(version);
+		let empty_root = state.root();
+
+		// Empty child tries are not stored.
+		state.child_mut(ChildInfo::new_default(b"child2"));
+
+		assert_eq!(state.root(), empty_root);
+
+		state
+			.child_mut(child_info.clone())
+			.extend([(b"alfa".to_vec(), vec![1; 40]), (b"bravo".to_vec(), b"2".to_vec())]);
+
+		let root = array_bytes::hex2array_unchecked(root);
+
+		assert_eq!(state.root(), root);
+		assert_eq!(
+			state.prove_child_read(&child_info, &[&b"alfa"[..], b"charlie"]),
+			trie_proof(child_proof)
+		);
+
+		let proof = state.prove_read(&TRIE_PROOF_KEYS);
+
+		if version == trie::StateVersion::V1 {
+			assert_eq!(proof, trie_proof(&v1_proof));
+		}
+
+		assert_eq!(
+			trie::verify_read_proof(&root, &proof, &TRIE_PROOF_KEYS).unwrap(),
+			TRIE_PROOF_KEYS
+				.iter()
+				.map(|k| state.top().get(k).map(<[u8]>::to_vec))
+				.collect::<Vec<_>>()
+		);
+	}
+
+	assert_eq!(trie::StateVersion::try_from(1).unwrap(), trie::StateVersion::V1);
+	assert!(matches!(trie::StateVersion::try_from(2), Err(Error::UnsupportedStateVersion(2))));
+}
+
+#[test]
+fn snapshot_should_work() {
+	let header = snapshot::SnapshotHeader {
+		chain: "Polkadot".into(),
+		block_number: 1_000,
+		block_hash: [1; 32],
+		state_version: trie::StateVersion::V0,
+	};
+	let mut state = trie_state(trie::StateVersion::V0);
+
+	state
+		.child_mut(ChildInfo::new_default(b"child1"))
+		.extend([(b"alfa".to_vec(), vec![1; 40]), (b"bravo".to_vec(), b"2".to_vec())]);
+
+	let mut writer = snapshot::SnapshotWriter::new(Vec::new(), &header).unwrap();
+
+	writer.write_state(&state).unwrap();
+
+	let encoded = writer.finish().unwrap();
+	let reader = snapshot::SnapshotReader::new(&encoded[..]).unwrap();
+
+	assert_eq!(reader.header(), &header);
+	assert_eq!(reader.count(), 16);
+
+	let imported = snapshot::SnapshotReader::new(&encoded[..]).unwrap().into_state().unwrap();
+
+	assert_eq!(imported, state);
+	assert_eq!(
+		imported.root(),
+		array_bytes::hex2array_unchecked::<_, 32>(
+			"0x8f85b1875ce6b10452b99d441b53d11204ad0c3212ee660c86b734833e585b74"
+		)
+	);
+
+	// Invalid magic.
+	assert!(matches!(
+		snapshot::SnapshotReader::new(&b"PANS\x01"[..]),
+		Err(Error::InvalidSnapshot(_))
+	));
+
+	// Truncated record.
+	let mut reader = snapshot::SnapshotReader::new(&encoded[..encoded.len() - 1]).unwrap();
+
+	assert!(reader.by_ref().take(15).all(|e| e.is_ok()));
+	assert!(matches!(reader.next(), Some(Err(Error::InvalidSnapshot(_)))));
+	assert!(reader.next().is_none());
+}
+
+#[test]
+fn state_diff_should_work() {
+	let child_info = ChildInfo::new_default(b"child1");
+	let old = trie_state(trie::StateVersion::V1);
+	let mut new = old.clone();
+
+	assert!(snapshot::StateDiff::new(&old, &new).is_empty());
+
+	new.top_mut().insert(b"dog".to_vec(), b"hound".to_vec());
+	new.top_mut().insert(b"dot".to_vec(), b"point".to_vec());
+	new.top_mut().remove(b"horse");
+	new.child_mut(child_info.clone()).insert(b"alfa".to_vec(), vec![1]);
+
+	let diff = snapshot::StateDiff::new(&old, &new);
+
+	assert_eq!(
+		diff.0,
+		[
+			snapshot::DiffEntry {
+				child: None,
+				key: b"dog".to_vec(),
+				change: snapshot::Change::Changed {
+					old: b"puppy".to_vec(),
+					new: b"hound".to_vec()
+				},
+			},
+			snapshot::DiffEntry {
+				child: None,
+				key: b"dot".to_vec(),
+				change: snapshot::Change::Added(b"point".to_vec()),
+			},
+			snapshot::DiffEntry {
+				child: None,
+				key: b"horse".to_vec(),
+				change: snapshot::Change::Removed(b"stallion".to_vec()),
+			},
+			snapshot::DiffEntry {
+				child: Some(child_info),
+				key: b"alfa".to_vec(),
+				change: snapshot::Change::Added(vec![1]),
+			},
+		]
+	);
+	assert_eq!(
+		diff.group_by(|e| e.child.is_some())
+			.into_iter()
+			.map(|(g, es)| (g, es.len()))
+			.collect::<Vec<_>>(),
+		[(false, 3), (true, 1)]
+	);
+}
+
+#[test]
+fn storage_key_value_type_should_work() {
+	let prefix = storage_map_prefix("System", "Account");
+	let k = StorageHasher::Blake2_128Concat.hash([1_u8; 32]);
+	let k = StorageKey([prefix.0.clone(), k.0].concat());
+
+	assert_eq!(k.to_string().parse::<StorageKey>().unwrap(), k);
+	assert!(matches!("0xzz".parse::<StorageKey>(), Err(Error::ArrayBytes(_))));
+	assert!(k.starts_with(&prefix));
+	assert!(!k.starts_with(storage_map_prefix("System", "Number")));
+	assert_eq!(k.strip_prefix(&prefix).unwrap()[16..], [1_u8; 32]);
+	assert_eq!(k.strip_prefix(storage_map_prefix("System", "Number")), None);
+
+	let keys = [k.clone(), prefix.clone(), k.clone()]
+		.into_iter()
+		.collect::<std::collections::BTreeSet<_>>();
+
+	assert_eq!(keys.into_iter().collect::<Vec<_>>(), [prefix, k]);
+	assert_eq!("0x0102".parse::<StorageData>().unwrap(), StorageData(vec![1, 2]));
+	assert_eq!(Vec::from(StorageData(vec![1, 2])), [1, 2]);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn storage_key_serde_should_work() {
+	let k = storage_value_key("System", "Number");
+	let json = serde_json::json!([[k], StorageData(vec![1, 2])]);
+
+	assert_eq!(
+		json,
+		serde_json::json!([
+			["0x26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac"],
+			"0x0102"
+		])
+	);
+
+	// Such as the result of `state_getKeys` and `state_getStorage`.
+	let (keys, data) =
+		serde_json::from_value::<(Vec<StorageKey>, Option<StorageData>)>(json).unwrap();
+
+	assert_eq!(keys, [k]);
+	assert_eq!(data, Some(StorageData(vec![1, 2])));
+	assert!(serde_json::from_value::<StorageKey>(serde_json::json!("0xzz")).is_err());
+}
+
+#[cfg(feature = "codec")]
+#[test]
+fn storage_data_codec_should_work() {
+	let data = StorageData::encode_from(&(1_u32, true));
+
+	assert_eq!(data.0, [1, 0, 0, 0, 1]);
+	assert_eq!(data.decode::<(u32, bool)>().unwrap(), (1, true));
+	// Trailing bytes are not allowed.
+	assert!(matches!(data.decode::<u32>(), Err(Error::Codec(_))));
+}
+
+#[test]
+fn change_tracker_should_work() {
+	let number = storage_value_key("System", "Number");
+	let account =
+		storage_n_map_key("System", "Account", [(StorageHasher::Blake2_128Concat, [1; 32])]);
+	let mut tracker = ChangeTracker::new();
+
+	tracker.extend([
+		StorageChangeSet {
+			block: "0x01",
+			changes: vec![
+				(number.clone(), Some(StorageData(vec![1]))),
+				(account.clone(), Some(StorageData(vec![0]))),
+			],
+		},
+		StorageChangeSet {
+			block: "0x02",
+			changes: vec![(number.clone(), Some(StorageData(vec![2])))],
+		},
+		StorageChangeSet {
+			block: "0x03",
+			changes: vec![(number.clone(), Some(StorageData(vec![3]))), (account.clone(), None)],
+		},
+	]);
+
+	assert_eq!(tracker.blocks(), ["0x01", "0x02", "0x03"]);
+	assert_eq!(tracker.get(&number), Some(&StorageData(vec![3])));
+	assert_eq!(tracker.get(&account), None);
+	assert_eq!(tracker.iter().count(), 1);
+	assert_eq!(
+		tracker.history(&account),
+		[(&"0x01", Some(&StorageData(vec![0]))), (&"0x03", None)]
+	);
+	assert_eq!(
+		tracker.history_between(&number, &"0x02", &"0x03").unwrap(),
+		[(&"0x02", Some(&StorageData(vec![2]))), (&"0x03", Some(&StorageData(vec![3])))]
+	);
+	assert_eq!(tracker.history_between(&number, &"0x02", &"0x04"), None);
+	assert!(tracker.history(&StorageKey::new()).is_empty());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn storage_change_set_serde_should_work() {
+	// Such as the result of `state_queryStorageAt`.
+	let result = serde_json::json!([{
+		"block": "0xcd3d8e3b7ca6a67c21b5a0d6adbd1cdd2b5e32d5f6c0fd2ae8a4d45a8b2a6b1d",
+		"changes": [
+			["0x26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac", "0x2a000000"],
+			["0x26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9", null]
+		]
+	}]);
+	let change_sets =
+		serde_json::from_value::<Vec<StorageChangeSet<String>>>(result.clone()).unwrap();
+
+	assert_eq!(
+		change_sets,
+		[StorageChangeSet {
+			block: "0xcd3d8e3b7ca6a67c21b5a0d6adbd1cdd2b5e32d5f6c0fd2ae8a4d45a8b2a6b1d".into(),
+			changes: vec![
+				(storage_value_key("System", "Number"), Some(StorageData(vec![42, 0, 0, 0]))),
+				(storage_map_prefix("System", "Account"), None),
+			],
+		}]
+	);
+	assert_eq!(serde_json::to_value(&change_sets).unwrap(), result);
+}
@@ -0,0 +1,305 @@
+//! In-memory trie.
+
+// std
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+// substrate-minimal
+use crate::{
+	trie::{
+		bytes_to_nibbles,
+		node::{self, Child, Value, EMPTY_TRIE},
+		proof, Hash, StateVersion,
+	},
+	ChildInfo, Error,
+};
+
+/// In-memory trie, which computes the root and emits the proofs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryTrie {
+	version: StateVersion,
+	entries: BTreeMap<Vec<u8>, Vec<u8>>,
+}
+impl MemoryTrie {
+	/// Create an empty [`MemoryTrie`] with the state version.
+	pub fn new(version: StateVersion) -> Self {
+		Self { version, entries: BTreeMap::new() }
+	}
+
+	/// Get the state version.
+	pub fn version(&self) -> StateVersion {
+		self.version
+	}
+
+	/// Get the value of the key.
+	pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+		self.entries.get(key).map(Vec::as_slice)
+	}
+
+	/// Insert a key/value pair, return the previous value.
+	pub fn insert(&mut self, key: Vec<u8>, value: Vec<u8>) -> Option<Vec<u8>> {
+		self.entries.insert(key, value)
+	}
+
+	/// Remove the key, return the previous value.
+	pub fn remove(&mut self, key: &[u8]) -> Option<Vec<u8>> {
+		self.entries.remove(key)
+	}
+
+	/// Get the number of the key/value pairs.
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// Check if the trie is empty.
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// Iterate the key/value pairs in the key order.
+	pub fn iter(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+		self.entries.iter().map(|(k, v)| (k.as_slice(), v.as_slice()))
+	}
+
+	/// Compute the root.
+	pub fn root(&self) -> Hash {
+		Builder::new(self.version, None).root(self.iter())
+	}
+
+	/// Generate a read proof of the keys, the encoded nodes are sorted.
+	pub fn prove<K>(&self, keys: &[K]) -> Vec<Vec<u8>>
+	where
+		K: AsRef<[u8]>,
+	{
+		let mut proof = Prover::new(self.version, self.iter());
+
+		keys.iter().for_each(|k| proof.record(k.as_ref()));
+
+		proof.into_nodes()
+	}
+}
+impl Extend<(Vec<u8>, Vec<u8>)> for MemoryTrie {
+	fn extend<I>(&mut self, iter: I)
+	where
+		I: IntoIterator<Item = (Vec<u8>, Vec<u8>)>,
+	{
+		self.entries.extend(iter);
+	}
+}
+
+/// In-memory state, the top trie along with the child tries.
+///
+/// The root of each non-empty child trie is stored in the top trie under its prefixed storage key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MemoryState {
+	top: MemoryTrie,
+	children: BTreeMap<ChildInfo, MemoryTrie>,
+}
+impl MemoryState {
+	/// Create an empty [`MemoryState`] with the state version.
+	pub fn new(version: StateVersion) -> Self {
+		Self { top: MemoryTrie::new(version), children: BTreeMap::new() }
+	}
+
+	/// Get the state version.
+	pub fn version(&self) -> StateVersion {
+		self.top.version
+	}
+
+	/// Get the top trie.
+	pub fn top(&self) -> &MemoryTrie {
+		&self.top
+	}
+
+	/// Get the mutable top trie.
+	pub fn top_mut(&mut self) -> &mut MemoryTrie {
+		&mut self.top
+	}
+
+	/// Get the child trie.
+	pub fn child(&self, child_info: &ChildInfo) -> Option<&MemoryTrie> {
+		self.children.get(child_info)
+	}
+
+	/// Get the mutable child trie, create an empty one if it doesn't exist.
+	pub fn child_mut(&mut self, child_info: ChildInfo) -> &mut MemoryTrie {
+		let version = self.top.version;
+
+		self.children.entry(child_info).or_insert_with(|| MemoryTrie::new(version))
+	}
+
+	/// Iterate the child tries.
+	pub fn children(&self) -> impl Iterator<Item = (&ChildInfo, &MemoryTrie)> {
+		self.children.iter()
+	}
+
+	/// Compute the state root.
+	pub fn root(&self) -> Hash {
+		let child_roots = self.child_roots();
+
+		Builder::new(self.version(), None).root(self.top_entries(&child_roots).into_iter())
+	}
+
+	/// Generate a read proof of the keys in the top trie, the encoded nodes are sorted.
+	pub fn prove_read<K>(&self, keys: &[K]) -> Vec<Vec<u8>>
+	where
+		K: AsRef<[u8]>,
+	{
+		let child_roots = self.child_roots();
+		let mut proof = Prover::new(self.version(), self.top_entries(&child_roots).into_iter());
+
+		keys.iter().for_each(|k| proof.record(k.as_ref()));
+
+		proof.into_nodes()
+	}
+
+	/// Generate a read proof of the keys in the child trie, the encoded nodes are sorted.
+	///
+	/// The proof contains the nodes on the path to the child trie root in the top trie.
+	pub fn prove_child_read<K>(&self, child_info: &ChildInfo, keys: &[K]) -> Vec<Vec<u8>>
+	where
+		K: AsRef<[u8]>,
+	{
+		let child_roots = self.child_roots();
+		let mut proof = Prover::new(self.version(), self.top_entries(&child_roots).into_iter());
+
+		proof.record(&child_info.prefixed_storage_key());
+
+		if let Some(child) = self.children.get(child_info).filter(|c| !c.is_empty()) {
+			let mut child_proof = Prover::new(self.version(), child.iter());
+
+			keys.iter().for_each(|k| child_proof.record(k.as_ref()));
+			proof.nodes.append(&mut child_proof.nodes);
+		}
+
+		proof.into_nodes()
+	}
+
+	fn child_roots(&self) -> Vec<(Vec<u8>, Hash)> {
+		self.children
+			.iter()
+			.filter(|(_, c)| !c.is_empty())
+			.map(|(i, c)| (i.prefixed_storage_key().0, c.root()))
+			.collect()
+	}
+
+	fn top_entries<'a>(
+		&'a self,
+		child_roots: &'a [(Vec<u8>, Hash)],
+	) -> BTreeMap<&'a [u8], &'a [u8]> {
+		self.top
+			.iter()
+			.chain(child_roots.iter().map(|(k, r)| (k.as_slice(), r.as_slice())))
+			.collect()
+	}
+}
+
+struct Prover {
+	root: Hash,
+	db: HashMap<Hash, Vec<u8>>,
+	nodes: BTreeSet<Vec<u8>>,
+}
+impl Prover {
+	fn new<'a, I>(version: StateVersion, entries: I) -> Self
+	where
+		I: Iterator<Item = (&'a [u8], &'a [u8])>,
+	{
+		let mut db = HashMap::new();
+		let root = Builder::new(version, Some(&mut db)).root(entries);
+
+		Self { root, db, nodes: BTreeSet::new() }
+	}
+
+	fn record(&mut self, key: &[u8]) {
+		let Self { root, db, nodes } = self;
+
+		proof::lookup(
+			|h| {
+				let node = db.get(h).ok_or(Error::IncompleteTrieProof(*h))?;
+
+				nodes.insert(node.clone());
+
+				Ok(node.as_slice())
+			},
+			root,
+			key,
+		)
+		.expect("the trie is complete; qed");
+	}
+
+	fn into_nodes(self) -> Vec<Vec<u8>> {
+		self.nodes.into_iter().collect()
+	}
+}
+
+struct Builder<'a> {
+	version: StateVersion,
+	db: Option<&'a mut HashMap<Hash, Vec<u8>>>,
+}
+impl<'a> Builder<'a> {
+	fn new(version: StateVersion, db: Option<&'a mut HashMap<Hash, Vec<u8>>>) -> Self {
+		Self { version, db }
+	}
+
+	fn root<'b, I>(&mut self, entries: I) -> Hash
+	where
+		I: Iterator<Item = (&'b [u8], &'b [u8])>,
+	{
+		let entries = entries.map(|(k, v)| (bytes_to_nibbles(k), v)).collect::<Vec<_>>();
+		let encoded = if entries.is_empty() { vec![EMPTY_TRIE] } else { self.node(&entries, 0) };
+
+		self.hash(encoded)
+	}
+
+	fn hash(&mut self, data: Vec<u8>) -> Hash {
+		let hash = subhasher::blake2_256(&data);
+
+		if let Some(db) = &mut self.db {
+			db.insert(hash, data);
+		}
+
+		hash
+	}
+
+	// Values which are at least 33 bytes long are stored as separate nodes in a `V1` trie.
+	fn value<'b>(&mut self, value: &'b [u8]) -> Value<'b> {
+		match self.version {
+			StateVersion::V1 if value.len() > 32 => Value::Hashed(self.hash(value.to_vec())),
+			_ => Value::Inline(value),
+		}
+	}
+
+	// Encode the node of the sorted entries, which share the same first `depth` nibbles.
+	fn node(&mut self, entries: &[(Vec<u8>, &[u8])], depth: usize) -> Vec<u8> {
+		let (first, last) = (&entries[0], &entries[entries.len() - 1]);
+
+		if entries.len() == 1 {
+			let value = self.value(first.1);
+
+			return node::encode_leaf(&first.0[depth..], value);
+		}
+
+		let common = depth
+			+ first.0[depth..].iter().zip(&last.0[depth..]).take_while(|(a, b)| a == b).count();
+		let (value, entries) = if first.0.len() == common {
+			(Some(self.value(first.1)), &entries[1..])
+		} else {
+			(None, entries)
+		};
+		let mut children = <[Option<Vec<u8>>; 16]>::default();
+
+		for group in entries.chunk_by(|a, b| a.0[common] == b.0[common]) {
+			let child = self.node(group, common + 1);
+
+			children[group[0].0[common] as usize] =
+				Some(if child.len() < 32 { child } else { self.hash(child).to_vec() });
+		}
+
+		let children = children.each_ref().map(|c| {
+			c.as_deref().map(|c| match c.try_into() {
+				Ok(h) => Child::Hash(h),
+				Err(_) => Child::Inline(c),
+			})
+		});
+
+		node::encode_branch(&first.0[depth..common], value, &children)
+	}
+}
@@ -0,0 +1,55 @@
+//! Substrate base-16 modified Merkle Patricia trie.
+//!
+//! Nodes are hashed with `blake2_256`.
+//! Both state versions share the same node codec, values of a `V1` trie which are at least 33
+//! bytes long are stored as separate nodes and referenced by their hashes.
+//!
+//! Substrate reference(s):
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/primitives/trie/src/lib.rs>
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/primitives/state-machine/src/lib.rs>
+
+mod memory;
+pub use memory::*;
+
+mod node;
+
+mod proof;
+pub use proof::*;
+
+// crates.io
+#[cfg(feature = "codec")] use parity_scale_codec::{Decode, Encode};
+// substrate-minimal
+use crate::Error;
+
+/// Trie node hash.
+pub type Hash = [u8; 32];
+
+/// State version, which determines the trie layout.
+///
+/// Substrate reference(s):
+/// - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/primitives/storage/src/lib.rs>
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "codec", derive(Encode, Decode))]
+pub enum StateVersion {
+	/// All the values are stored in the nodes.
+	V0 = 0,
+	/// The values which are at least 33 bytes long are stored as separate nodes.
+	#[default]
+	V1 = 1,
+}
+impl TryFrom<u8> for StateVersion {
+	type Error = Error;
+
+	fn try_from(v: u8) -> Result<Self, Self::Error> {
+		match v {
+			0 => Ok(Self::V0),
+			1 => Ok(Self::V1),
+			v => Err(Error::UnsupportedStateVersion(v)),
+		}
+	}
+}
+
+// Split the bytes into nibbles, high nibble first.
+fn bytes_to_nibbles(bytes: &[u8]) -> Vec<u8> {
+	bytes.iter().flat_map(|b| [b >> 4, b & 0xf]).collect()
+}
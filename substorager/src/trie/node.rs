@@ -0,0 +1,307 @@
+//! Trie node codec.
+//!
+//! Substrate reference(s):
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/primitives/trie/src/node_header.rs>
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/primitives/trie/src/node_codec.rs>
+
+// substrate-minimal
+use crate::{trie::Hash, Error, Result};
+
+pub(crate) const EMPTY_TRIE: u8 = 0;
+const LEAF_PREFIX_MASK: u8 = 0b01 << 6;
+const BRANCH_WITHOUT_VALUE_MASK: u8 = 0b10 << 6;
+const BRANCH_WITH_VALUE_MASK: u8 = 0b11 << 6;
+const ALT_HASHING_LEAF_PREFIX_MASK: u8 = 0b001 << 5;
+const ALT_HASHING_BRANCH_WITH_MASK: u8 = 0b0001 << 4;
+
+/// Value of a node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Value<'a> {
+	/// Value is stored in the node.
+	Inline(&'a [u8]),
+	/// Hash of the value, the value is stored as a separate node.
+	Hashed(Hash),
+}
+
+/// Reference to a child node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Child<'a> {
+	/// Hash of the child node.
+	Hash(Hash),
+	/// Encoded child node, which is shorter than a hash.
+	Inline(&'a [u8]),
+}
+
+/// Decoded trie node.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Node<'a> {
+	Empty,
+	Leaf { partial: Vec<u8>, value: Value<'a> },
+	Branch { partial: Vec<u8>, value: Option<Value<'a>>, children: Box<[Option<Child<'a>>; 16]> },
+}
+impl<'a> Node<'a> {
+	/// Decode a node from its encoding.
+	pub(crate) fn decode(data: &'a [u8]) -> Result<Self> {
+		let mut input = Input(data);
+		let header = input.byte()?;
+		let node = match header {
+			EMPTY_TRIE => Self::Empty,
+			_ if header & BRANCH_WITH_VALUE_MASK == LEAF_PREFIX_MASK => {
+				let partial = input.partial(header, 2)?;
+				let value = Value::Inline(input.compact_bytes()?);
+
+				Self::Leaf { partial, value }
+			},
+			_ if header & BRANCH_WITH_VALUE_MASK == BRANCH_WITHOUT_VALUE_MASK =>
+				input.branch(header, 2, None)?,
+			_ if header & BRANCH_WITH_VALUE_MASK == BRANCH_WITH_VALUE_MASK =>
+				input.branch(header, 2, Some(false))?,
+			_ if header & (0b111 << 5) == ALT_HASHING_LEAF_PREFIX_MASK => {
+				let partial = input.partial(header, 3)?;
+				let value = Value::Hashed(input.hash()?);
+
+				Self::Leaf { partial, value }
+			},
+			_ if header & (0b1111 << 4) == ALT_HASHING_BRANCH_WITH_MASK =>
+				input.branch(header, 4, Some(true))?,
+			_ => return Err(Error::InvalidTrieNode("unknown header")),
+		};
+
+		if input.0.is_empty() {
+			Ok(node)
+		} else {
+			Err(Error::InvalidTrieNode("trailing bytes"))
+		}
+	}
+}
+
+struct Input<'a>(&'a [u8]);
+impl<'a> Input<'a> {
+	fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+		if self.0.len() < len {
+			Err(Error::InvalidTrieNode("unexpected end of data"))?;
+		}
+
+		let (taken, rest) = self.0.split_at(len);
+
+		self.0 = rest;
+
+		Ok(taken)
+	}
+
+	fn byte(&mut self) -> Result<u8> {
+		Ok(self.take(1)?[0])
+	}
+
+	fn hash(&mut self) -> Result<Hash> {
+		Ok(self.take(32)?.try_into().expect("the length has been checked; qed"))
+	}
+
+	fn compact(&mut self) -> Result<u32> {
+		let first = self.byte()?;
+		let v = match first & 0b11 {
+			0b00 => first as u32 >> 2,
+			0b01 => u16::from_le_bytes([first, self.byte()?]) as u32 >> 2,
+			0b10 => {
+				let rest = self.take(3)?;
+
+				u32::from_le_bytes([first, rest[0], rest[1], rest[2]]) >> 2
+			},
+			_ => {
+				let len = (first >> 2) as usize + 4;
+
+				if len != 4 {
+					Err(Error::InvalidTrieNode("compact length out of range"))?;
+				}
+
+				u32::from_le_bytes(
+					self.take(4)?.try_into().expect("the length has been checked; qed"),
+				)
+			},
+		};
+
+		Ok(v)
+	}
+
+	fn compact_bytes(&mut self) -> Result<&'a [u8]> {
+		let len = self.compact()? as usize;
+
+		self.take(len)
+	}
+
+	// Decode the nibble count from the header and the following bytes, then the partial key.
+	fn partial(&mut self, header: u8, prefix_bits: u8) -> Result<Vec<u8>> {
+		let max = 255 >> prefix_bits;
+		let mut len = (header & max) as usize;
+
+		if len == max as usize {
+			len -= 1;
+
+			loop {
+				let n = self.byte()?;
+
+				len += n as usize;
+
+				if n < 255 {
+					len += 1;
+
+					break;
+				}
+				if len > u16::MAX as usize {
+					Err(Error::InvalidTrieNode("partial key too long"))?;
+				}
+			}
+		}
+
+		let bytes = self.take(len.div_ceil(2))?;
+		let mut nibbles = super::bytes_to_nibbles(bytes);
+
+		if len % 2 == 1 {
+			// The padding nibble must be zero.
+			if nibbles[0] != 0 {
+				Err(Error::InvalidTrieNode("invalid partial key padding"))?;
+			}
+
+			nibbles.remove(0);
+		}
+
+		Ok(nibbles)
+	}
+
+	// `hashed_value` is `None` for a branch without value.
+	fn branch(
+		&mut self,
+		header: u8,
+		prefix_bits: u8,
+		hashed_value: Option<bool>,
+	) -> Result<Node<'a>> {
+		let partial = self.partial(header, prefix_bits)?;
+		let bitmap = u16::from_le_bytes([self.byte()?, self.byte()?]);
+
+		if bitmap == 0 {
+			Err(Error::InvalidTrieNode("branch without children"))?;
+		}
+
+		let value = match hashed_value {
+			None => None,
+			Some(false) => Some(Value::Inline(self.compact_bytes()?)),
+			Some(true) => Some(Value::Hashed(self.hash()?)),
+		};
+		let mut children = Box::new([None; 16]);
+
+		for (i, c) in children.iter_mut().enumerate() {
+			if bitmap & (1 << i) == 0 {
+				continue;
+			}
+
+			let data = self.compact_bytes()?;
+
+			*c = Some(if data.len() == 32 {
+				Child::Hash(data.try_into().expect("the length has been checked; qed"))
+			} else if data.len() < 32 {
+				Child::Inline(data)
+			} else {
+				Err(Error::InvalidTrieNode("child reference too long"))?
+			});
+		}
+
+		Ok(Node::Branch { partial, value, children })
+	}
+}
+
+/// Encode a leaf node.
+pub(crate) fn encode_leaf(partial: &[u8], value: Value) -> Vec<u8> {
+	let mut encoded = match value {
+		Value::Inline(_) => encode_partial(LEAF_PREFIX_MASK, 2, partial),
+		Value::Hashed(_) => encode_partial(ALT_HASHING_LEAF_PREFIX_MASK, 3, partial),
+	};
+
+	encode_value(&mut encoded, value);
+
+	encoded
+}
+
+/// Encode a branch node.
+pub(crate) fn encode_branch(
+	partial: &[u8],
+	value: Option<Value>,
+	children: &[Option<Child>; 16],
+) -> Vec<u8> {
+	let mut encoded = match value {
+		None => encode_partial(BRANCH_WITHOUT_VALUE_MASK, 2, partial),
+		Some(Value::Inline(_)) => encode_partial(BRANCH_WITH_VALUE_MASK, 2, partial),
+		Some(Value::Hashed(_)) => encode_partial(ALT_HASHING_BRANCH_WITH_MASK, 4, partial),
+	};
+	let bitmap = children
+		.iter()
+		.enumerate()
+		.fold(0_u16, |b, (i, c)| if c.is_some() { b | 1 << i } else { b });
+
+	encoded.extend_from_slice(&bitmap.to_le_bytes());
+
+	if let Some(value) = value {
+		encode_value(&mut encoded, value);
+	}
+
+	for c in children.iter().flatten() {
+		match c {
+			Child::Hash(h) => encode_compact_bytes(&mut encoded, h),
+			Child::Inline(d) => encode_compact_bytes(&mut encoded, d),
+		}
+	}
+
+	encoded
+}
+
+// Encode the header with the nibble count, then the partial key.
+fn encode_partial(prefix: u8, prefix_bits: u8, partial: &[u8]) -> Vec<u8> {
+	let max = 255 >> prefix_bits;
+	let len = partial.len();
+	let mut encoded = Vec::with_capacity(len / 2 + 4);
+
+	if len < max as usize {
+		encoded.push(prefix | len as u8);
+	} else {
+		encoded.push(prefix | max);
+
+		let mut rem = len - (max as usize - 1);
+
+		while rem >= 256 {
+			encoded.push(255);
+			rem -= 255;
+		}
+
+		encoded.push((rem - 1) as u8);
+	}
+
+	if len % 2 == 1 {
+		encoded.push(partial[0]);
+	}
+
+	encoded.extend(partial[len % 2..].chunks(2).map(|n| n[0] << 4 | n[1]));
+
+	encoded
+}
+
+fn encode_value(encoded: &mut Vec<u8>, value: Value) {
+	match value {
+		Value::Inline(v) => encode_compact_bytes(encoded, v),
+		Value::Hashed(h) => encoded.extend_from_slice(&h),
+	}
+}
+
+fn encode_compact_bytes(encoded: &mut Vec<u8>, bytes: &[u8]) {
+	let len = bytes.len() as u32;
+
+	match len {
+		0..=0x3f => encoded.push((len as u8) << 2),
+		0x40..=0x3fff => encoded.extend_from_slice(&((len as u16) << 2 | 0b01).to_le_bytes()),
+		0x4000..=0x3fff_ffff => encoded.extend_from_slice(&(len << 2 | 0b10).to_le_bytes()),
+		_ => {
+			encoded.push(0b11);
+			encoded.extend_from_slice(&len.to_le_bytes());
+		},
+	}
+
+	encoded.extend_from_slice(bytes);
+}
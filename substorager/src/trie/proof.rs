@@ -0,0 +1,119 @@
+//! Read proof verification.
+
+// std
+use std::collections::HashMap;
+// substrate-minimal
+use crate::{
+	trie::{
+		bytes_to_nibbles,
+		node::{Child, Node, Value},
+		Hash,
+	},
+	ChildInfo, Error, Result,
+};
+
+/// Verify a read proof against the state root.
+///
+/// The proof is a set of encoded trie nodes, such as the `proof` field of the
+/// `state_getReadProof` RPC response.
+/// Return the values of the `keys` in the same order, [`None`] if the key is proven to be absent.
+///
+/// Substrate reference(s):
+/// - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/primitives/state-machine/src/lib.rs>
+pub fn verify_read_proof<N, K>(root: &Hash, proof: &[N], keys: &[K]) -> Result<Vec<Option<Vec<u8>>>>
+where
+	N: AsRef<[u8]>,
+	K: AsRef<[u8]>,
+{
+	let db = ProofDb::new(proof);
+
+	keys.iter().map(|k| db.lookup(root, k.as_ref())).collect()
+}
+
+/// Verify a child read proof against the state root.
+///
+/// The proof must contain the nodes on the path to the child trie root in the top trie, such as
+/// the `proof` field of the `state_getChildReadProof` RPC response.
+/// Return the values of the `keys` in the same order, [`None`] if the key is proven to be absent.
+pub fn verify_child_read_proof<N, K>(
+	root: &Hash,
+	proof: &[N],
+	child_info: &ChildInfo,
+	keys: &[K],
+) -> Result<Vec<Option<Vec<u8>>>>
+where
+	N: AsRef<[u8]>,
+	K: AsRef<[u8]>,
+{
+	let db = ProofDb::new(proof);
+	let Some(child_root) = db.lookup(root, &child_info.prefixed_storage_key())? else {
+		// The child trie doesn't exist, which is the same as an empty one.
+		return Ok(vec![None; keys.len()]);
+	};
+	let child_root = child_root.try_into().map_err(|_| Error::InvalidChildTrieRoot)?;
+
+	keys.iter().map(|k| db.lookup(&child_root, k.as_ref())).collect()
+}
+
+struct ProofDb<'a>(HashMap<Hash, &'a [u8]>);
+impl<'a> ProofDb<'a> {
+	fn new<N>(proof: &'a [N]) -> Self
+	where
+		N: AsRef<[u8]>,
+	{
+		Self(proof.iter().map(|n| (subhasher::blake2_256(n.as_ref()), n.as_ref())).collect())
+	}
+
+	fn get(&self, hash: &Hash) -> Result<&'a [u8]> {
+		self.0.get(hash).copied().ok_or(Error::IncompleteTrieProof(*hash))
+	}
+
+	fn lookup(&self, root: &Hash, key: &[u8]) -> Result<Option<Vec<u8>>> {
+		lookup(|h| self.get(h), root, key)
+	}
+}
+
+// Look up the value of the key, fetching the nodes and the hashed values through `get`.
+pub(super) fn lookup<'a, F>(mut get: F, root: &Hash, key: &[u8]) -> Result<Option<Vec<u8>>>
+where
+	F: FnMut(&Hash) -> Result<&'a [u8]>,
+{
+	let nibbles = bytes_to_nibbles(key);
+	let mut key = &nibbles[..];
+	let mut data = get(root)?;
+
+	loop {
+		match Node::decode(data)? {
+			Node::Empty => return Ok(None),
+			Node::Leaf { partial, value: v } =>
+				return if key == partial { value(&mut get, v).map(Some) } else { Ok(None) },
+			Node::Branch { partial, value: v, children } => {
+				let Some(rest) = key.strip_prefix(&partial[..]) else {
+					return Ok(None);
+				};
+				let Some((i, rest)) = rest.split_first() else {
+					return v.map(|v| value(&mut get, v)).transpose();
+				};
+				let Some(child) = children[*i as usize] else {
+					return Ok(None);
+				};
+
+				key = rest;
+				data = match child {
+					Child::Hash(h) => get(&h)?,
+					Child::Inline(d) => d,
+				};
+			},
+		}
+	}
+}
+
+fn value<'a, F>(get: &mut F, value: Value) -> Result<Vec<u8>>
+where
+	F: FnMut(&Hash) -> Result<&'a [u8]>,
+{
+	match value {
+		Value::Inline(v) => Ok(v.to_vec()),
+		Value::Hashed(h) => get(&h).map(<[u8]>::to_vec),
+	}
+}
@@ -0,0 +1,45 @@
+//! Well known keys of the Substrate storage.
+//!
+//! Substrate reference(s):
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/primitives/storage/src/lib.rs>
+//! - <https://github.com/paritytech/polkadot-sdk/blob/master/substrate/frame/support/src/traits/metadata.rs>
+
+// substrate-minimal
+use crate::StorageKey;
+
+/// Wasm code of the runtime.
+pub const CODE: &[u8] = b":code";
+
+/// Number of the wasm linear memory pages, which are required by the runtime.
+pub const HEAP_PAGES: &[u8] = b":heappages";
+
+/// Index of the current extrinsic in the block.
+pub const EXTRINSIC_INDEX: &[u8] = b":extrinsic_index";
+
+/// Entropy of the current block, which is available to the extrinsics.
+pub const INTRABLOCK_ENTROPY: &[u8] = b":intrablock_entropy";
+
+/// Prefix of all the child storage keys.
+pub const CHILD_STORAGE_KEY_PREFIX: &[u8] = b":child_storage:";
+
+/// Prefix of the default child storage keys.
+pub const DEFAULT_CHILD_STORAGE_KEY_PREFIX: &[u8] = b":child_storage:default:";
+
+/// Postfix of the pallet storage version key.
+pub const STORAGE_VERSION_STORAGE_KEY_POSTFIX: &[u8] = b":__STORAGE_VERSION__:";
+
+/// Check if the key is a child storage key.
+pub fn is_child_storage_key<K>(key: K) -> bool
+where
+	K: AsRef<[u8]>,
+{
+	key.as_ref().starts_with(CHILD_STORAGE_KEY_PREFIX)
+}
+
+/// Calculate the key of the pallet's on-chain storage version.
+pub fn storage_version_key<P>(pallet: P) -> StorageKey
+where
+	P: AsRef<[u8]>,
+{
+	crate::storage_value_key(pallet, STORAGE_VERSION_STORAGE_KEY_POSTFIX)
+}